@@ -0,0 +1,270 @@
+//! The `author` module matches commit authors against user-supplied patterns.
+//!
+//! A pattern is written as an optional kind prefix followed by a value:
+//!
+//! | Pattern               | Matches                                               |
+//! |-----------------------|-------------------------------------------------------|
+//! | `Name <email>`        | exactly `Name <email>`                                |
+//! | `substr:text`         | `Name <email>` containing `text`                      |
+//! | `icase:text`          | `Name <email>` containing `text`, ignoring case       |
+//! | `email:addr`          | an email address equal to `addr`, ignoring case       |
+//! | `regex:pattern`       | `Name <email>` matching the regular expression        |
+//!
+//! # Example
+//! ```rust
+//! use walrust::author::AuthorFilter;
+//! use walrust::commit::CommitAuthor;
+//!
+//! let filter = AuthorFilter::parse(["email:bix@ferrix.org", "icase:andor"]).unwrap();
+//! let cassian = CommitAuthor::new("Cassian Andor".to_string(), "cassian@ferrix.org".to_string());
+//! assert!(filter.matches(&cassian));
+//! ```
+
+use crate::commit::CommitAuthor;
+use crate::{Result, WalrustError};
+use regex::Regex;
+use std::fmt;
+use std::str::FromStr;
+
+/// A single author pattern.
+#[derive(Debug, Clone)]
+pub enum AuthorMatcher {
+    /// Matches `Name <email>` exactly.
+    Exact(String),
+    /// Matches when `Name <email>` contains the text.
+    Substring(String),
+    /// Matches when `Name <email>` contains the text, ignoring case. The text
+    /// is stored in lowercase.
+    CaseInsensitive(String),
+    /// Matches the email address, ignoring case. The address is stored in
+    /// lowercase.
+    Email(String),
+    /// Matches when the regular expression matches `Name <email>`.
+    Regex(Regex),
+}
+
+impl AuthorMatcher {
+    /// Tests an author against this pattern.
+    ///
+    /// # Arguments
+    /// - `author`: The author or committer to test.
+    ///
+    /// # Returns
+    /// `true` if the author matches.
+    pub fn matches(&self, author: &CommitAuthor) -> bool {
+        match self {
+            AuthorMatcher::Exact(text) => author.to_string() == *text,
+            AuthorMatcher::Substring(text) => author.to_string().contains(text.as_str()),
+            AuthorMatcher::CaseInsensitive(text) => {
+                author.to_string().to_lowercase().contains(text.as_str())
+            }
+            AuthorMatcher::Email(email) => author.email.to_lowercase() == *email,
+            AuthorMatcher::Regex(regex) => regex.is_match(&author.to_string()),
+        }
+    }
+}
+
+impl PartialEq for AuthorMatcher {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (AuthorMatcher::Exact(a), AuthorMatcher::Exact(b)) => a == b,
+            (AuthorMatcher::Substring(a), AuthorMatcher::Substring(b)) => a == b,
+            (AuthorMatcher::CaseInsensitive(a), AuthorMatcher::CaseInsensitive(b)) => a == b,
+            (AuthorMatcher::Email(a), AuthorMatcher::Email(b)) => a == b,
+            (AuthorMatcher::Regex(a), AuthorMatcher::Regex(b)) => a.as_str() == b.as_str(),
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for AuthorMatcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuthorMatcher::Exact(text) => write!(f, "{}", text),
+            AuthorMatcher::Substring(text) => write!(f, "substr:{}", text),
+            AuthorMatcher::CaseInsensitive(text) => write!(f, "icase:{}", text),
+            AuthorMatcher::Email(email) => write!(f, "email:{}", email),
+            AuthorMatcher::Regex(regex) => write!(f, "regex:{}", regex.as_str()),
+        }
+    }
+}
+
+impl FromStr for AuthorMatcher {
+    type Err = WalrustError;
+
+    /// Parses an author pattern.
+    ///
+    /// Values without a recognized `kind:` prefix are exact matches.
+    ///
+    /// # Errors
+    /// Returns a `ParseError` if the value is empty or a regular expression is
+    /// invalid.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let (kind, value) = match s.split_once(':') {
+            Some((kind @ ("substr" | "icase" | "email" | "regex"), value)) => (kind, value),
+            _ => ("exact", s),
+        };
+        if value.is_empty() {
+            return Err(WalrustError::ParseError(format!(
+                "empty {} author pattern '{}'",
+                kind, s
+            )));
+        }
+        let matcher = match kind {
+            "substr" => AuthorMatcher::Substring(value.to_string()),
+            "icase" => AuthorMatcher::CaseInsensitive(value.to_lowercase()),
+            "email" => AuthorMatcher::Email(value.to_lowercase()),
+            "regex" => Regex::new(value).map(AuthorMatcher::Regex).map_err(|err| {
+                WalrustError::ParseError(format!("invalid author regex '{}': {}", value, err))
+            })?,
+            _ => AuthorMatcher::Exact(value.to_string()),
+        };
+        Ok(matcher)
+    }
+}
+
+/// A set of author patterns, matching an author that matches any of them.
+///
+/// An empty filter matches every author.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AuthorFilter {
+    /// The patterns to try.
+    pub matchers: Vec<AuthorMatcher>,
+}
+
+impl AuthorFilter {
+    /// Creates a filter from parsed patterns.
+    pub fn new(matchers: Vec<AuthorMatcher>) -> Self {
+        AuthorFilter { matchers }
+    }
+
+    /// Creates a filter by parsing each pattern.
+    ///
+    /// # Errors
+    /// Returns a `ParseError` if any pattern is invalid.
+    pub fn parse<I, S>(patterns: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let matchers = patterns
+            .into_iter()
+            .map(|pattern| pattern.as_ref().parse())
+            .collect::<Result<Vec<_>>>()?;
+        Ok(AuthorFilter::new(matchers))
+    }
+
+    /// Returns `true` if the filter has no patterns and so matches everyone.
+    pub fn is_empty(&self) -> bool {
+        self.matchers.is_empty()
+    }
+
+    /// Tests an author against the filter.
+    ///
+    /// # Arguments
+    /// - `author`: The author or committer to test.
+    ///
+    /// # Returns
+    /// `true` if the filter is empty or any pattern matches.
+    pub fn matches(&self, author: &CommitAuthor) -> bool {
+        self.is_empty() || self.matchers.iter().any(|m| m.matches(author))
+    }
+}
+
+impl fmt::Display for AuthorFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let patterns: Vec<_> = self.matchers.iter().map(|m| m.to_string()).collect();
+        write!(f, "{}", patterns.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cassian() -> CommitAuthor {
+        CommitAuthor::new(
+            "Cassian Andor".to_string(),
+            "Cassian@Ferrix.org".to_string(),
+        )
+    }
+
+    #[test]
+    fn test_exact() {
+        let matcher: AuthorMatcher = "Cassian Andor <Cassian@Ferrix.org>".parse().unwrap();
+        assert!(matcher.matches(&cassian()));
+        let matcher: AuthorMatcher = "Cassian Andor".parse().unwrap();
+        assert!(!matcher.matches(&cassian()));
+    }
+
+    #[test]
+    fn test_substring() {
+        let matcher: AuthorMatcher = "substr:Andor".parse().unwrap();
+        assert!(matcher.matches(&cassian()));
+        let matcher: AuthorMatcher = "substr:andor".parse().unwrap();
+        assert!(!matcher.matches(&cassian()));
+    }
+
+    #[test]
+    fn test_case_insensitive() {
+        let matcher: AuthorMatcher = "icase:ANDOR <cassian@".parse().unwrap();
+        assert!(matcher.matches(&cassian()));
+    }
+
+    #[test]
+    fn test_email() {
+        let matcher: AuthorMatcher = "email:cassian@ferrix.org".parse().unwrap();
+        assert!(matcher.matches(&cassian()));
+        let matcher: AuthorMatcher = "email:cassian@ferrix".parse().unwrap();
+        assert!(!matcher.matches(&cassian()));
+    }
+
+    #[test]
+    fn test_regex() {
+        let matcher: AuthorMatcher = r"regex:^Cassian .*@ferrix\.org>$".parse().unwrap();
+        assert!(!matcher.matches(&cassian()));
+        let matcher: AuthorMatcher = r"regex:(?i)^Cassian .*@ferrix\.org>$".parse().unwrap();
+        assert!(matcher.matches(&cassian()));
+    }
+
+    #[test]
+    fn test_invalid_patterns() {
+        assert!("regex:(".parse::<AuthorMatcher>().is_err());
+        assert!("email:".parse::<AuthorMatcher>().is_err());
+        assert!("".parse::<AuthorMatcher>().is_err());
+    }
+
+    #[test]
+    fn test_unknown_prefix_is_exact() {
+        let matcher: AuthorMatcher = "team:Cassian".parse().unwrap();
+        assert_eq!(matcher, AuthorMatcher::Exact("team:Cassian".to_string()));
+    }
+
+    #[test]
+    fn test_eq_compares_variants() {
+        assert_ne!(
+            AuthorMatcher::Exact("substr:Andor".to_string()),
+            AuthorMatcher::Substring("Andor".to_string())
+        );
+        assert_ne!(
+            AuthorMatcher::Exact("email:cassian@ferrix.org".to_string()),
+            AuthorMatcher::Email("cassian@ferrix.org".to_string())
+        );
+        let regex: AuthorMatcher = "regex:Andor".parse().unwrap();
+        assert_eq!(regex, "regex:Andor".parse().unwrap());
+        assert_ne!(regex, AuthorMatcher::Exact("regex:Andor".to_string()));
+    }
+
+    #[test]
+    fn test_filter_any_of() {
+        let filter = AuthorFilter::parse(["email:bix@ferrix.org", "substr:Cassian"]).unwrap();
+        assert!(filter.matches(&cassian()));
+        let filter = AuthorFilter::parse(["email:bix@ferrix.org"]).unwrap();
+        assert!(!filter.matches(&cassian()));
+    }
+
+    #[test]
+    fn test_empty_filter_matches_everyone() {
+        assert!(AuthorFilter::default().matches(&cassian()));
+    }
+}
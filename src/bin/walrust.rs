@@ -1,11 +1,58 @@
 use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, ParseError, TimeZone};
-use clap::Parser;
-use log;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::io::{IsTerminal, Write};
 use std::path::PathBuf;
 use std::process;
-use walrust::commit::Commit;
-use walrust::repository::GitRepository;
-use walrust::repository_locator::GitRepositoryLocator;
+use walrust::author::AuthorFilter;
+use walrust::changelog::{Changelog, ChangelogGrouping};
+use walrust::commit::{Commit, DateField, DiffStats, DisplayTimezone};
+use walrust::config::ConfigFile;
+use walrust::filter::CommitFilter;
+use walrust::glob::Glob;
+use walrust::grep::{self, Grep, GrepScope};
+use walrust::pathspec::Pathspec;
+use walrust::query::{CommitQuery, MergeMode, RefSelection, RevisionRange};
+use walrust::report::{self, Column, Delimiter, ReportOptions, RepositoryInfo};
+use walrust::repository::{GitRepository, Repository};
+use walrust::repository_locator::{read_repository_list, DiscoveryFilter, GitRepositoryLocator};
+use walrust::scanner::scan_repositories;
+use walrust::summary::{group_commits, split_non_conforming, summarize, GroupBy, Summary};
+use walrust::template::Template;
+use walrust::WalrustError;
+
+/// The output formats supported by the `walrust` CLI tool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// One `hash date title` line per commit.
+    Text,
+    /// A JSON array with one object per repository.
+    #[cfg(feature = "json")]
+    Json,
+    /// Newline-delimited JSON with one object per commit.
+    #[cfg(feature = "json")]
+    Ndjson,
+    /// Comma-separated values with a header row.
+    Csv,
+    /// Tab-separated values with a header row.
+    Tsv,
+}
+
+/// When to highlight matches in text output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ColorChoice {
+    /// Highlight when writing to a terminal.
+    Auto,
+    /// Always highlight.
+    Always,
+    /// Never highlight.
+    Never,
+}
+
+/// The ANSI escape sequence that starts a highlighted match.
+const HIGHLIGHT_START: &str = "\x1b[1;31m";
+
+/// The ANSI escape sequence that ends a highlighted match.
+const HIGHLIGHT_END: &str = "\x1b[0m";
 
 /// The configuration for the `walrust` CLI tool.
 ///
@@ -13,58 +60,466 @@ use walrust::repository_locator::GitRepositoryLocator;
 /// including the search root, search depth, date filters, and author filters.
 ///
 /// # Fields
-/// - `search_root`: The root directory to start searching for repositories.
-/// - `search_depth`: The maximum recursion depth for directory scanning.
+/// - `discovery`: The options selecting the repositories to query.
 /// - `since`: The starting date to filter commits (inclusive).
 /// - `until`: The ending date to filter commits (inclusive).
-/// - `author`: The author name to filter commits by.
+/// - `author`: The author patterns to filter commits by.
+/// - `branches`: Walk all local branches instead of only HEAD.
+/// - `all`: Walk all refs instead of only HEAD.
+/// - `glob`: Walk refs matching the given glob patterns instead of only HEAD.
+/// - `range`: Walk a revision range instead of only HEAD.
+/// - `no_merges`: Skip merge commits.
+/// - `merges_only`: Report only merge commits.
+/// - `first_parent`: Follow only the first parent of merges.
+/// - `date_field`: Whether the date filters apply to the author or committer date.
+/// - `timezone`: The timezone commit dates are displayed in.
+/// - `grep`: The patterns to search commit messages for.
+/// - `regexp_ignore_case`: Match `grep` patterns regardless of case.
+/// - `grep_scope`: Whether `grep` searches the title or the full message.
+/// - `invert_grep`: Keep commits that do not match `grep` instead.
+/// - `color`: When to highlight `grep` matches in text output.
+/// - `types`: The Conventional Commits types to keep.
+/// - `use_mailmap`: Resolve authors and committers through `.mailmap` files.
+/// - `mailmap_file`: A mailmap file applied to every repository.
+/// - `jobs`: The number of repositories to query concurrently.
+/// - `format`: The output format.
+/// - `columns`: The columns written by the CSV and TSV formats.
+/// - `template`: The template, or name of a configured template, for text output.
+/// - `group_by`: How to group commits in text output.
+/// - `summary`: Append per-repository and per-author counts to text output.
+/// - `stat`: Compute the lines each commit adds and removes.
+/// - `pathspecs`: Git pathspecs of which commits must change a matching path.
+/// - `profile`: The config file profile whose settings apply.
+/// - `command`: A subcommand to run instead of listing commits.
 ///
 /// # Example
 /// ```bash
 /// walrust -r /path/to/search -d 3 -a "John Doe <john.doe@example.com>"
 /// ```
 #[derive(Debug, Parser, PartialEq)]
-#[command(version, about, long_about = None)]
+#[command(
+    version,
+    about,
+    long_about = None,
+    args_conflicts_with_subcommands = true
+)]
 pub struct Config {
-    /// The root directory to start searching for repositories.
+    /// The options selecting the repositories to query.
+    #[command(flatten)]
+    pub discovery: DiscoveryArgs,
+
+    /// The starting date to filter commits (inclusive).
+    #[arg(
+        short = 'a',
+        long,
+        value_name = "SINCE",
+        value_hint = clap::ValueHint::Other,
+        help = "Filters commits since this date, inclusive. Defaults to yesterday's date."
+    )]
+    #[arg(
+        short = 's',
+        long,
+        value_name = "SINCE",
+        value_hint = clap::ValueHint::Other,
+        value_parser = parse_datetime,
+        help = "Filters commits since this date, inclusive. Defaults to yesterday's date."
+    )]
+    pub since: Option<DateTime<Local>>,
+
+    /// The ending date to filter commits (inclusive).
+    #[arg(
+        short = 'u',
+        long,
+        value_name = "UNTIL",
+        value_hint = clap::ValueHint::Other,
+        value_parser = parse_datetime,
+        help = "Filters commits until this date, inclusive."
+    )]
+    pub until: Option<DateTime<Local>>,
+
+    /// The author patterns to filter commits by.
+    #[arg(
+        short = 'a',
+        long,
+        value_name = "AUTHOR",
+        value_hint = clap::ValueHint::Other,
+        help = "Filters commits by author: 'Name <email>', or prefixed with 'substr:', 'icase:', 'email:' or 'regex:'. May be repeated."
+    )]
+    pub author: Vec<String>,
+
+    /// Walk all local branches instead of only HEAD.
+    #[arg(
+        long,
+        conflicts_with_all = ["all", "glob"],
+        help = "Walks all local branches instead of only HEAD"
+    )]
+    pub branches: bool,
+
+    /// Walk all refs instead of only HEAD.
+    #[arg(
+        long,
+        conflicts_with = "glob",
+        help = "Walks all refs, including remote-tracking branches and tags"
+    )]
+    pub all: bool,
+
+    /// Walk refs matching the given glob patterns instead of only HEAD.
+    #[arg(
+        long,
+        value_name = "PATTERN",
+        value_hint = clap::ValueHint::Other,
+        help = "Walks refs matching the glob pattern, e.g. 'refs/heads/feature/*'. May be repeated."
+    )]
+    pub glob: Vec<String>,
+
+    /// The revision range to walk instead of HEAD.
+    #[arg(
+        long,
+        value_name = "RANGE",
+        value_hint = clap::ValueHint::Other,
+        conflicts_with_all = ["branches", "all", "glob"],
+        help = "Walks a revision range such as 'v1.2.0..HEAD' or 'main...feature', resolved in each repository. Without --since, includes the whole range."
+    )]
+    pub range: Option<RevisionRange>,
+
+    /// Skip merge commits.
+    #[arg(long, conflicts_with = "merges_only", help = "Skips merge commits")]
+    pub no_merges: bool,
+
+    /// Report only merge commits.
+    #[arg(long, help = "Reports only merge commits")]
+    pub merges_only: bool,
+
+    /// Follow only the first parent of merges.
+    #[arg(
+        long,
+        help = "Follows only the first parent of merges, reporting the mainline history"
+    )]
+    pub first_parent: bool,
+
+    /// Whether the date filters apply to the author or committer date.
+    #[arg(
+        long,
+        value_name = "FIELD",
+        default_value_t = DateField::Committer,
+        help = "Filters and displays commits by their 'author' or 'committer' date"
+    )]
+    pub date_field: DateField,
+
+    /// The timezone commit dates are displayed in.
+    #[arg(
+        long,
+        value_name = "ZONE",
+        default_value_t = DisplayTimezone::Original,
+        help = "Displays dates in the commit's 'original' timezone, 'local' time or 'utc'"
+    )]
+    pub timezone: DisplayTimezone,
+
+    /// The patterns to search commit messages for.
+    #[arg(
+        long,
+        value_name = "PATTERN",
+        value_hint = clap::ValueHint::Other,
+        help = "Keeps commits whose message matches the regular expression. May be repeated to match any of several."
+    )]
+    pub grep: Vec<String>,
+
+    /// Match `grep` patterns regardless of case.
+    #[arg(short = 'i', long, help = "Matches --grep patterns regardless of case")]
+    pub regexp_ignore_case: bool,
+
+    /// Whether `grep` searches the title or the full message.
+    #[arg(
+        long,
+        value_name = "SCOPE",
+        default_value_t = GrepScope::Message,
+        help = "Searches the commit 'title' or the full 'message' with --grep"
+    )]
+    pub grep_scope: GrepScope,
+
+    /// Keep commits that do not match `grep` instead.
+    #[arg(
+        long,
+        requires = "grep",
+        help = "Keeps commits whose message does not match any --grep pattern"
+    )]
+    pub invert_grep: bool,
+
+    /// When to highlight `grep` matches in text output.
+    #[arg(
+        long,
+        value_enum,
+        value_name = "WHEN",
+        default_value_t = ColorChoice::Auto,
+        help = "Highlights --grep matches in text output: 'auto', 'always' or 'never'"
+    )]
+    pub color: ColorChoice,
+
+    /// The Conventional Commits types to keep.
+    #[arg(
+        long = "type",
+        value_name = "TYPE",
+        value_delimiter = ',',
+        help = "Keeps conventional commits of the comma-separated types, e.g. 'feat,fix'. Non-conforming commits are reported on stderr."
+    )]
+    pub types: Vec<String>,
+
+    /// Resolve authors and committers through `.mailmap` files.
+    #[arg(
+        long,
+        help = "Resolves authors and committers through each repository's .mailmap"
+    )]
+    pub use_mailmap: bool,
+
+    /// A mailmap file applied to every repository.
+    #[arg(
+        long,
+        value_name = "FILE",
+        value_hint = clap::ValueHint::FilePath,
+        help = "Applies a mailmap file to every repository, overriding their own entries. Implies --use-mailmap."
+    )]
+    pub mailmap_file: Option<PathBuf>,
+
+    /// The number of repositories to query concurrently.
+    #[arg(
+        short = 'j',
+        long,
+        default_value_t = 0,
+        value_name = "N",
+        value_hint = clap::ValueHint::Other,
+        help = "Sets the number of repositories to query concurrently. Defaults to the number of CPUs."
+    )]
+    pub jobs: usize,
+
+    /// The output format.
+    #[arg(
+        long,
+        value_enum,
+        value_name = "FORMAT",
+        help = "Sets the output format. Defaults to the format in the config file, or text."
+    )]
+    pub format: Option<OutputFormat>,
+
+    /// The columns written by the CSV and TSV formats.
+    #[arg(
+        long,
+        value_name = "COLUMNS",
+        value_delimiter = ',',
+        value_hint = clap::ValueHint::Other,
+        help = "Sets the CSV/TSV columns, e.g. 'repo,hash,author,date,title'. Defaults to those five."
+    )]
+    pub columns: Vec<Column>,
+
+    /// The template, or name of a configured template, for text output.
+    #[arg(
+        long,
+        value_name = "TEMPLATE",
+        value_hint = clap::ValueHint::Other,
+        help = "Formats text output with a template, e.g. '{short_hash} {author_name} {title}', or the name of a template in the config file"
+    )]
+    pub template: Option<String>,
+
+    /// How to group commits in text output.
+    #[arg(
+        long,
+        value_name = "GROUP",
+        help = "Groups text output under 'repo', 'author', 'day', 'week' or 'type' headers"
+    )]
+    pub group_by: Option<GroupBy>,
+
+    /// Append per-repository and per-author counts to text output.
+    #[arg(long, help = "Appends a table of commit counts to text output")]
+    pub summary: bool,
+
+    /// Compute the lines each commit adds and removes.
+    #[arg(
+        long,
+        help = "Shows the files each commit changes with their added and removed lines. Slower on large histories."
+    )]
+    pub stat: bool,
+
+    /// Git pathspecs of which commits must change a matching path.
+    #[arg(
+        last = true,
+        value_name = "PATHSPEC",
+        value_hint = clap::ValueHint::AnyPath,
+        help = "Keeps commits changing a path matching any pathspec, e.g. 'deploy/' or '*.toml'. Merges are compared against their first parent."
+    )]
+    pub pathspecs: Vec<String>,
+
+    /// The config file profile whose settings apply.
+    #[arg(
+        long,
+        global = true,
+        value_name = "NAME",
+        value_hint = clap::ValueHint::Other,
+        help = "Applies the settings of a profile from the config file"
+    )]
+    pub profile: Option<String>,
+
+    /// A subcommand to run instead of listing commits.
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+/// The options selecting the repositories to query, shared by the commands.
+///
+/// # Fields
+/// - `search_root`: The root directories to start searching for repositories.
+/// - `repo_list`: A file listing repository paths to query instead of searching.
+/// - `search_depth`: The maximum recursion depth for directory scanning.
+/// - `include`: Patterns of the repositories to report.
+/// - `exclude`: Patterns of the directories not to search.
+/// - `no_default_excludes`: Search the directories excluded by default.
+/// - `nested`: Keep searching inside the repositories found.
+/// - `submodules`: Report the submodules of the repositories found.
+/// - `follow_symlinks`: Search directories reached through symbolic links.
+#[derive(Debug, Args, Default, PartialEq)]
+pub struct DiscoveryArgs {
+    /// The root directories to start searching for repositories.
     #[arg(
         short = 'r',
         long,
-        default_value = ".",
         value_hint = clap::ValueHint::DirPath,
-        help = "Sets the root directory to search",
-        required = true
+        help = "Sets a root directory to search. May be repeated. Defaults to the roots in the config file, or the current directory."
     )]
-    pub search_root: PathBuf,
+    pub search_root: Vec<PathBuf>,
+
+    /// A file listing repository paths to query instead of searching.
+    #[arg(
+        long,
+        value_name = "FILE",
+        value_hint = clap::ValueHint::FilePath,
+        conflicts_with_all = ["search_root", "search_depth", "include", "exclude", "no_default_excludes", "nested", "follow_symlinks"],
+        help = "Queries the repositories listed in a file, one path per line, instead of searching for them"
+    )]
+    pub repo_list: Option<PathBuf>,
 
     /// The maximum recursion depth for directory scanning relative to the
     /// starting directory.
     #[arg(
         short = 'd',
         long,
-        default_value_t = 5,
         value_name = "DEPTH",
         value_hint = clap::ValueHint::Other,
-        help = "Sets the depth of the search",
-        required = true
+        help = "Sets the depth of the search. Defaults to the depth in the config file, or 5."
     )]
-    pub search_depth: usize,
+    pub search_depth: Option<usize>,
 
-    /// The starting date to filter commits (inclusive).
+    /// Patterns of the repositories to report.
     #[arg(
-        short = 'a',
         long,
-        value_name = "SINCE",
+        value_name = "GLOB",
         value_hint = clap::ValueHint::Other,
-        help = "Filters commits since this date, inclusive. Defaults to yesterday's date."
+        help = "Only reports repositories whose path below the search root matches the gitignore-style pattern, e.g. 'work/*'. May be repeated."
+    )]
+    pub include: Vec<String>,
+
+    /// Patterns of the directories not to search.
+    #[arg(
+        long,
+        value_name = "GLOB",
+        value_hint = clap::ValueHint::Other,
+        help = "Skips directories matching the gitignore-style pattern, e.g. 'archive' or '/old/**'. May be repeated. Defaults to the excludes in the config file."
+    )]
+    pub exclude: Vec<String>,
+
+    /// Search the directories excluded by default.
+    #[arg(
+        long,
+        help = "Searches node_modules, target, vendor, .venv and __pycache__ directories, which are skipped by default"
+    )]
+    pub no_default_excludes: bool,
+
+    /// Keep searching inside the repositories found.
+    #[arg(
+        long,
+        help = "Keeps searching inside the repositories found for repositories nested in them, up to the search depth"
+    )]
+    pub nested: bool,
+
+    /// Report the submodules of the repositories found.
+    #[arg(
+        long,
+        help = "Also queries the checked-out submodules of every repository, named after their parent"
+    )]
+    pub submodules: bool,
+
+    /// Search directories reached through symbolic links.
+    #[arg(
+        short = 'L',
+        long,
+        help = "Follows symbolic links to directories while searching, skipping links that loop back"
+    )]
+    pub follow_symlinks: bool,
+}
+
+/// The subcommands of the `walrust` CLI tool.
+#[derive(Debug, Subcommand, PartialEq)]
+pub enum Command {
+    /// Writes a Markdown changelog of a revision range in every repository.
+    Changelog(Box<ChangelogArgs>),
+    /// Inspects the configuration.
+    #[command(subcommand)]
+    Config(ConfigCommand),
+}
+
+/// The subcommands of `walrust config`.
+#[derive(Debug, Subcommand, PartialEq, Eq)]
+pub enum ConfigCommand {
+    /// Prints the effective settings and the config files they come from.
+    Show,
+}
+
+/// The arguments of the `changelog` subcommand.
+///
+/// # Fields
+/// - `discovery`: The options selecting the repositories to query.
+/// - `from`: The revision the changelog starts after, such as the previous tag.
+/// - `to`: The revision the changelog ends at.
+/// - `since`: The starting date to filter commits (inclusive).
+/// - `until`: The ending date to filter commits (inclusive).
+/// - `group_by`: Whether sections follow commit types or configured title prefixes.
+/// - `output`: The file to write the changelog to instead of standard output.
+/// - `jobs`: The number of repositories to query concurrently.
+///
+/// # Example
+/// ```bash
+/// walrust changelog -r /path/to/search --from v1.2.0 --to v1.3.0 -o CHANGELOG.md
+/// ```
+#[derive(Debug, Args, PartialEq)]
+pub struct ChangelogArgs {
+    /// The options selecting the repositories to query.
+    #[command(flatten)]
+    pub discovery: DiscoveryArgs,
+
+    /// The revision the changelog starts after.
+    #[arg(
+        long,
+        value_name = "REV",
+        value_hint = clap::ValueHint::Other,
+        help = "Leaves out commits reachable from this revision, e.g. the previous release tag"
+    )]
+    pub from: Option<String>,
+
+    /// The revision the changelog ends at.
+    #[arg(
+        long,
+        default_value = "HEAD",
+        value_name = "REV",
+        value_hint = clap::ValueHint::Other,
+        help = "Lists commits reachable from this revision, e.g. the new release tag"
     )]
+    pub to: String,
+
+    /// The starting date to filter commits (inclusive).
     #[arg(
         short = 's',
         long,
         value_name = "SINCE",
         value_hint = clap::ValueHint::Other,
         value_parser = parse_datetime,
-        help = "Filters commits since this date, inclusive. Defaults to yesterday's date."
+        help = "Filters commits since this date, inclusive. Defaults to the first commit."
     )]
     pub since: Option<DateTime<Local>>,
 
@@ -79,15 +534,347 @@ pub struct Config {
     )]
     pub until: Option<DateTime<Local>>,
 
-    /// The author name to filter commits by.
+    /// Whether sections follow commit types or configured title prefixes.
     #[arg(
-        short = 'a',
         long,
-        value_name = "AUTHOR",
+        value_name = "GROUP",
+        default_value_t = ChangelogGrouping::Type,
+        help = "Groups entries by Conventional Commits 'type' or by the title 'prefix' headings in the config file"
+    )]
+    pub group_by: ChangelogGrouping,
+
+    /// The file to write the changelog to.
+    #[arg(
+        short = 'o',
+        long,
+        value_name = "FILE",
+        value_hint = clap::ValueHint::FilePath,
+        help = "Writes the changelog to this file instead of standard output"
+    )]
+    pub output: Option<PathBuf>,
+
+    /// The number of repositories to query concurrently.
+    #[arg(
+        short = 'j',
+        long,
+        default_value_t = 0,
+        value_name = "N",
         value_hint = clap::ValueHint::Other,
-        help = "Filters commits by author in 'Name <email>' format"
+        help = "Sets the number of repositories to query concurrently. Defaults to the number of CPUs."
     )]
-    pub author: Option<String>,
+    pub jobs: usize,
+}
+
+impl ChangelogArgs {
+    /// Returns the revision range the changelog covers.
+    pub fn range(&self) -> RevisionRange {
+        RevisionRange::new(self.from.clone(), self.to.clone())
+    }
+
+    /// Returns the top-level heading of the changelog, naming the range and
+    /// any date filters.
+    pub fn title(&self) -> String {
+        let mut title = format!("Changelog {}", self.range());
+        if let Some(since) = self.since {
+            title.push_str(&format!(" since {}", since.format("%Y-%m-%d")));
+        }
+        if let Some(until) = self.until {
+            title.push_str(&format!(" until {}", until.format("%Y-%m-%d")));
+        }
+        title
+    }
+}
+
+/// The search root used when neither the command line nor the config file
+/// names one.
+const DEFAULT_SEARCH_ROOT: &str = ".";
+
+/// The search depth used when neither the command line nor the config file
+/// gives one.
+const DEFAULT_SEARCH_DEPTH: usize = 5;
+
+/// The settings of a run, combined from the command line, the selected profile,
+/// the config files and the built-in defaults, in that order of precedence.
+///
+/// # Fields
+/// - `profile`: The name of the selected profile, if any.
+/// - `roots`: The directories to search for repositories.
+/// - `repo_list`: A file listing the repositories to query instead of searching.
+/// - `depth`: The maximum recursion depth for directory scanning.
+/// - `filter`: The include and exclude patterns of the directories searched.
+/// - `nested`: Whether to keep searching inside the repositories found.
+/// - `submodules`: Whether to report the submodules of the repositories found.
+/// - `follow_symlinks`: Whether to search directories reached through symbolic links.
+/// - `identities`: The author patterns used when `--author` is not given.
+/// - `format`: The output format.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settings {
+    pub profile: Option<String>,
+    pub roots: Vec<PathBuf>,
+    pub repo_list: Option<PathBuf>,
+    pub depth: usize,
+    pub filter: DiscoveryFilter,
+    pub nested: bool,
+    pub submodules: bool,
+    pub follow_symlinks: bool,
+    pub identities: Vec<String>,
+    pub format: OutputFormat,
+}
+
+impl Settings {
+    /// Resolves the settings of a run.
+    ///
+    /// # Arguments
+    /// - `file`: The configuration loaded from the config files.
+    /// - `profile`: The name of the profile to apply, if any.
+    /// - `discovery`: The repository selection given on the command line.
+    /// - `format`: The output format given on the command line, if any.
+    ///
+    /// # Errors
+    /// Returns an error if the profile does not exist, an include or exclude
+    /// pattern is invalid, or the configured format is not a known output
+    /// format.
+    fn resolve(
+        file: &ConfigFile,
+        profile: Option<&str>,
+        discovery: &DiscoveryArgs,
+        format: Option<OutputFormat>,
+    ) -> walrust::Result<Self> {
+        let configured = file.profile(profile)?;
+        let roots = match (
+            discovery.search_root.is_empty(),
+            configured.roots.is_empty(),
+        ) {
+            (false, _) => discovery.search_root.clone(),
+            (true, false) => configured.roots,
+            (true, true) => vec![PathBuf::from(DEFAULT_SEARCH_ROOT)],
+        };
+        let format = match (format, &configured.format) {
+            (Some(format), _) => format,
+            (None, Some(name)) => OutputFormat::from_str(name, true).map_err(|_| {
+                WalrustError::ParseError(format!(
+                    "unknown format '{}' in the config file, expected one of: {}",
+                    name,
+                    OutputFormat::value_variants()
+                        .iter()
+                        .filter_map(|v| v.to_possible_value())
+                        .map(|v| v.get_name().to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ))
+            })?,
+            (None, None) => OutputFormat::Text,
+        };
+        let pick = |cli: &Vec<String>, configured: &Vec<String>| match cli.is_empty() {
+            true => configured.clone(),
+            false => cli.clone(),
+        };
+        let mut filter = DiscoveryFilter::new(
+            pick(&discovery.include, &configured.include),
+            pick(&discovery.exclude, &configured.exclude),
+        )?;
+        if !discovery.no_default_excludes && configured.default_excludes != Some(false) {
+            filter = filter.with_default_excludes();
+        }
+        Ok(Settings {
+            profile: profile.map(str::to_string),
+            roots,
+            repo_list: discovery.repo_list.clone(),
+            depth: discovery
+                .search_depth
+                .or(configured.depth)
+                .unwrap_or(DEFAULT_SEARCH_DEPTH),
+            filter,
+            nested: discovery.nested || configured.nested.unwrap_or(false),
+            submodules: discovery.submodules || configured.submodules.unwrap_or(false),
+            follow_symlinks: discovery.follow_symlinks
+                || configured.follow_symlinks.unwrap_or(false),
+            identities: configured.identities,
+            format,
+        })
+    }
+}
+
+impl Config {
+    /// Returns the reference selection mode requested on the command line.
+    ///
+    /// # Returns
+    /// The `RefSelection` matching the `--branches`, `--all` and `--glob` flags,
+    /// or `RefSelection::Head` if none were given.
+    fn ref_selection(&self) -> RefSelection {
+        if self.branches {
+            RefSelection::LocalBranches
+        } else if self.all {
+            RefSelection::AllRefs
+        } else if !self.glob.is_empty() {
+            RefSelection::Globs(self.glob.clone())
+        } else {
+            RefSelection::Head
+        }
+    }
+
+    /// Returns the merge handling mode requested on the command line.
+    ///
+    /// # Returns
+    /// The `MergeMode` matching the `--no-merges` and `--merges-only` flags,
+    /// or `MergeMode::Include` if neither was given.
+    fn merge_mode(&self) -> MergeMode {
+        if self.no_merges {
+            MergeMode::Exclude
+        } else if self.merges_only {
+            MergeMode::Only
+        } else {
+            MergeMode::Include
+        }
+    }
+
+    /// Resolves the `--template` option.
+    ///
+    /// A value containing a `{` is parsed as a template; anything else is
+    /// looked up by name in the config file.
+    ///
+    /// # Returns
+    /// The parsed template, or `None` if `--template` was not given.
+    ///
+    /// # Errors
+    /// Returns an error if the named template does not exist or the template
+    /// is invalid.
+    fn template(&self, file: &ConfigFile) -> walrust::Result<Option<Template>> {
+        let Some(value) = &self.template else {
+            return Ok(None);
+        };
+        let template = match value.contains('{') {
+            true => value.parse()?,
+            false => file.template(value)?.parse()?,
+        };
+        Ok(Some(template))
+    }
+
+    /// Checks that the options only text output supports are not combined
+    /// with another output format.
+    ///
+    /// # Arguments
+    /// - `format`: The resolved output format.
+    ///
+    /// # Errors
+    /// Returns a `ParseError` if `--group-by` or `--summary` is given with a
+    /// format other than text.
+    fn check_format(&self, format: OutputFormat) -> walrust::Result<()> {
+        let text_only = [
+            ("--group-by", self.group_by.is_some()),
+            ("--summary", self.summary),
+        ];
+        match text_only.iter().find(|(_, given)| *given) {
+            Some((option, _)) if format != OutputFormat::Text => {
+                let name = format
+                    .to_possible_value()
+                    .map(|v| v.get_name().to_string())
+                    .unwrap_or_default();
+                Err(WalrustError::ParseError(format!(
+                    "{} only applies to text output, not '{}'",
+                    option, name
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Parses the `--grep` patterns.
+    ///
+    /// # Errors
+    /// Returns an error if any pattern is not a valid regular expression.
+    fn greps(&self) -> walrust::Result<Vec<Grep>> {
+        self.grep
+            .iter()
+            .map(|pattern| Grep::new(pattern, self.regexp_ignore_case, self.grep_scope))
+            .collect()
+    }
+
+    /// Compiles the trailing pathspecs.
+    ///
+    /// # Errors
+    /// Returns an error if any pathspec is invalid.
+    fn pathspec(&self) -> walrust::Result<Pathspec> {
+        Pathspec::new(self.pathspecs.iter().cloned())
+    }
+
+    /// Parses the `--type` values into a filter, see [`CommitFilter::types`].
+    ///
+    /// # Returns
+    /// The filter, or `None` if no types are given.
+    ///
+    /// # Errors
+    /// Returns an error if any type is not a valid Conventional Commits type.
+    fn type_filter(&self) -> walrust::Result<Option<CommitFilter>> {
+        match self.types.is_empty() {
+            true => Ok(None),
+            false => CommitFilter::types(&self.types).map(Some),
+        }
+    }
+
+    /// Builds the filter commits must match from the command-line options.
+    ///
+    /// # Arguments
+    /// - `authors`: The resolved author filter.
+    /// - `greps`: The parsed `--grep` patterns.
+    /// - `types`: The parsed `--type` filter, if any.
+    ///
+    /// # Returns
+    /// The combined filter, or `None` if every commit in the date range matches.
+    fn commit_filter(
+        &self,
+        authors: AuthorFilter,
+        greps: &[Grep],
+        types: Option<CommitFilter>,
+    ) -> Option<CommitFilter> {
+        let mut filters = Vec::new();
+        if !authors.is_empty() {
+            filters.push(CommitFilter::Author(authors));
+        }
+        if !greps.is_empty() {
+            let grep = CommitFilter::Or(greps.iter().cloned().map(CommitFilter::Message).collect());
+            filters.push(match self.invert_grep {
+                true => !grep,
+                false => grep,
+            });
+        }
+        if let Some(types) = types {
+            filters.push(types);
+        }
+        match filters.len() {
+            0 => None,
+            1 => filters.pop(),
+            _ => Some(CommitFilter::And(filters)),
+        }
+    }
+
+    /// Resolves the settings combining these flags with the config file.
+    ///
+    /// # Errors
+    /// Returns an error if the `--profile` does not exist or the configured
+    /// patterns or format are invalid.
+    fn settings(&self, file: &ConfigFile) -> walrust::Result<Settings> {
+        Settings::resolve(file, self.profile.as_deref(), &self.discovery, self.format)
+    }
+
+    /// Resolves the author filter.
+    ///
+    /// The `--author` patterns are used if given, where empty patterns match
+    /// everyone. Otherwise the configured identities are used, falling back to
+    /// the user configured in Git.
+    ///
+    /// # Errors
+    /// Returns an error if any pattern is invalid.
+    fn author_filter(&self, settings: &Settings) -> walrust::Result<AuthorFilter> {
+        if !self.author.is_empty() {
+            return AuthorFilter::parse(self.author.iter().filter(|a| !a.is_empty()));
+        }
+        let identities = AuthorFilter::parse(&settings.identities)?;
+        if !identities.is_empty() {
+            return Ok(identities);
+        }
+        AuthorFilter::parse(get_local_git_default_author())
+    }
 }
 
 /// Parses a string into a `chrono::DateTime<Local>` object.
@@ -160,6 +947,7 @@ fn get_local_git_default_author() -> Option<String> {
 ///
 /// # Arguments
 /// - `config`: The configuration containing the search root, depth, date filters, and author filter.
+/// - `file`: The configuration file supplying defaults, profiles and named templates.
 ///
 /// # Returns
 /// A `Result` indicating success or failure. If no repositories are found or an error occurs,
@@ -175,112 +963,619 @@ fn get_local_git_default_author() -> Option<String> {
 /// use chrono::Utc;
 ///
 /// let config = Config {
-///     search_root: "/path/to/search".into(),
-///     search_depth: 3,
+///     discovery: DiscoveryArgs {
+///         search_root: vec!["/path/to/search".into()],
+///         search_depth: Some(3),
+///         ..Default::default()
+///     },
 ///     since: Some(Utc::now() - chrono::Duration::days(1)),
 ///     until: Some(Utc::now()),
-///     author: Some("John Doe <john.doe@example.com>".to_string()),
+///     author: vec!["John Doe <john.doe@example.com>".to_string()],
+///     branches: false,
+///     all: false,
+///     glob: Vec::new(),
+///     range: None,
+///     no_merges: false,
+///     merges_only: false,
+///     first_parent: false,
+///     date_field: DateField::Committer,
+///     timezone: DisplayTimezone::Original,
+///     grep: Vec::new(),
+///     regexp_ignore_case: false,
+///     grep_scope: GrepScope::Message,
+///     invert_grep: false,
+///     color: ColorChoice::Auto,
+///     types: Vec::new(),
+///     use_mailmap: false,
+///     mailmap_file: None,
+///     jobs: 0,
+///     format: Some(OutputFormat::Text),
+///     columns: Vec::new(),
+///     template: None,
+///     group_by: None,
+///     summary: false,
+///     stat: false,
+///     pathspecs: Vec::new(),
+///     profile: None,
+///     command: None,
 /// };
 ///
-/// if let Err(err) = run(config) {
+/// if let Err(err) = run(config, &ConfigFile::default()) {
 ///     eprintln!("Error: {}", err);
 /// }
 /// ```
-fn run(config: Config) -> Result<(), String> {
+fn run(config: Config, file: &ConfigFile) -> Result<(), String> {
+    match &config.command {
+        Some(Command::Changelog(args)) => {
+            return run_changelog(args, config.profile.as_deref(), file)
+        }
+        Some(Command::Config(ConfigCommand::Show)) => {
+            let settings = config.settings(file).map_err(|err| err.to_string())?;
+            let paths = ConfigFile::default_paths();
+            return write_settings(std::io::stdout().lock(), &settings, file, &paths)
+                .map_err(|err| format!("Error writing settings: {}", err));
+        }
+        None => {}
+    }
+    let settings = config.settings(file).map_err(|err| err.to_string())?;
+    config
+        .check_format(settings.format)
+        .map_err(|err| err.to_string())?;
+    let template = config.template(file).map_err(|err| err.to_string())?;
+    let author_filter = config
+        .author_filter(&settings)
+        .map_err(|err| err.to_string())?;
+    let greps = config.greps().map_err(|err| err.to_string())?;
+    let types = config.type_filter().map_err(|err| err.to_string())?;
+    let pathspec = config.pathspec().map_err(|err| err.to_string())?;
+
     let start_time = std::time::Instant::now();
 
-    let locator = GitRepositoryLocator::new(&config.search_root, config.search_depth);
-    let result = locator.locate();
-    let repositories = match result {
-        Ok(repositories) => repositories,
-        Err(err) => {
-            eprintln!("Error locating repositories: {}", err);
-            return Err("No repositories found".to_string());
+    let mut repositories = locate_repositories(&settings)?;
+
+    let elapsed_time = start_time.elapsed();
+
+    if repositories.is_empty() {
+        return Err("No repositories found".to_string());
+    }
+
+    log::info!(
+        "Found {} repositories in {:?}",
+        repositories.len(),
+        elapsed_time
+    );
+
+    if config.use_mailmap || config.mailmap_file.is_some() {
+        let global = match &config.mailmap_file {
+            Some(path) => Some(std::fs::read_to_string(path).map_err(|err| {
+                format!("Error reading mailmap file {}: {}", path.display(), err)
+            })?),
+            None => None,
+        };
+        for repo in &mut repositories {
+            repo.vcs.enable_mailmap(global.clone());
+        }
+    }
+
+    let commits_since = match (config.since, &config.range) {
+        (Some(since), _) => since.to_utc(),
+        (None, Some(_)) => DateTime::<chrono::Utc>::MIN_UTC,
+        (None, None) => chrono::Utc::now() - chrono::Duration::hours(24),
+    };
+
+    let commits_until = config
+        .until
+        .unwrap_or_else(|| chrono::Utc::now().with_timezone(&Local))
+        .to_utc();
+
+    let refs = config.ref_selection();
+
+    log::info!(
+        "Filtering commits since: {}, until: {}, by {} date, by author: {}, refs: {:?}, range: {:?}",
+        commits_since.to_rfc3339(),
+        commits_until.to_rfc3339(),
+        config.date_field,
+        author_filter,
+        refs,
+        config.range,
+    );
+
+    let show_branches =
+        refs != RefSelection::Head || config.range.as_ref().is_some_and(|r| r.symmetric);
+    let columns = match config.columns.is_empty() {
+        true => Column::DEFAULT.to_vec(),
+        false => config.columns.clone(),
+    };
+    let needs_branches = match settings.format {
+        OutputFormat::Text => match &template {
+            Some(template) => template.uses(Column::Branches),
+            None => show_branches,
+        },
+        #[cfg(feature = "json")]
+        OutputFormat::Json | OutputFormat::Ndjson => true,
+        OutputFormat::Csv | OutputFormat::Tsv => columns.contains(&Column::Branches),
+    };
+    let mut query = CommitQuery::new(commits_since, commits_until)
+        .with_date_field(config.date_field)
+        .with_refs(refs)
+        .with_paths(pathspec)
+        .with_stats(config.stat)
+        .with_branches(needs_branches)
+        .with_merges(config.merge_mode())
+        .with_first_parent(config.first_parent);
+    if let Some(range) = &config.range {
+        query = query.with_range(range.clone());
+    }
+    if let Some(filter) = config.commit_filter(author_filter, &greps, types) {
+        query = query.with_filter(filter);
+    }
+
+    let mut results = Vec::new();
+    for (git_repo, commits) in scan_repositories(repositories, &query, config.jobs) {
+        log::info!(
+            "Repository: {}, Name: {}, Head: {}",
+            git_repo.get_uri().display(),
+            git_repo.get_name(),
+            git_repo.vcs.head()
+        );
+
+        match commits {
+            Ok(commits) => {
+                log::debug!("Matching Commit Count: {}", commits.len());
+                results.push((git_repo, commits));
+            }
+            Err(err) => report_commit_error(&git_repo, &err),
+        }
+    }
+
+    // Non-conforming commits pass the `--type` filter, so they are counted in
+    // the summary before being reported apart from the listed commits
+    let summary = config.summary.then(|| summarize(&results));
+    if !config.types.is_empty() {
+        for (repo, commit) in split_non_conforming(&mut results) {
+            if let Err(err) = commit.conventional() {
+                eprintln!("Skipping {} in {}: {}", commit.hash.short, repo, err);
+            }
+        }
+    }
+
+    let options = ReportOptions {
+        date_field: config.date_field,
+        timezone: config.timezone,
+    };
+    let stdout = std::io::stdout().lock();
+    let color = match config.color {
+        ColorChoice::Auto => stdout.is_terminal(),
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+    };
+    let line_format = LineFormat {
+        template,
+        options,
+        show_branches,
+        highlight: match color && !config.invert_grep {
+            true => greps,
+            false => Vec::new(),
+        },
+    };
+    match settings.format {
+        OutputFormat::Text => write_text(stdout, &results, &config, &line_format, summary.as_ref()),
+        #[cfg(feature = "json")]
+        OutputFormat::Json => report::write_json(stdout, &results),
+        #[cfg(feature = "json")]
+        OutputFormat::Ndjson => report::write_ndjson(stdout, &results),
+        OutputFormat::Csv => {
+            report::write_delimited(stdout, &results, Delimiter::Comma, &columns, &options)
+        }
+        OutputFormat::Tsv => {
+            report::write_delimited(stdout, &results, Delimiter::Tab, &columns, &options)
+        }
+    }
+    .map_err(|err| format!("Error writing output: {}", err))
+}
+
+/// How each commit line of text output is rendered.
+struct LineFormat {
+    /// The template for each line, if any.
+    template: Option<Template>,
+    /// The options selecting the date field and timezone.
+    options: ReportOptions,
+    /// Whether to append the refs that contain each commit.
+    show_branches: bool,
+    /// The searches whose matches are highlighted.
+    highlight: Vec<Grep>,
+}
+
+impl LineFormat {
+    /// Renders one commit as a line of text, without a trailing newline.
+    ///
+    /// Lines are rendered with the template if there is one, otherwise as
+    /// `hash date title`. Search matches are only highlighted in the commit's
+    /// title and message, never in the hash, dates or other fields.
+    fn render<G: GitRepository>(&self, repo: &Repository<G>, commit: &Commit) -> String {
+        let highlighted;
+        let commit = match self.highlight.is_empty() {
+            true => commit,
+            false => {
+                let mark = |text: &str| {
+                    grep::highlight(text, &self.highlight, HIGHLIGHT_START, HIGHLIGHT_END)
+                };
+                highlighted = Commit {
+                    title: mark(&commit.title),
+                    message: mark(&commit.message),
+                    ..commit.clone()
+                };
+                &highlighted
+            }
+        };
+        match &self.template {
+            Some(template) => template.render(&RepositoryInfo::new(repo), commit, &self.options),
+            None => {
+                let date = self
+                    .options
+                    .timezone
+                    .format(&commit.date(self.options.date_field));
+                match self.show_branches {
+                    true => format!(
+                        "{} {} {} ({})",
+                        commit.hash.short,
+                        date,
+                        commit.title,
+                        commit.branches.join(", ")
+                    ),
+                    false => format!("{} {} {}", commit.hash.short, date, commit.title),
+                }
+            }
+        }
+    }
+}
+
+/// Writes one line per commit, optionally grouped and followed by a summary.
+///
+/// # Arguments
+/// - `writer`: The destination for the output.
+/// - `results`: The repositories and their commits.
+/// - `config`: The configuration selecting the grouping and summary.
+/// - `line_format`: How each commit line is rendered.
+/// - `summary`: The summary to append, if any.
+///
+/// # Errors
+/// Returns an error if writing to `writer` fails.
+fn write_text<G: GitRepository, W: Write>(
+    mut writer: W,
+    results: &[(Repository<G>, Vec<Commit>)],
+    config: &Config,
+    line_format: &LineFormat,
+    summary: Option<&Summary>,
+) -> walrust::Result<()> {
+    let options = &line_format.options;
+    let line = |repo: &Repository<G>, commit: &Commit| line_format.render(repo, commit);
+
+    match config.group_by {
+        Some(group_by) => {
+            for (i, group) in group_commits(results, group_by, options).iter().enumerate() {
+                if i > 0 {
+                    writeln!(writer)?;
+                }
+                writeln!(
+                    writer,
+                    "{} ({})",
+                    group.key,
+                    plural(group.commits.len(), "commit")
+                )?;
+                for (repo, commit) in &group.commits {
+                    writeln!(writer, "  {}", line(repo, commit))?;
+                    if let Some(stats) = &commit.stats {
+                        write_stat(&mut writer, "  ", stats)?;
+                    }
+                }
+            }
+        }
+        None => {
+            for (repo, commits) in results {
+                for commit in commits {
+                    writeln!(writer, "{}", line(repo, commit))?;
+                    if let Some(stats) = &commit.stats {
+                        write_stat(&mut writer, "", stats)?;
+                    }
+                }
+            }
+        }
+    }
+
+    if let Some(summary) = summary {
+        if summary.commits > 0 {
+            writeln!(writer)?;
+        }
+        let show_types = !config.types.is_empty() || config.group_by == Some(GroupBy::Type);
+        write_summary(writer, summary, show_types)?;
+    }
+    Ok(())
+}
+
+/// Writes the files a commit changes, followed by its totals, like `git log --stat`.
+///
+/// # Arguments
+/// - `writer`: The destination for the output.
+/// - `indent`: The prefix of every line.
+/// - `stats`: The commit's diff statistics.
+///
+/// # Errors
+/// Returns an error if writing to `writer` fails.
+fn write_stat<W: Write>(mut writer: W, indent: &str, stats: &DiffStats) -> walrust::Result<()> {
+    let width = stats
+        .files
+        .iter()
+        .map(|file| file.path.chars().count())
+        .max()
+        .unwrap_or_default();
+    for file in &stats.files {
+        writeln!(
+            writer,
+            "{} {:<width$} | +{} -{}",
+            indent, file.path, file.insertions, file.deletions
+        )?;
+    }
+    writeln!(writer, "{} {}", indent, stats)?;
+    Ok(())
+}
+
+/// Writes a summary as a table of commit counts per repository and per author.
+///
+/// # Arguments
+/// - `writer`: The destination for the output.
+/// - `summary`: The summary to write.
+/// - `show_types`: Whether to add a table of commit counts per Conventional
+///   Commits type.
+///
+/// # Errors
+/// Returns an error if writing to `writer` fails.
+fn write_summary<W: Write>(
+    mut writer: W,
+    summary: &Summary,
+    show_types: bool,
+) -> walrust::Result<()> {
+    let active = summary.repositories.iter().filter(|(_, n)| *n > 0).count();
+    writeln!(
+        writer,
+        "{} in {} of {} repositories by {}",
+        plural(summary.commits, "commit"),
+        active,
+        summary.repositories.len(),
+        plural(summary.authors.len(), "author")
+    )?;
+    if let Some(stats) = &summary.stats {
+        writeln!(
+            writer,
+            "{} changed, {}(+), {}(-)",
+            plural(stats.files_changed, "file"),
+            plural(stats.insertions, "insertion"),
+            plural(stats.deletions, "deletion")
+        )?;
+    }
+    let mut tables = vec![
+        ("Repository", &summary.repositories),
+        ("Author", &summary.authors),
+    ];
+    if show_types {
+        tables.push(("Type", &summary.types));
+    }
+    for (heading, rows) in tables {
+        let width = rows
+            .iter()
+            .map(|(name, _)| name.chars().count())
+            .chain([heading.len()])
+            .max()
+            .unwrap_or_default();
+        writeln!(writer)?;
+        writeln!(writer, "{:<width$}  Commits", heading)?;
+        for (name, count) in rows {
+            writeln!(writer, "{:<width$}  {:>7}", name, count)?;
+        }
+    }
+    Ok(())
+}
+
+/// Formats a count with a noun, e.g. `1 commit` or `3 commits`.
+fn plural(count: usize, noun: &str) -> String {
+    match count {
+        1 => format!("1 {}", noun),
+        n => format!("{} {}s", n, noun),
+    }
+}
+
+/// Locates the repositories to query.
+///
+/// The repositories in the repository list are used if one is given, and the
+/// search roots are searched otherwise.
+///
+/// # Arguments
+/// - `settings`: The settings naming the repository list or search roots.
+///
+/// # Returns
+/// The repositories, sorted by path.
+///
+/// # Errors
+/// Returns an error if the repository list cannot be read or the search fails.
+fn locate_repositories(settings: &Settings) -> Result<Vec<Repository>, String> {
+    let locator = match &settings.repo_list {
+        Some(path) => {
+            let paths = read_repository_list(path).map_err(|err| {
+                format!("Error reading repository list {}: {}", path.display(), err)
+            })?;
+            GitRepositoryLocator::from_list(paths)
+        }
+        None => GitRepositoryLocator::from_roots(&settings.roots, settings.depth)
+            .with_filter(settings.filter.clone())
+            .with_nested(settings.nested)
+            .with_follow_symlinks(settings.follow_symlinks),
+    }
+    .with_submodules(settings.submodules);
+    locator.locate().map_err(|err| {
+        eprintln!("Error locating repositories: {}", err);
+        "No repositories found".to_string()
+    })
+}
+
+/// Writes the effective settings, followed by the config files they come from.
+///
+/// # Arguments
+/// - `writer`: The destination for the output.
+/// - `settings`: The resolved settings.
+/// - `file`: The configuration, listing the templates and profiles.
+/// - `paths`: The config files the configuration was loaded from.
+///
+/// # Errors
+/// Returns an error if writing to `writer` fails.
+fn write_settings<W: Write>(
+    mut writer: W,
+    settings: &Settings,
+    file: &ConfigFile,
+    paths: &[PathBuf],
+) -> std::io::Result<()> {
+    let list = |items: Vec<String>| {
+        let quoted: Vec<_> = items.iter().map(|item| format!("{:?}", item)).collect();
+        format!("[{}]", quoted.join(", "))
+    };
+    match paths.is_empty() {
+        true => writeln!(writer, "# No config files found")?,
+        false => {
+            for path in paths {
+                writeln!(writer, "# Loaded {}", path.display())?;
+            }
+        }
+    }
+    if let Some(profile) = &settings.profile {
+        writeln!(writer, "profile = {:?}", profile)?;
+    }
+    let roots = settings.roots.iter().map(|r| r.display().to_string());
+    writeln!(writer, "roots = {}", list(roots.collect()))?;
+    if let Some(repo_list) = &settings.repo_list {
+        writeln!(writer, "repo_list = {:?}", repo_list.display().to_string())?;
+    }
+    writeln!(writer, "depth = {}", settings.depth)?;
+    let globs = |globs: &[Glob]| list(globs.iter().map(|g| g.to_string()).collect());
+    if !settings.filter.include.is_empty() {
+        writeln!(writer, "include = {}", globs(&settings.filter.include))?;
+    }
+    writeln!(writer, "exclude = {}", globs(&settings.filter.exclude))?;
+    writeln!(writer, "nested = {}", settings.nested)?;
+    writeln!(writer, "submodules = {}", settings.submodules)?;
+    writeln!(writer, "follow_symlinks = {}", settings.follow_symlinks)?;
+    let identities = match settings.identities.is_empty() {
+        true => get_local_git_default_author().into_iter().collect(),
+        false => settings.identities.clone(),
+    };
+    writeln!(writer, "identities = {}", list(identities))?;
+    if let Some(format) = settings.format.to_possible_value() {
+        writeln!(writer, "format = {:?}", format.get_name())?;
+    }
+    writeln!(
+        writer,
+        "templates = {}",
+        list(file.templates.keys().cloned().collect())
+    )?;
+    writeln!(
+        writer,
+        "profiles = {}",
+        list(file.profiles.keys().cloned().collect())
+    )?;
+    Ok(())
+}
+
+/// Reports a repository whose commits could not be retrieved.
+///
+/// A tag or branch often exists in only some of the repositories searched, so
+/// repositories missing a revision of the range are skipped with a note.
+///
+/// # Arguments
+/// - `repo`: The repository.
+/// - `err`: The error retrieving its commits.
+fn report_commit_error<G: GitRepository>(repo: &Repository<G>, err: &WalrustError) {
+    match err {
+        WalrustError::RevisionNotFound(rev) => {
+            eprintln!("Skipping {}: revision '{}' not found", repo.name, rev)
         }
-    };
+        err => eprintln!("Error getting commits: {}", err),
+    }
+}
 
-    let elapsed_time = start_time.elapsed();
+/// Runs the `changelog` subcommand.
+///
+/// This function locates repositories, collects the commits of the revision
+/// range in each of them, and writes them as a Markdown changelog to standard
+/// output or to the output file.
+///
+/// # Arguments
+/// - `args`: The arguments of the subcommand.
+/// - `profile`: The config file profile whose settings apply, if any.
+/// - `file`: The configuration, holding the title prefixes.
+///
+/// # Returns
+/// A `Result` indicating success or failure.
+///
+/// # Errors
+/// - Returns an error if the profile does not exist.
+/// - Returns an error if prefix grouping is requested without configured prefixes.
+/// - Returns an error if no repositories are found.
+/// - Returns an error if the output file cannot be written.
+fn run_changelog(
+    args: &ChangelogArgs,
+    profile: Option<&str>,
+    file: &ConfigFile,
+) -> Result<(), String> {
+    let settings =
+        Settings::resolve(file, profile, &args.discovery, None).map_err(|err| err.to_string())?;
+    let changelog = Changelog::new(args.group_by).with_prefixes(file.changelog.prefixes.clone());
+    if changelog.grouping == ChangelogGrouping::Prefix && changelog.prefixes.is_empty() {
+        return Err(
+            "Grouping by prefix needs [[changelog.prefixes]] in the config file".to_string(),
+        );
+    }
 
+    let repositories = locate_repositories(&settings)?;
     if repositories.is_empty() {
         return Err("No repositories found".to_string());
     }
 
-    log::info!(
-        "Found {} repositories in {:?}",
-        repositories.len(),
-        elapsed_time
-    );
-
-    let commits_since = config
+    let since = args
         .since
-        .unwrap_or_else(|| {
-            let now = chrono::Utc::now() - chrono::Duration::hours(24);
-            now.with_timezone(&Local)
-        })
-        .to_utc();
-
-    let commits_until = config
+        .map_or(DateTime::<chrono::Utc>::MIN_UTC, |since| since.to_utc());
+    let until = args
         .until
         .unwrap_or_else(|| chrono::Utc::now().with_timezone(&Local))
         .to_utc();
+    let query = CommitQuery::new(since, until)
+        .with_range(args.range())
+        .with_branches(false);
 
-    let author_match = config
-        .author
-        .unwrap_or(get_local_git_default_author().unwrap_or_default());
-
-    let author_predicate = |commit: &Commit| match author_match {
-        ref author if author.is_empty() => true,
-        ref author if commit.author.to_string() == *author => true,
-        _ => false,
-    };
-
-    log::info!(
-        "Filtering commits since: {}, until: {}, by author: {}",
-        commits_since.to_rfc3339(),
-        commits_until.to_rfc3339(),
-        author_match,
-    );
-
-    for git_repo in &repositories {
-        log::info!(
-            "Repository: {}, Name: {}, Head: {}",
-            git_repo.get_uri().display(),
-            git_repo.get_name(),
-            git_repo.vcs.head()
-        );
-
-        let commits = git_repo.vcs.get_commits(commits_since, commits_until);
+    let mut results = Vec::new();
+    for (git_repo, commits) in scan_repositories(repositories, &query, args.jobs) {
         match commits {
-            Ok(commits) => {
-                let filtered_commits = commits
-                    .into_iter()
-                    .filter(|commit| author_predicate(&commit))
-                    .collect::<Vec<_>>();
-
-                log::debug!("Matching Commit Count: {}", filtered_commits.len());
-
-                for commit in &filtered_commits {
-                    println!(
-                        "{} {} {}",
-                        commit.hash.short,
-                        commit.commit_date.to_rfc3339(),
-                        commit.title
-                    );
-                }
-            }
-            Err(err) => {
-                eprintln!("Error getting commits: {}", err);
-            }
+            Ok(commits) => results.push((git_repo, commits)),
+            Err(err) => report_commit_error(&git_repo, &err),
         }
     }
 
-    Ok(())
+    let title = args.title();
+    let written = match &args.output {
+        Some(path) => {
+            let output = std::fs::File::create(path)
+                .map_err(|err| format!("Error creating {}: {}", path.display(), err))?;
+            let mut writer = std::io::BufWriter::new(output);
+            changelog
+                .write_markdown(&mut writer, &title, &results)
+                .and_then(|_| writer.flush().map_err(Into::into))
+        }
+        None => changelog.write_markdown(std::io::stdout().lock(), &title, &results),
+    };
+    written.map_err(|err| format!("Error writing changelog: {}", err))
 }
 
 /// The main entry point for the `walrust` CLI tool.
 ///
-/// This function parses the command-line arguments, runs the repository locator,
-/// and handles any errors that occur.
+/// This function parses the command-line arguments, loads the configuration
+/// file, runs the repository locator, and handles any errors that occur.
 ///
 /// # Example
 /// ```bash
@@ -289,7 +1584,10 @@ fn run(config: Config) -> Result<(), String> {
 fn main() {
     let config = Config::parse();
 
-    match run(config) {
+    match ConfigFile::load_default()
+        .map_err(|err| err.to_string())
+        .and_then(|file| run(config, &file))
+    {
         Ok(_) => {}
         Err(err) => {
             eprintln!("{}", err);
@@ -301,6 +1599,8 @@ fn main() {
 mod tests {
     use super::*;
     use chrono::Utc;
+    use walrust::commit::FileStats;
+    use walrust::summary::DiffTotals;
 
     #[test]
     fn test_parse_datetime_rfc3339() {
@@ -351,18 +1651,818 @@ mod tests {
     #[test]
     fn test_run_no_repositories() {
         let config = Config {
-            search_root: "/non/existent/path".into(),
-            search_depth: 3,
+            discovery: DiscoveryArgs {
+                search_root: vec!["/non/existent/path".into()],
+                repo_list: None,
+                search_depth: Some(3),
+                include: Vec::new(),
+                exclude: Vec::new(),
+                no_default_excludes: false,
+                nested: false,
+                submodules: false,
+                follow_symlinks: false,
+            },
             since: None,
             until: None,
-            author: None,
+            author: Vec::new(),
+            branches: false,
+            all: false,
+            glob: Vec::new(),
+            range: None,
+            no_merges: false,
+            merges_only: false,
+            first_parent: false,
+            date_field: DateField::Committer,
+            timezone: DisplayTimezone::Original,
+            grep: Vec::new(),
+            regexp_ignore_case: false,
+            grep_scope: GrepScope::Message,
+            invert_grep: false,
+            color: ColorChoice::Auto,
+            types: Vec::new(),
+            use_mailmap: false,
+            mailmap_file: None,
+            jobs: 0,
+            format: Some(OutputFormat::Text),
+            columns: Vec::new(),
+            template: None,
+            group_by: None,
+            summary: false,
+            stat: false,
+            pathspecs: Vec::new(),
+            profile: None,
+            command: None,
         };
 
-        let result = run(config);
+        let result = run(config, &ConfigFile::default());
         assert!(result.is_err());
         assert_eq!(result.unwrap_err(), "No repositories found".to_string());
     }
 
+    /// Parses the given extra arguments after fixed search options.
+    fn parse_config(args: &[&str]) -> Config {
+        let mut argv = vec!["walrust", "-r", ".", "-d", "1"];
+        argv.extend_from_slice(args);
+        Config::try_parse_from(argv).unwrap()
+    }
+
+    #[test]
+    fn test_config_ref_selection() {
+        let config = parse_config(&[]);
+        assert_eq!(config.ref_selection(), RefSelection::Head);
+
+        let config = parse_config(&["--branches"]);
+        assert_eq!(config.ref_selection(), RefSelection::LocalBranches);
+
+        let config = parse_config(&["--all"]);
+        assert_eq!(config.ref_selection(), RefSelection::AllRefs);
+
+        let config = parse_config(&[
+            "--glob",
+            "refs/heads/feature/*",
+            "--glob",
+            "refs/heads/fix/*",
+        ]);
+        assert_eq!(
+            config.ref_selection(),
+            RefSelection::Globs(vec![
+                "refs/heads/feature/*".to_string(),
+                "refs/heads/fix/*".to_string()
+            ])
+        );
+    }
+
+    #[test]
+    fn test_config_range() {
+        assert_eq!(parse_config(&[]).range, None);
+
+        let config = parse_config(&["--range", "v1.2.0..HEAD", "--since", "2025-05-01"]);
+        assert_eq!(
+            config.range,
+            Some(RevisionRange::new(
+                Some("v1.2.0".to_string()),
+                "HEAD".to_string()
+            ))
+        );
+        assert!(config.since.is_some());
+
+        let config = parse_config(&["--range", "main...feature"]);
+        assert_eq!(
+            config.range,
+            Some(RevisionRange::symmetric(
+                "main".to_string(),
+                "feature".to_string()
+            ))
+        );
+
+        let args = vec!["walrust", "-r", ".", "-d", "1", "--range", "a..b", "--all"];
+        assert!(Config::try_parse_from(args).is_err());
+        let args = vec!["walrust", "-r", ".", "-d", "1", "--range", "a..b..c"];
+        assert!(Config::try_parse_from(args).is_err());
+    }
+
+    #[test]
+    fn test_config_merge_mode() {
+        let config = parse_config(&[]);
+        assert_eq!(config.merge_mode(), MergeMode::Include);
+        assert!(!config.first_parent);
+
+        let config = parse_config(&["--no-merges", "--first-parent"]);
+        assert_eq!(config.merge_mode(), MergeMode::Exclude);
+        assert!(config.first_parent);
+
+        let config = parse_config(&["--merges-only"]);
+        assert_eq!(config.merge_mode(), MergeMode::Only);
+
+        let args = vec![
+            "walrust",
+            "-r",
+            ".",
+            "-d",
+            "1",
+            "--no-merges",
+            "--merges-only",
+        ];
+        assert!(Config::try_parse_from(args).is_err());
+    }
+
+    #[test]
+    fn test_config_date_field() {
+        let config = parse_config(&[]);
+        assert_eq!(config.date_field, DateField::Committer);
+
+        let config = parse_config(&["--date-field", "author"]);
+        assert_eq!(config.date_field, DateField::Author);
+
+        let args = vec!["walrust", "-r", ".", "-d", "1", "--date-field", "commit"];
+        assert!(Config::try_parse_from(args).is_err());
+    }
+
+    #[test]
+    fn test_config_timezone() {
+        let config = parse_config(&[]);
+        assert_eq!(config.timezone, DisplayTimezone::Original);
+
+        let config = parse_config(&["--timezone", "utc"]);
+        assert_eq!(config.timezone, DisplayTimezone::Utc);
+
+        let config = parse_config(&["--timezone", "local"]);
+        assert_eq!(config.timezone, DisplayTimezone::Local);
+    }
+
+    #[test]
+    fn test_config_mailmap() {
+        let config = parse_config(&[]);
+        assert!(!config.use_mailmap);
+        assert_eq!(config.mailmap_file, None);
+
+        let config = parse_config(&["--use-mailmap", "--mailmap-file", "/etc/mailmap"]);
+        assert!(config.use_mailmap);
+        assert_eq!(config.mailmap_file, Some(PathBuf::from("/etc/mailmap")));
+    }
+
+    #[test]
+    fn test_config_jobs() {
+        let config = parse_config(&[]);
+        assert_eq!(config.jobs, 0);
+
+        let config = parse_config(&["-j", "8"]);
+        assert_eq!(config.jobs, 8);
+    }
+
+    #[test]
+    fn test_config_format() {
+        let config = parse_config(&[]);
+        assert_eq!(config.format, None);
+        let settings = config.settings(&ConfigFile::default()).unwrap();
+        assert_eq!(settings.format, OutputFormat::Text);
+
+        #[cfg(feature = "json")]
+        {
+            let config = parse_config(&["--format", "json"]);
+            assert_eq!(config.format, Some(OutputFormat::Json));
+
+            let config = parse_config(&["--format", "ndjson"]);
+            assert_eq!(config.format, Some(OutputFormat::Ndjson));
+        }
+    }
+
+    #[test]
+    fn test_config_columns() {
+        let config = parse_config(&[]);
+        assert!(config.columns.is_empty());
+
+        let config = parse_config(&["--format", "csv", "--columns", "repo,hash,title"]);
+        assert_eq!(config.format, Some(OutputFormat::Csv));
+        assert_eq!(
+            config.columns,
+            vec![Column::Repo, Column::Hash, Column::Title]
+        );
+
+        let args = vec!["walrust", "-r", ".", "-d", "1", "--columns", "repo,sha"];
+        assert!(Config::try_parse_from(args).is_err());
+    }
+
+    #[test]
+    fn test_config_template() {
+        let file = ConfigFile::parse("[templates]\nshort = \"{short_hash}\"").unwrap();
+        let config = parse_config(&[]);
+        assert!(config.template(&file).unwrap().is_none());
+
+        let config = parse_config(&["--template", "{short_hash} {title}"]);
+        assert_eq!(
+            config.template(&file).unwrap(),
+            Some("{short_hash} {title}".parse().unwrap())
+        );
+
+        let config = parse_config(&["--template", "short"]);
+        assert_eq!(
+            config.template(&file).unwrap(),
+            Some("{short_hash}".parse().unwrap())
+        );
+
+        let config = parse_config(&["--template", "{short_hash} {sha}"]);
+        assert!(config.template(&file).is_err());
+
+        let config = parse_config(&["--template", "long"]);
+        assert!(config.template(&file).is_err());
+    }
+
+    #[test]
+    fn test_config_author_filter() {
+        let file = ConfigFile::parse(r#"identities = ["email:me@work.example"]"#).unwrap();
+        let settings = parse_config(&[]).settings(&file).unwrap();
+
+        let config = parse_config(&["-a", "email:me@home.example", "--author", "icase:me"]);
+        let filter = config.author_filter(&settings).unwrap();
+        assert_eq!(
+            filter,
+            AuthorFilter::parse(["email:me@home.example", "icase:me"]).unwrap()
+        );
+
+        let config = parse_config(&[]);
+        assert_eq!(
+            config.author_filter(&settings).unwrap(),
+            file.identities().unwrap()
+        );
+
+        let config = parse_config(&["-a", ""]);
+        assert!(config.author_filter(&settings).unwrap().is_empty());
+
+        let config = parse_config(&["-a", "regex:("]);
+        assert!(config.author_filter(&settings).is_err());
+    }
+
+    #[test]
+    fn test_config_grep() {
+        let config = parse_config(&[]);
+        assert!(config.greps().unwrap().is_empty());
+        assert_eq!(
+            config.commit_filter(AuthorFilter::default(), &[], None),
+            None
+        );
+
+        let config = parse_config(&[
+            "--grep",
+            "JIRA-\\d+",
+            "--grep",
+            "hotfix",
+            "-i",
+            "--grep-scope",
+            "title",
+            "--invert-grep",
+        ]);
+        let greps = config.greps().unwrap();
+        assert_eq!(
+            greps,
+            vec![
+                Grep::new("JIRA-\\d+", true, GrepScope::Title).unwrap(),
+                Grep::new("hotfix", true, GrepScope::Title).unwrap(),
+            ]
+        );
+        let authors = AuthorFilter::parse(["substr:Bix"]).unwrap();
+        assert_eq!(
+            config.commit_filter(authors.clone(), &greps, None),
+            Some(CommitFilter::And(vec![
+                CommitFilter::Author(authors),
+                !CommitFilter::Or(greps.into_iter().map(CommitFilter::Message).collect()),
+            ]))
+        );
+
+        let args = vec!["walrust", "-r", ".", "-d", "1", "--invert-grep"];
+        assert!(Config::try_parse_from(args).is_err());
+
+        let config = parse_config(&["--grep", "("]);
+        assert!(config.greps().is_err());
+
+        assert_eq!(parse_config(&[]).color, ColorChoice::Auto);
+        let config = parse_config(&["--color", "never"]);
+        assert_eq!(config.color, ColorChoice::Never);
+    }
+
+    #[test]
+    fn test_config_pathspecs() {
+        assert!(parse_config(&[]).pathspecs.is_empty());
+
+        let config = parse_config(&["--summary", "--", "deploy/", "*.toml"]);
+        assert!(config.summary);
+        assert_eq!(config.pathspecs, vec!["deploy/", "*.toml"]);
+        assert_eq!(config.pathspec().unwrap().patterns(), ["deploy/", "*.toml"]);
+
+        let config = parse_config(&["--", "v[0-9"]);
+        assert!(config.pathspec().is_err());
+
+        let args = vec!["walrust", "-r", ".", "-d", "1", "deploy/"];
+        assert!(Config::try_parse_from(args).is_err());
+    }
+
+    #[test]
+    fn test_config_types() {
+        let config = parse_config(&[]);
+        assert_eq!(config.type_filter().unwrap(), None);
+
+        let config = parse_config(&["--type", "feat,FIX", "--type", "docs"]);
+        let types = config.type_filter().unwrap();
+        assert_eq!(
+            types,
+            Some(CommitFilter::Or(vec![
+                CommitFilter::Type("feat".to_string()),
+                CommitFilter::Type("fix".to_string()),
+                CommitFilter::Type("docs".to_string()),
+                !CommitFilter::Conventional,
+            ]))
+        );
+        assert_eq!(
+            config.commit_filter(AuthorFilter::default(), &[], types.clone()),
+            types
+        );
+
+        let config = parse_config(&["--type", "feat:"]);
+        assert!(config.type_filter().is_err());
+    }
+
+    #[test]
+    fn test_config_settings() {
+        let file = ConfigFile::parse(
+            r#"
+            roots = ["/src", "/work"]
+            depth = 3
+            format = "csv"
+
+            [profiles.work]
+            roots = ["/work"]
+            identities = ["email:me@work.example"]
+            format = "tsv"
+            "#,
+        )
+        .unwrap();
+
+        let settings = parse_config(&[]).settings(&ConfigFile::default()).unwrap();
+        assert_eq!(settings.roots, vec![PathBuf::from(".")]);
+        assert_eq!(settings.depth, 1);
+        assert_eq!(settings.format, OutputFormat::Text);
+
+        let config = Config::try_parse_from(["walrust"]).unwrap();
+        let settings = config.settings(&file).unwrap();
+        assert_eq!(settings.profile, None);
+        assert_eq!(
+            settings.roots,
+            vec![PathBuf::from("/src"), PathBuf::from("/work")]
+        );
+        assert_eq!(settings.depth, 3);
+        assert!(settings.identities.is_empty());
+        assert_eq!(settings.format, OutputFormat::Csv);
+
+        let config = Config::try_parse_from(["walrust", "--profile", "work"]).unwrap();
+        let settings = config.settings(&file).unwrap();
+        assert_eq!(settings.profile.as_deref(), Some("work"));
+        assert_eq!(settings.roots, vec![PathBuf::from("/work")]);
+        assert_eq!(settings.depth, 3);
+        assert_eq!(settings.identities, vec!["email:me@work.example"]);
+        assert_eq!(settings.format, OutputFormat::Tsv);
+
+        let config = Config::try_parse_from([
+            "walrust",
+            "--profile",
+            "work",
+            "-r",
+            "/tmp",
+            "-d",
+            "2",
+            "--format",
+            "text",
+        ])
+        .unwrap();
+        let settings = config.settings(&file).unwrap();
+        assert_eq!(settings.roots, vec![PathBuf::from("/tmp")]);
+        assert_eq!(settings.depth, 2);
+        assert_eq!(settings.format, OutputFormat::Text);
+
+        let config =
+            Config::try_parse_from(["walrust", "-r", "/a", "--search-root", "/b"]).unwrap();
+        let settings = config.settings(&file).unwrap();
+        assert_eq!(
+            settings.roots,
+            vec![PathBuf::from("/a"), PathBuf::from("/b")]
+        );
+        assert_eq!(settings.repo_list, None);
+
+        let config = Config::try_parse_from(["walrust", "--repo-list", "repos.txt"]).unwrap();
+        let settings = config.settings(&file).unwrap();
+        assert_eq!(settings.repo_list, Some(PathBuf::from("repos.txt")));
+        assert!(
+            Config::try_parse_from(["walrust", "--repo-list", "repos.txt", "-r", "/a"]).is_err()
+        );
+        assert!(
+            Config::try_parse_from(["walrust", "--repo-list", "repos.txt", "-d", "2"]).is_err()
+        );
+
+        let config = Config::try_parse_from(["walrust", "--profile", "home"]).unwrap();
+        assert!(config.settings(&file).is_err());
+
+        let file = ConfigFile::parse(r#"format = "yaml""#).unwrap();
+        let err = parse_config(&[]).settings(&file).unwrap_err();
+        assert!(err.to_string().contains("unknown format 'yaml'"));
+    }
+
+    #[test]
+    fn test_config_discovery_filter() {
+        let file = ConfigFile::parse(
+            r#"
+            exclude = ["archive"]
+
+            [profiles.clients]
+            include = ["clients/*"]
+            default_excludes = false
+            "#,
+        )
+        .unwrap();
+        let defaults = DiscoveryFilter::default();
+
+        let settings = parse_config(&[]).settings(&ConfigFile::default()).unwrap();
+        assert_eq!(settings.filter, defaults);
+
+        let settings = parse_config(&[]).settings(&file).unwrap();
+        assert!(settings.filter.include.is_empty());
+        assert_eq!(settings.filter.exclude[0].as_str(), "archive");
+        assert_eq!(settings.filter.exclude.len(), defaults.exclude.len() + 1);
+
+        let config = parse_config(&["--exclude", "old", "--exclude", "*.bak"]);
+        let settings = config.settings(&file).unwrap();
+        let excludes: Vec<_> = settings.filter.exclude.iter().map(Glob::as_str).collect();
+        assert_eq!(&excludes[..2], ["old", "*.bak"]);
+        assert!(!excludes.contains(&"archive"));
+
+        let config = parse_config(&["--no-default-excludes"]);
+        let settings = config.settings(&file).unwrap();
+        assert_eq!(
+            settings.filter,
+            DiscoveryFilter::new([""; 0], ["archive"]).unwrap()
+        );
+
+        let config = parse_config(&["--profile", "clients"]);
+        let settings = config.settings(&file).unwrap();
+        assert_eq!(
+            settings.filter,
+            DiscoveryFilter::new(["clients/*"], ["archive"]).unwrap()
+        );
+
+        let config = parse_config(&["--profile", "clients", "--include", "work/*"]);
+        let settings = config.settings(&file).unwrap();
+        assert_eq!(settings.filter.include[0].as_str(), "work/*");
+
+        let err = parse_config(&["--exclude", "v[0-9"])
+            .settings(&file)
+            .unwrap_err();
+        assert!(err.to_string().contains("invalid glob 'v[0-9'"));
+
+        assert!(
+            Config::try_parse_from(["walrust", "--repo-list", "repos.txt", "--exclude", "a"])
+                .is_err()
+        );
+        let config = Config::try_parse_from(["walrust", "changelog", "--exclude", "old"]).unwrap();
+        let Some(Command::Changelog(args)) = config.command else {
+            panic!("expected the changelog subcommand");
+        };
+        assert_eq!(args.discovery.exclude, vec!["old"]);
+    }
+
+    #[test]
+    fn test_config_nested_submodules() {
+        let file = ConfigFile::parse(
+            r#"
+            submodules = true
+
+            [profiles.flat]
+            submodules = false
+            "#,
+        )
+        .unwrap();
+
+        let settings = parse_config(&[]).settings(&ConfigFile::default()).unwrap();
+        assert!(!settings.nested);
+        assert!(!settings.submodules);
+
+        let settings = parse_config(&[]).settings(&file).unwrap();
+        assert!(!settings.nested);
+        assert!(settings.submodules);
+
+        let config = parse_config(&["--profile", "flat", "--nested"]);
+        let settings = config.settings(&file).unwrap();
+        assert!(settings.nested);
+        assert!(!settings.submodules);
+
+        let config = parse_config(&["--profile", "flat", "--submodules"]);
+        assert!(config.settings(&file).unwrap().submodules);
+
+        let config =
+            Config::try_parse_from(["walrust", "--repo-list", "repos.txt", "--submodules"])
+                .unwrap();
+        assert!(config.settings(&file).unwrap().submodules);
+        assert!(
+            Config::try_parse_from(["walrust", "--repo-list", "repos.txt", "--nested"]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_config_follow_symlinks() {
+        let file = ConfigFile::parse("follow_symlinks = true").unwrap();
+
+        let settings = parse_config(&[]).settings(&ConfigFile::default()).unwrap();
+        assert!(!settings.follow_symlinks);
+        assert!(parse_config(&[]).settings(&file).unwrap().follow_symlinks);
+        assert!(
+            parse_config(&["-L"])
+                .settings(&ConfigFile::default())
+                .unwrap()
+                .follow_symlinks
+        );
+        assert!(Config::try_parse_from([
+            "walrust",
+            "--repo-list",
+            "repos.txt",
+            "--follow-symlinks"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_config_show() {
+        let config = Config::try_parse_from(["walrust", "config", "show"]).unwrap();
+        assert_eq!(config.command, Some(Command::Config(ConfigCommand::Show)));
+        assert_eq!(config.profile, None);
+
+        let config =
+            Config::try_parse_from(["walrust", "config", "show", "--profile", "work"]).unwrap();
+        assert_eq!(config.profile.as_deref(), Some("work"));
+
+        let config = Config::try_parse_from(["walrust", "changelog", "--profile", "work"]).unwrap();
+        assert_eq!(config.profile.as_deref(), Some("work"));
+    }
+
+    #[test]
+    fn test_write_settings() {
+        let file = ConfigFile::parse(
+            r#"
+            [profiles.work]
+            depth = 2
+
+            [templates]
+            short = "{short_hash}"
+            "#,
+        )
+        .unwrap();
+        let settings = Settings {
+            profile: Some("work".to_string()),
+            roots: vec![PathBuf::from("/src"), PathBuf::from("/work")],
+            repo_list: None,
+            depth: 2,
+            filter: DiscoveryFilter::new(["work/*"], ["archive"]).unwrap(),
+            nested: false,
+            submodules: true,
+            follow_symlinks: false,
+            identities: vec!["email:me@work.example".to_string()],
+            format: OutputFormat::Csv,
+        };
+        let paths = [PathBuf::from("/home/me/.config/walrust/config.toml")];
+
+        let mut output = Vec::new();
+        write_settings(&mut output, &settings, &file, &paths).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "# Loaded /home/me/.config/walrust/config.toml\n\
+             profile = \"work\"\n\
+             roots = [\"/src\", \"/work\"]\n\
+             depth = 2\n\
+             include = [\"work/*\"]\n\
+             exclude = [\"archive\"]\n\
+             nested = false\n\
+             submodules = true\n\
+             follow_symlinks = false\n\
+             identities = [\"email:me@work.example\"]\n\
+             format = \"csv\"\n\
+             templates = [\"short\"]\n\
+             profiles = [\"work\"]\n"
+        );
+
+        let mut output = Vec::new();
+        write_settings(&mut output, &settings, &file, &[]).unwrap();
+        assert!(String::from_utf8(output)
+            .unwrap()
+            .starts_with("# No config files found\n"));
+    }
+
+    #[test]
+    fn test_config_changelog() {
+        assert_eq!(parse_config(&[]).command, None);
+
+        let config = Config::try_parse_from(["walrust", "changelog"]).unwrap();
+        let Some(Command::Changelog(args)) = config.command else {
+            panic!("expected the changelog subcommand");
+        };
+        assert!(args.discovery.search_root.is_empty());
+        assert_eq!(args.range(), RevisionRange::new(None, "HEAD".to_string()));
+        assert_eq!(args.group_by, ChangelogGrouping::Type);
+        assert_eq!(args.output, None);
+        assert_eq!(args.title(), "Changelog HEAD");
+
+        let config = Config::try_parse_from([
+            "walrust",
+            "changelog",
+            "-r",
+            "/src",
+            "--from",
+            "v1.2.0",
+            "--to",
+            "v1.3.0",
+            "--until",
+            "2025-05-06",
+            "--group-by",
+            "prefix",
+            "-o",
+            "CHANGELOG.md",
+        ])
+        .unwrap();
+        let Some(Command::Changelog(args)) = config.command else {
+            panic!("expected the changelog subcommand");
+        };
+        assert_eq!(args.discovery.search_root, vec![PathBuf::from("/src")]);
+        assert_eq!(
+            args.range(),
+            RevisionRange::new(Some("v1.2.0".to_string()), "v1.3.0".to_string())
+        );
+        assert_eq!(args.group_by, ChangelogGrouping::Prefix);
+        assert_eq!(args.output, Some(PathBuf::from("CHANGELOG.md")));
+        assert_eq!(args.title(), "Changelog v1.2.0..v1.3.0 until 2025-05-06");
+
+        assert!(Config::try_parse_from(["walrust", "--stat", "changelog"]).is_err());
+        assert!(Config::try_parse_from(["walrust", "changelog", "--author", "Bix"]).is_err());
+    }
+
+    #[test]
+    fn test_config_group_by() {
+        let config = parse_config(&[]);
+        assert_eq!(config.group_by, None);
+        assert!(!config.summary);
+
+        let config = parse_config(&["--group-by", "week", "--summary"]);
+        assert_eq!(config.group_by, Some(GroupBy::Week));
+        assert!(config.summary);
+
+        let args = vec!["walrust", "-r", ".", "-d", "1", "--group-by", "month"];
+        assert!(Config::try_parse_from(args).is_err());
+    }
+
+    #[test]
+    fn test_config_check_format() {
+        let config = parse_config(&["--group-by", "repo", "--summary"]);
+        assert!(config.check_format(OutputFormat::Text).is_ok());
+        let err = config.check_format(OutputFormat::Csv).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("--group-by only applies to text output, not 'csv'"));
+
+        let config = parse_config(&["--summary"]);
+        assert!(config.check_format(OutputFormat::Tsv).is_err());
+        assert!(parse_config(&[]).check_format(OutputFormat::Csv).is_ok());
+    }
+
+    #[test]
+    fn test_write_stat() {
+        assert!(!parse_config(&[]).stat);
+        assert!(parse_config(&["--stat"]).stat);
+
+        let stats = DiffStats::new(vec![
+            FileStats {
+                path: "src/lib.rs".to_string(),
+                insertions: 2,
+                deletions: 1,
+            },
+            FileStats {
+                path: "README.md".to_string(),
+                insertions: 1,
+                deletions: 0,
+            },
+        ]);
+        let mut output = Vec::new();
+        write_stat(&mut output, "  ", &stats).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "   src/lib.rs | +2 -1\n\
+            \x20  README.md  | +1 -0\n\
+            \x20  2 files changed, 3 insertions(+), 1 deletion(-)\n"
+        );
+    }
+
+    #[test]
+    fn test_write_summary() {
+        let summary = Summary {
+            commits: 3,
+            repositories: vec![("coruscant".to_string(), 3), ("ferrix".to_string(), 0)],
+            authors: vec![("Luthen Rael <luthen@axis.org>".to_string(), 3)],
+            types: vec![("feat".to_string(), 2), ("non-conforming".to_string(), 1)],
+            stats: Some(DiffTotals {
+                commits: 3,
+                files_changed: 4,
+                insertions: 1,
+                deletions: 12,
+            }),
+        };
+        let mut output = Vec::new();
+        write_summary(&mut output, &summary, false).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "3 commits in 1 of 2 repositories by 1 author\n\
+             4 files changed, 1 insertion(+), 12 deletions(-)\n\
+             \n\
+             Repository  Commits\n\
+             coruscant         3\n\
+             ferrix            0\n\
+             \n\
+             Author                         Commits\n\
+             Luthen Rael <luthen@axis.org>        3\n"
+        );
+
+        let mut output = Vec::new();
+        write_summary(&mut output, &summary, true).unwrap();
+        assert!(String::from_utf8(output).unwrap().ends_with(
+            "\n\
+             Type            Commits\n\
+             feat                  2\n\
+             non-conforming        1\n"
+        ));
+    }
+
+    #[test]
+    fn test_line_format_highlights_title_only() {
+        let dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(dir.path()).unwrap();
+        let repo: Repository = Repository::new(dir.path()).unwrap();
+        let commit = Commit {
+            title: "Fix 2025 bug".to_string(),
+            hash: walrust::commit::CommitHash::new("2025abcdef".to_string()),
+            ..Commit::default()
+        };
+        let highlight = vec![
+            Grep::new("^Fix", false, GrepScope::Message).unwrap(),
+            Grep::new("2025", false, GrepScope::Message).unwrap(),
+        ];
+
+        let line_format = LineFormat {
+            template: None,
+            options: ReportOptions::default(),
+            show_branches: false,
+            highlight: highlight.clone(),
+        };
+        let line = line_format.render(&repo, &commit);
+        assert!(line.starts_with("2025abc "));
+        assert!(line.ends_with(&format!(
+            "{0}Fix{1} {0}2025{1} bug",
+            HIGHLIGHT_START, HIGHLIGHT_END
+        )));
+
+        let line_format = LineFormat {
+            template: Some("{short_hash}: {title}".parse().unwrap()),
+            options: ReportOptions::default(),
+            show_branches: false,
+            highlight,
+        };
+        assert_eq!(
+            line_format.render(&repo, &commit),
+            format!(
+                "2025abc: {0}Fix{1} {0}2025{1} bug",
+                HIGHLIGHT_START, HIGHLIGHT_END
+            )
+        );
+    }
+
+    #[test]
+    fn test_config_ref_selection_conflicts() {
+        let args = vec!["walrust", "-r", ".", "-d", "1", "--branches", "--all"];
+        let result = Config::try_parse_from(args);
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_config_invalid_arguments() {
         let args = vec!["walrust", "-r", "", "-d", "invalid-depth"];
@@ -0,0 +1,355 @@
+//! The `changelog` module renders commit query results as a Markdown changelog.
+//!
+//! Commits are sorted into sections either by their Conventional Commits type
+//! or by a configurable mapping from title prefixes to headings. Each
+//! repository gets its own heading, followed by its non-empty sections.
+//!
+//! # Example
+//! ```rust
+//! use walrust::changelog::{Changelog, ChangelogGrouping, TitlePrefix};
+//!
+//! let changelog = Changelog::new(ChangelogGrouping::Prefix).with_prefixes(vec![
+//!     TitlePrefix::new("Add", "Added"),
+//!     TitlePrefix::new("Fix", "Fixed"),
+//! ]);
+//! assert_eq!(changelog.headings(), vec!["Added", "Fixed", "Other Changes"]);
+//! ```
+
+use crate::commit::Commit;
+use crate::repository::{GitRepository, Repository};
+use crate::{Result, WalrustError};
+use serde::Deserialize;
+use std::fmt;
+use std::io::Write;
+use std::str::FromStr;
+
+/// The heading of breaking changes when grouping by type.
+pub const BREAKING_HEADING: &str = "Breaking Changes";
+
+/// The heading of commits that match no other section.
+pub const OTHER_HEADING: &str = "Other Changes";
+
+/// The Conventional Commits types with their own section, in changelog order.
+/// Other types are listed under [`OTHER_HEADING`].
+const TYPE_HEADINGS: [(&str, &str); 6] = [
+    ("feat", "Features"),
+    ("fix", "Bug Fixes"),
+    ("perf", "Performance Improvements"),
+    ("revert", "Reverts"),
+    ("docs", "Documentation"),
+    ("refactor", "Code Refactoring"),
+];
+
+/// Selects how commits are sorted into changelog sections.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChangelogGrouping {
+    /// By Conventional Commits type, with breaking changes listed first.
+    #[default]
+    Type,
+    /// By the [`TitlePrefix`] mapping the commit title starts with.
+    Prefix,
+}
+
+impl fmt::Display for ChangelogGrouping {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChangelogGrouping::Type => write!(f, "type"),
+            ChangelogGrouping::Prefix => write!(f, "prefix"),
+        }
+    }
+}
+
+impl FromStr for ChangelogGrouping {
+    type Err = WalrustError;
+
+    /// Parses `"type"` or `"prefix"` into a `ChangelogGrouping`.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "type" => Ok(ChangelogGrouping::Type),
+            "prefix" => Ok(ChangelogGrouping::Prefix),
+            _ => Err(WalrustError::ParseError(format!(
+                "unknown changelog grouping '{}', expected 'type' or 'prefix'",
+                s
+            ))),
+        }
+    }
+}
+
+/// Maps commits whose title starts with a prefix to a changelog heading.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TitlePrefix {
+    /// The title prefix, e.g. `Add` or `[docs]`.
+    pub prefix: String,
+    /// The heading of the section, e.g. `Added`.
+    pub heading: String,
+}
+
+impl TitlePrefix {
+    /// Creates a new `TitlePrefix`.
+    ///
+    /// # Arguments
+    /// - `prefix`: The title prefix.
+    /// - `heading`: The heading of the section.
+    ///
+    /// # Returns
+    /// A new `TitlePrefix` instance.
+    pub fn new(prefix: &str, heading: &str) -> Self {
+        Self {
+            prefix: prefix.to_string(),
+            heading: heading.to_string(),
+        }
+    }
+
+    /// Tests whether a title starts with the prefix.
+    ///
+    /// A prefix ending in a letter or digit must be followed by something else,
+    /// so `Add` matches `Add the radio` but not `Address the crowd`.
+    ///
+    /// # Arguments
+    /// - `title`: The commit title.
+    ///
+    /// # Returns
+    /// `true` if the title starts with the prefix.
+    pub fn matches(&self, title: &str) -> bool {
+        match title.strip_prefix(&self.prefix) {
+            Some(rest) => {
+                !self.prefix.ends_with(|c: char| c.is_alphanumeric())
+                    || !rest.starts_with(|c: char| c.is_alphanumeric())
+            }
+            None => false,
+        }
+    }
+}
+
+/// Renders commits as a Markdown changelog.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Changelog {
+    /// How commits are sorted into sections.
+    pub grouping: ChangelogGrouping,
+    /// The title prefixes used by [`ChangelogGrouping::Prefix`], in order.
+    pub prefixes: Vec<TitlePrefix>,
+}
+
+impl Changelog {
+    /// Creates a new `Changelog` without title prefixes.
+    ///
+    /// # Arguments
+    /// - `grouping`: How commits are sorted into sections.
+    ///
+    /// # Returns
+    /// A new `Changelog` instance.
+    pub fn new(grouping: ChangelogGrouping) -> Self {
+        Self {
+            grouping,
+            prefixes: Vec::new(),
+        }
+    }
+
+    /// Sets the title prefixes used when grouping by prefix.
+    ///
+    /// # Arguments
+    /// - `prefixes`: The prefixes, in the order their sections are written.
+    ///   A commit is listed under the first prefix it matches.
+    ///
+    /// # Returns
+    /// The updated `Changelog`.
+    pub fn with_prefixes(mut self, prefixes: Vec<TitlePrefix>) -> Self {
+        self.prefixes = prefixes;
+        self
+    }
+
+    /// Returns every section heading, in the order sections are written.
+    pub fn headings(&self) -> Vec<&str> {
+        let mut headings: Vec<&str> = match self.grouping {
+            ChangelogGrouping::Type => [BREAKING_HEADING]
+                .into_iter()
+                .chain(TYPE_HEADINGS.iter().map(|(_, heading)| *heading))
+                .collect(),
+            ChangelogGrouping::Prefix => {
+                let mut headings = Vec::new();
+                for prefix in &self.prefixes {
+                    if !headings.contains(&prefix.heading.as_str()) {
+                        headings.push(prefix.heading.as_str());
+                    }
+                }
+                headings
+            }
+        };
+        headings.push(OTHER_HEADING);
+        headings
+    }
+
+    /// Sorts commits into sections.
+    ///
+    /// When grouping by type, breaking changes are listed both under
+    /// [`BREAKING_HEADING`] and under their type.
+    ///
+    /// # Arguments
+    /// - `commits`: The commits to sort.
+    ///
+    /// # Returns
+    /// The non-empty sections in heading order, each with its commits in the
+    /// order given.
+    pub fn sections<'a>(&self, commits: &'a [Commit]) -> Vec<(&str, Vec<&'a Commit>)> {
+        let mut sections: Vec<(&str, Vec<&'a Commit>)> = self
+            .headings()
+            .into_iter()
+            .map(|heading| (heading, Vec::new()))
+            .collect();
+        let mut push = |heading: &str, commit: &'a Commit| {
+            if let Some((_, commits)) = sections.iter_mut().find(|(h, _)| *h == heading) {
+                commits.push(commit);
+            }
+        };
+
+        for commit in commits {
+            match self.grouping {
+                ChangelogGrouping::Type => match commit.conventional() {
+                    Ok(conventional) => {
+                        if conventional.breaking {
+                            push(BREAKING_HEADING, commit);
+                        }
+                        let heading = TYPE_HEADINGS
+                            .iter()
+                            .find(|(kind, _)| *kind == conventional.kind)
+                            .map_or(OTHER_HEADING, |(_, heading)| heading);
+                        push(heading, commit);
+                    }
+                    Err(_) => push(OTHER_HEADING, commit),
+                },
+                ChangelogGrouping::Prefix => {
+                    let heading = self
+                        .prefixes
+                        .iter()
+                        .find(|prefix| prefix.matches(&commit.title))
+                        .map_or(OTHER_HEADING, |prefix| prefix.heading.as_str());
+                    push(heading, commit);
+                }
+            }
+        }
+        sections.retain(|(_, commits)| !commits.is_empty());
+        sections
+    }
+
+    /// Renders one commit as a changelog entry, without the leading `- `.
+    ///
+    /// Conventional commits are rendered as their description, preceded by
+    /// their scope in bold when grouping by type. Other commits are rendered as
+    /// their title. Every entry ends with the short hash in parentheses.
+    ///
+    /// # Arguments
+    /// - `commit`: The commit to render.
+    ///
+    /// # Returns
+    /// The entry text.
+    pub fn entry(&self, commit: &Commit) -> String {
+        let text = match (self.grouping, commit.conventional()) {
+            (ChangelogGrouping::Type, Ok(conventional)) => match conventional.scope {
+                Some(scope) => format!("**{}:** {}", scope, conventional.description),
+                None => conventional.description,
+            },
+            _ => commit.title.clone(),
+        };
+        format!("{} ({})", text, commit.hash.short)
+    }
+
+    /// Writes the changelog of query results as Markdown.
+    ///
+    /// Repositories without commits are left out. If no repository has
+    /// commits, the changelog says so.
+    ///
+    /// # Arguments
+    /// - `writer`: The destination for the output.
+    /// - `title`: The top-level heading, e.g. `Changelog v1.2.0..v1.3.0`.
+    /// - `results`: The repositories and their commits.
+    ///
+    /// # Errors
+    /// Returns an error if writing to `writer` fails.
+    pub fn write_markdown<G: GitRepository, W: Write>(
+        &self,
+        mut writer: W,
+        title: &str,
+        results: &[(Repository<G>, Vec<Commit>)],
+    ) -> Result<()> {
+        writeln!(writer, "# {}", title)?;
+        let mut empty = true;
+        for (repo, commits) in results {
+            if commits.is_empty() {
+                continue;
+            }
+            empty = false;
+            writeln!(writer)?;
+            writeln!(writer, "## {}", repo.name)?;
+            for (heading, commits) in self.sections(commits) {
+                writeln!(writer)?;
+                writeln!(writer, "### {}", heading)?;
+                writeln!(writer)?;
+                for commit in commits {
+                    writeln!(writer, "- {}", self.entry(commit))?;
+                }
+            }
+        }
+        if empty {
+            writeln!(writer)?;
+            writeln!(writer, "No changes.")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grouping_from_str() {
+        for grouping in [ChangelogGrouping::Type, ChangelogGrouping::Prefix] {
+            assert_eq!(
+                grouping.to_string().parse::<ChangelogGrouping>().unwrap(),
+                grouping
+            );
+        }
+        assert!("scope".parse::<ChangelogGrouping>().is_err());
+    }
+
+    #[test]
+    fn test_title_prefix_matches() {
+        let add = TitlePrefix::new("Add", "Added");
+        assert!(add.matches("Add the radio"));
+        assert!(add.matches("Add: the radio"));
+        assert!(add.matches("Add"));
+        assert!(!add.matches("Address the crowd"));
+        assert!(!add.matches("add the radio"));
+
+        let docs = TitlePrefix::new("[docs]", "Documentation");
+        assert!(docs.matches("[docs]Fix typo"));
+        assert!(!docs.matches("docs: Fix typo"));
+    }
+
+    #[test]
+    fn test_headings() {
+        assert_eq!(
+            Changelog::new(ChangelogGrouping::Type).headings(),
+            vec![
+                "Breaking Changes",
+                "Features",
+                "Bug Fixes",
+                "Performance Improvements",
+                "Reverts",
+                "Documentation",
+                "Code Refactoring",
+                "Other Changes",
+            ]
+        );
+        let changelog = Changelog::new(ChangelogGrouping::Prefix).with_prefixes(vec![
+            TitlePrefix::new("Fix", "Fixed"),
+            TitlePrefix::new("Add", "Added"),
+            TitlePrefix::new("Repair", "Fixed"),
+        ]);
+        assert_eq!(
+            changelog.headings(),
+            vec!["Fixed", "Added", "Other Changes"]
+        );
+    }
+}
@@ -1,13 +1,18 @@
-use chrono::{DateTime, Utc};
+use crate::conventional::ConventionalCommit;
+use crate::WalrustError;
+use chrono::{DateTime, FixedOffset, Local, Utc};
+use std::fmt;
+use std::str::FromStr;
 
-/// Represents the author of a commit.
+/// Represents the author or committer of a commit.
 ///
-/// This struct encapsulates the author's name and email address.
+/// This struct encapsulates the person's name and email address.
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
 pub struct CommitAuthor {
-    /// The name of the author.
+    /// The name of the author or committer.
     pub name: String,
-    /// The email address of the author.
+    /// The email address of the author or committer.
     pub email: String,
 }
 
@@ -15,34 +20,29 @@ impl CommitAuthor {
     /// Creates a new `CommitAuthor` instance.
     ///
     /// # Arguments
-    /// - `name`: The name of the author.
-    /// - `email`: The email address of the author.
+    /// - `name`: The name of the author or committer.
+    /// - `email`: The email address of the author or committer.
     ///
     /// # Returns
     /// A new `CommitAuthor` instance.
     pub fn new(name: String, email: String) -> Self {
         Self { name, email }
     }
+}
 
-    /// Returns the author in the format `"Name <email>"`.
-    ///
-    /// This method checks if both the name and email are empty. If they are, it returns an empty string.
+impl fmt::Display for CommitAuthor {
+    /// Formats the author as `"Name <email>"`.
     ///
-    /// # Returns
-    /// A string representing the author in the format `"Name <email>"`, or an empty string if both fields are empty.
-    pub fn to_string(&self) -> String {
+    /// If both the name and email are empty, nothing is written.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let email = match self.email.is_empty() {
             true => String::new(),
             false => format!("<{}>", self.email),
         };
-        let name = match self.name.is_empty() {
-            true => String::new(),
-            false => format!("{}", self.name),
-        };
-        // If both name and email are empty, return an empty string
-        match (name.is_empty(), email.is_empty()) {
-            (true, true) => String::new(),
-            _ => format!("{} {}", name, email).trim().to_string(),
+        // If both name and email are empty, write an empty string
+        match (self.name.is_empty(), email.is_empty()) {
+            (true, true) => Ok(()),
+            _ => write!(f, "{}", format!("{} {}", self.name, email).trim()),
         }
     }
 }
@@ -51,6 +51,7 @@ impl CommitAuthor {
 ///
 /// This struct encapsulates both the short and full representations of a commit hash.
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
 pub struct CommitHash {
     /// The short version of the hash (e.g., first 7 characters).
     pub short: String,
@@ -74,23 +75,211 @@ impl CommitHash {
     }
 }
 
+/// Selects which of a commit's two dates to use.
+///
+/// Git records when a change was originally written (the author date) and when
+/// it was last applied (the committer date). Rebases and cherry-picks update
+/// only the committer date.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DateField {
+    /// The date the change was originally authored.
+    Author,
+    /// The date the commit was last created or rewritten.
+    #[default]
+    Committer,
+}
+
+impl fmt::Display for DateField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DateField::Author => write!(f, "author"),
+            DateField::Committer => write!(f, "committer"),
+        }
+    }
+}
+
+impl FromStr for DateField {
+    type Err = WalrustError;
+
+    /// Parses `"author"` or `"committer"` into a `DateField`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "author" => Ok(DateField::Author),
+            "committer" => Ok(DateField::Committer),
+            _ => Err(WalrustError::ParseError(format!(
+                "unknown date field '{}', expected 'author' or 'committer'",
+                s
+            ))),
+        }
+    }
+}
+
+/// Selects the timezone commit dates are displayed in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DisplayTimezone {
+    /// The timezone recorded in the commit by its author or committer.
+    #[default]
+    Original,
+    /// The local timezone of the machine running the query.
+    Local,
+    /// Coordinated Universal Time.
+    Utc,
+}
+
+impl DisplayTimezone {
+    /// Formats a commit date as RFC 3339 in this timezone.
+    ///
+    /// # Arguments
+    /// - `date`: The date to format.
+    ///
+    /// # Returns
+    /// The formatted date, e.g. `2025-05-04T11:00:00-07:00`.
+    pub fn format(&self, date: &DateTime<FixedOffset>) -> String {
+        self.convert(date).to_rfc3339()
+    }
+
+    /// Converts a commit date to this timezone.
+    ///
+    /// # Arguments
+    /// - `date`: The date to convert.
+    ///
+    /// # Returns
+    /// The same instant with this timezone's offset.
+    pub fn convert(&self, date: &DateTime<FixedOffset>) -> DateTime<FixedOffset> {
+        match self {
+            DisplayTimezone::Original => *date,
+            DisplayTimezone::Local => date.with_timezone(&Local).fixed_offset(),
+            DisplayTimezone::Utc => date.with_timezone(&Utc).fixed_offset(),
+        }
+    }
+}
+
+impl fmt::Display for DisplayTimezone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DisplayTimezone::Original => write!(f, "original"),
+            DisplayTimezone::Local => write!(f, "local"),
+            DisplayTimezone::Utc => write!(f, "utc"),
+        }
+    }
+}
+
+impl FromStr for DisplayTimezone {
+    type Err = WalrustError;
+
+    /// Parses `"original"`, `"local"` or `"utc"` into a `DisplayTimezone`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "original" => Ok(DisplayTimezone::Original),
+            "local" => Ok(DisplayTimezone::Local),
+            "utc" => Ok(DisplayTimezone::Utc),
+            _ => Err(WalrustError::ParseError(format!(
+                "unknown timezone '{}', expected 'original', 'local' or 'utc'",
+                s
+            ))),
+        }
+    }
+}
+
+/// The lines one file gained and lost in a commit.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+pub struct FileStats {
+    /// The path of the file, relative to the repository root.
+    pub path: String,
+    /// The number of lines added.
+    pub insertions: usize,
+    /// The number of lines removed.
+    pub deletions: usize,
+}
+
+/// The size of a commit's changes, file by file.
+///
+/// Binary files are listed with no insertions or deletions.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+pub struct DiffStats {
+    /// The total number of lines added.
+    pub insertions: usize,
+    /// The total number of lines removed.
+    pub deletions: usize,
+    /// The changed files, in the order Git reports them.
+    pub files: Vec<FileStats>,
+}
+
+impl DiffStats {
+    /// Creates a new `DiffStats` from its changed files.
+    ///
+    /// # Arguments
+    /// - `files`: The changed files.
+    ///
+    /// # Returns
+    /// The statistics, with the totals summed over `files`.
+    pub fn new(files: Vec<FileStats>) -> Self {
+        Self {
+            insertions: files.iter().map(|f| f.insertions).sum(),
+            deletions: files.iter().map(|f| f.deletions).sum(),
+            files,
+        }
+    }
+
+    /// Returns the number of files changed.
+    pub fn files_changed(&self) -> usize {
+        self.files.len()
+    }
+}
+
+impl fmt::Display for DiffStats {
+    /// Formats the totals like `git diff --shortstat`, e.g.
+    /// `2 files changed, 5 insertions(+), 1 deletion(-)`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let plural = |count: usize| if count == 1 { "" } else { "s" };
+        write!(
+            f,
+            "{} file{} changed, {} insertion{}(+), {} deletion{}(-)",
+            self.files_changed(),
+            plural(self.files_changed()),
+            self.insertions,
+            plural(self.insertions),
+            self.deletions,
+            plural(self.deletions)
+        )
+    }
+}
+
 /// Represents a Git commit.
 ///
 /// This struct encapsulates metadata about a commit, including its title, author,
-/// date, message, and hash.
+/// committer, their dates, message, hash, parents, and the branches that contain it.
 #[derive(Debug, Clone, Default)]
 #[cfg_attr(test, derive(PartialEq))]
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
 pub struct Commit {
     /// The title of the commit message.
     pub title: String,
     /// The author of the commit.
     pub author: CommitAuthor,
-    /// The date and time of the commit.
-    pub commit_date: DateTime<Utc>,
+    /// The date and time the commit was authored, in the author's timezone.
+    pub author_date: DateTime<FixedOffset>,
+    /// The committer of the commit.
+    pub committer: CommitAuthor,
+    /// The date and time the commit was committed, in the committer's timezone.
+    pub committer_date: DateTime<FixedOffset>,
     /// The full commit message body.
     pub message: String,
     /// The hash of the commit.
     pub hash: CommitHash,
+    /// The names of the queried references that contain this commit.
+    pub branches: Vec<String>,
+    /// The full hashes of the commit's parents. Merge commits have more than one.
+    pub parents: Vec<String>,
+    /// The paths changed relative to the first parent. Only populated when a
+    /// query needs them, e.g. to filter by path.
+    pub paths: Vec<String>,
+    /// The lines changed relative to the first parent. Only populated when a
+    /// query asks for them, see
+    /// [`CommitQuery::with_stats`](crate::query::CommitQuery::with_stats).
+    pub stats: Option<DiffStats>,
 }
 
 impl Commit {
@@ -99,7 +288,9 @@ impl Commit {
     /// # Arguments
     /// - `title`: The title of the commit message.
     /// - `author`: The author of the commit.
-    /// - `commit_date`: The date and time when the commit was created.
+    /// - `author_date`: The date and time when the commit was authored.
+    /// - `committer`: The committer of the commit.
+    /// - `committer_date`: The date and time when the commit was committed.
     /// - `message`: The full commit message body.
     /// - `hash`: The hash of the commit.
     ///
@@ -108,16 +299,49 @@ impl Commit {
     pub fn new(
         title: String,
         author: CommitAuthor,
-        commit_date: DateTime<Utc>,
+        author_date: DateTime<FixedOffset>,
+        committer: CommitAuthor,
+        committer_date: DateTime<FixedOffset>,
         message: String,
         hash: CommitHash,
     ) -> Commit {
         Commit {
             title,
             author,
-            commit_date,
+            author_date,
+            committer,
+            committer_date,
             message,
             hash,
+            branches: Vec::new(),
+            parents: Vec::new(),
+            paths: Vec::new(),
+            stats: None,
+        }
+    }
+
+    /// Parses the commit as a Conventional Commit.
+    ///
+    /// # Returns
+    /// The type, scope, breaking flag, description and footers of the commit.
+    ///
+    /// # Errors
+    /// Returns a `ParseError` if the title does not follow Conventional Commits.
+    pub fn conventional(&self) -> Result<ConventionalCommit, WalrustError> {
+        ConventionalCommit::parse(&self.title, &self.message)
+    }
+
+    /// Returns the author or committer date of the commit.
+    ///
+    /// # Arguments
+    /// - `field`: Which of the two dates to return.
+    ///
+    /// # Returns
+    /// The selected date, in the timezone it was recorded in.
+    pub fn date(&self, field: DateField) -> DateTime<FixedOffset> {
+        match field {
+            DateField::Author => self.author_date,
+            DateField::Committer => self.committer_date,
         }
     }
 }
@@ -125,13 +349,15 @@ impl Commit {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use chrono::{DateTime, TimeZone, Utc};
+    use chrono::{DateTime, FixedOffset, TimeZone};
     use lazy_static::lazy_static;
 
     lazy_static! {
         /// A static test commit date for use in tests.
-        static ref TEST_COMMIT_DATE: DateTime<Utc> =
-            Utc.with_ymd_and_hms(2025, 5, 4, 11, 0, 0).unwrap();
+        static ref TEST_COMMIT_DATE: DateTime<FixedOffset> = FixedOffset::west_opt(7 * 3600)
+            .unwrap()
+            .with_ymd_and_hms(2025, 5, 4, 11, 0, 0)
+            .unwrap();
     }
 
     #[test]
@@ -142,9 +368,12 @@ mod tests {
         let commit = Commit::default();
         assert_eq!(commit.title, "");
         assert_eq!(commit.author.to_string(), "");
-        assert_eq!(commit.commit_date, DateTime::<Utc>::default());
+        assert_eq!(commit.author_date, DateTime::<FixedOffset>::default());
+        assert_eq!(commit.committer.to_string(), "");
+        assert_eq!(commit.committer_date, DateTime::<FixedOffset>::default());
         assert_eq!(commit.message, "");
         assert_eq!(commit.hash.full, "");
+        assert!(commit.branches.is_empty());
     }
 
     #[test]
@@ -157,6 +386,11 @@ mod tests {
             "Luthen Rael".to_string(),
             "luthen.rael@totallynotarebel.com".to_string(),
         );
+        let committer = CommitAuthor::new(
+            "Kleya Marki".to_string(),
+            "kleya.marki@totallynotarebel.com".to_string(),
+        );
+        let committer_date = *TEST_COMMIT_DATE + chrono::Duration::hours(1);
         let message = "Initial commit message".to_string();
         let hash_full = "abc123def4567890".to_string();
         let hash = CommitHash::new(hash_full.clone());
@@ -164,13 +398,17 @@ mod tests {
             title.clone(),
             author.clone(),
             *TEST_COMMIT_DATE,
+            committer.clone(),
+            committer_date,
             message.clone(),
             hash.clone(),
         );
 
         assert_eq!(commit.title, title);
         assert_eq!(commit.author, author);
-        assert_eq!(commit.commit_date, *TEST_COMMIT_DATE);
+        assert_eq!(commit.author_date, *TEST_COMMIT_DATE);
+        assert_eq!(commit.committer, committer);
+        assert_eq!(commit.committer_date, committer_date);
         assert_eq!(commit.message, message);
         assert_eq!(commit.hash, hash);
     }
@@ -244,15 +482,121 @@ mod tests {
         let commit = Commit::new(
             "".to_string(),
             CommitAuthor::default(),
-            DateTime::<Utc>::default(),
+            DateTime::<FixedOffset>::default(),
+            CommitAuthor::default(),
+            DateTime::<FixedOffset>::default(),
             "".to_string(),
             CommitHash::default(),
         );
 
         assert_eq!(commit.title, "");
         assert_eq!(commit.author, CommitAuthor::default());
-        assert_eq!(commit.commit_date, DateTime::<Utc>::default());
+        assert_eq!(commit.author_date, DateTime::<FixedOffset>::default());
+        assert_eq!(commit.committer, CommitAuthor::default());
+        assert_eq!(commit.committer_date, DateTime::<FixedOffset>::default());
         assert_eq!(commit.message, "");
         assert_eq!(commit.hash, CommitHash::default());
     }
+
+    #[test]
+    /// Tests the `date` method of the `Commit` struct.
+    ///
+    /// This test verifies that the author and committer dates can be selected independently.
+    fn test_commit_date_field() {
+        let committer_date = *TEST_COMMIT_DATE + chrono::Duration::days(3);
+        let commit = Commit {
+            author_date: *TEST_COMMIT_DATE,
+            committer_date,
+            ..Commit::default()
+        };
+        assert_eq!(commit.date(DateField::Author), *TEST_COMMIT_DATE);
+        assert_eq!(commit.date(DateField::Committer), committer_date);
+    }
+
+    #[test]
+    /// Tests parsing and formatting of the `DateField` enum.
+    ///
+    /// This test verifies that `DateField` round-trips through strings and rejects unknown values.
+    fn test_date_field_from_str() {
+        assert_eq!("author".parse::<DateField>().unwrap(), DateField::Author);
+        assert_eq!(
+            "committer".parse::<DateField>().unwrap(),
+            DateField::Committer
+        );
+        assert_eq!(DateField::Author.to_string(), "author");
+        assert_eq!(DateField::Committer.to_string(), "committer");
+        assert!("commit".parse::<DateField>().is_err());
+    }
+
+    #[test]
+    /// Tests that commit dates keep their original offset.
+    ///
+    /// This test verifies that dates in different timezones still compare by their true instant.
+    fn test_commit_date_keeps_offset() {
+        let commit = Commit {
+            author_date: *TEST_COMMIT_DATE,
+            ..Commit::default()
+        };
+        let utc = Utc.with_ymd_and_hms(2025, 5, 4, 18, 0, 0).unwrap();
+        assert_eq!(commit.author_date.offset().utc_minus_local(), 7 * 3600);
+        assert_eq!(commit.author_date, utc);
+        assert!(commit.author_date < utc + chrono::Duration::seconds(1));
+        assert!(commit.author_date > utc - chrono::Duration::seconds(1));
+    }
+
+    #[test]
+    /// Tests the `format` method of the `DisplayTimezone` enum.
+    ///
+    /// This test verifies that dates are rendered in the original offset or in UTC.
+    fn test_display_timezone_format() {
+        assert_eq!(
+            DisplayTimezone::Original.format(&TEST_COMMIT_DATE),
+            "2025-05-04T11:00:00-07:00"
+        );
+        assert_eq!(
+            DisplayTimezone::Utc.format(&TEST_COMMIT_DATE),
+            "2025-05-04T18:00:00+00:00"
+        );
+        assert_eq!(
+            DisplayTimezone::Local.format(&TEST_COMMIT_DATE),
+            TEST_COMMIT_DATE.with_timezone(&Local).to_rfc3339()
+        );
+    }
+
+    #[test]
+    /// Tests parsing and formatting of the `DisplayTimezone` enum.
+    ///
+    /// This test verifies that `DisplayTimezone` round-trips through strings and rejects unknown values.
+    fn test_display_timezone_from_str() {
+        for tz in [
+            DisplayTimezone::Original,
+            DisplayTimezone::Local,
+            DisplayTimezone::Utc,
+        ] {
+            assert_eq!(tz.to_string().parse::<DisplayTimezone>().unwrap(), tz);
+        }
+        assert!("author".parse::<DisplayTimezone>().is_err());
+    }
+
+    #[test]
+    /// Tests that `DiffStats` sums its files and formats like `git diff --shortstat`.
+    fn test_diff_stats() {
+        let file = |path: &str, insertions, deletions| FileStats {
+            path: path.to_string(),
+            insertions,
+            deletions,
+        };
+        let stats = DiffStats::new(vec![file("src/lib.rs", 4, 1), file("README.md", 1, 0)]);
+        assert_eq!(stats.files_changed(), 2);
+        assert_eq!(stats.insertions, 5);
+        assert_eq!(stats.deletions, 1);
+        assert_eq!(
+            stats.to_string(),
+            "2 files changed, 5 insertions(+), 1 deletion(-)"
+        );
+        assert_eq!(
+            DiffStats::new(vec![file("logo.png", 0, 0)]).to_string(),
+            "1 file changed, 0 insertions(+), 0 deletions(-)"
+        );
+    }
 }
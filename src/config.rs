@@ -0,0 +1,584 @@
+//! The `config` module loads the user configuration files.
+//!
+//! Configuration is TOML, read from `$XDG_CONFIG_HOME/walrust/config.toml` (or
+//! `~/.config/walrust/config.toml` when `XDG_CONFIG_HOME` is not set) and from
+//! a repository-local `.walrust.toml` in the current directory or its closest
+//! ancestor that has one. Values in the local file override the user file.
+//! Missing files are treated as an empty configuration.
+//!
+//! The top-level settings apply by default, and named profiles under
+//! `[profiles.<name>]` override them when selected.
+//!
+//! # Example
+//! ```toml
+//! roots = ["~/src", "~/work"]
+//! depth = 3
+//! identities = ["email:me@work.example", "email:me@home.example"]
+//! format = "text"
+//! exclude = ["archive", "*.bak"]
+//! follow_symlinks = true
+//!
+//! [profiles.work]
+//! roots = ["~/work"]
+//! include = ["clients/*"]
+//! submodules = true
+//! identities = ["email:me@work.example"]
+//! format = "csv"
+//!
+//! [templates]
+//! oneline = "{short_hash} {title}"
+//! standup = "{repo}: {title} ({author_name})"
+//!
+//! [[changelog.prefixes]]
+//! prefix = "Add"
+//! heading = "Added"
+//! ```
+
+use crate::author::AuthorFilter;
+use crate::changelog::TitlePrefix;
+use crate::{Result, WalrustError};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The name of the repository-local configuration file.
+pub const LOCAL_FILE_NAME: &str = ".walrust.toml";
+
+/// The contents of a configuration file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConfigFile {
+    /// The directories to search for repositories.
+    pub roots: Vec<PathBuf>,
+    /// The maximum recursion depth for directory scanning.
+    pub depth: Option<usize>,
+    /// Patterns of the repositories to report, see
+    /// [`DiscoveryFilter`](crate::repository_locator::DiscoveryFilter).
+    pub include: Vec<String>,
+    /// Patterns of the directories not to search.
+    pub exclude: Vec<String>,
+    /// Whether the default excludes, such as `node_modules`, also apply.
+    pub default_excludes: Option<bool>,
+    /// Whether to keep searching inside the repositories found.
+    pub nested: Option<bool>,
+    /// Whether to report the submodules of the repositories found.
+    pub submodules: Option<bool>,
+    /// Whether to search directories reached through symbolic links.
+    pub follow_symlinks: Option<bool>,
+    /// Author patterns for "any of my identities", see [`crate::author`].
+    pub identities: Vec<String>,
+    /// The default output format, such as `text` or `csv`.
+    pub format: Option<String>,
+    /// Named sets of settings that override the top-level ones.
+    pub profiles: BTreeMap<String, Profile>,
+    /// Named output templates, see [`crate::template::Template`].
+    pub templates: BTreeMap<String, String>,
+    /// Settings for `walrust changelog`.
+    pub changelog: ChangelogConfig,
+}
+
+/// The settings a profile can override, and the effective settings of a
+/// configuration once a profile is applied.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Profile {
+    /// The directories to search for repositories.
+    pub roots: Vec<PathBuf>,
+    /// The maximum recursion depth for directory scanning.
+    pub depth: Option<usize>,
+    /// Patterns of the repositories to report, see
+    /// [`DiscoveryFilter`](crate::repository_locator::DiscoveryFilter).
+    pub include: Vec<String>,
+    /// Patterns of the directories not to search.
+    pub exclude: Vec<String>,
+    /// Whether the default excludes, such as `node_modules`, also apply.
+    pub default_excludes: Option<bool>,
+    /// Whether to keep searching inside the repositories found.
+    pub nested: Option<bool>,
+    /// Whether to report the submodules of the repositories found.
+    pub submodules: Option<bool>,
+    /// Whether to search directories reached through symbolic links.
+    pub follow_symlinks: Option<bool>,
+    /// Author patterns for "any of my identities", see [`crate::author`].
+    pub identities: Vec<String>,
+    /// The default output format, such as `text` or `csv`.
+    pub format: Option<String>,
+}
+
+impl Profile {
+    /// Parses the identities into an author filter.
+    ///
+    /// # Errors
+    /// Returns a `ParseError` if any identity is not a valid author pattern.
+    pub fn identities(&self) -> Result<AuthorFilter> {
+        AuthorFilter::parse(&self.identities)
+    }
+
+    /// Overrides these settings with every setting `other` gives.
+    fn merge(&mut self, other: Profile) {
+        if !other.roots.is_empty() {
+            self.roots = other.roots;
+        }
+        if other.depth.is_some() {
+            self.depth = other.depth;
+        }
+        if !other.include.is_empty() {
+            self.include = other.include;
+        }
+        if !other.exclude.is_empty() {
+            self.exclude = other.exclude;
+        }
+        if other.default_excludes.is_some() {
+            self.default_excludes = other.default_excludes;
+        }
+        if other.nested.is_some() {
+            self.nested = other.nested;
+        }
+        if other.submodules.is_some() {
+            self.submodules = other.submodules;
+        }
+        if other.follow_symlinks.is_some() {
+            self.follow_symlinks = other.follow_symlinks;
+        }
+        if !other.identities.is_empty() {
+            self.identities = other.identities;
+        }
+        if other.format.is_some() {
+            self.format = other.format;
+        }
+    }
+}
+
+/// The `[changelog]` table of a configuration file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ChangelogConfig {
+    /// The title prefixes used to group changelogs by prefix, in order.
+    pub prefixes: Vec<TitlePrefix>,
+}
+
+impl ConfigFile {
+    /// Parses a configuration from TOML text.
+    ///
+    /// # Errors
+    /// Returns a `ParseError` if the text is not valid TOML or contains
+    /// unknown keys.
+    pub fn parse(text: &str) -> Result<Self> {
+        toml::from_str(text).map_err(|err| WalrustError::ParseError(err.to_string()))
+    }
+
+    /// Loads a configuration from the given file.
+    ///
+    /// A leading `~` in a root stands for the home directory, and relative
+    /// roots are relative to the directory holding the file.
+    ///
+    /// # Errors
+    /// Returns an `IoError` if the file cannot be read, or a `ParseError` if
+    /// its contents are invalid.
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path)?;
+        let mut config = Self::parse(&text)
+            .map_err(|err| WalrustError::ParseError(format!("{}: {}", path.display(), err)))?;
+        let base = path.parent().unwrap_or(Path::new(""));
+        let profiles = config.profiles.values_mut();
+        for root in config
+            .roots
+            .iter_mut()
+            .chain(profiles.flat_map(|profile| profile.roots.iter_mut()))
+        {
+            *root = resolve_path(base, root);
+        }
+        Ok(config)
+    }
+
+    /// Returns the default location of the user configuration file.
+    ///
+    /// # Returns
+    /// The path under `$XDG_CONFIG_HOME`, falling back to `$HOME/.config`, or
+    /// `None` if neither variable is set.
+    pub fn default_path() -> Option<PathBuf> {
+        let base = env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+        Some(base.join("walrust").join("config.toml"))
+    }
+
+    /// Finds the repository-local configuration file.
+    ///
+    /// # Arguments
+    /// - `dir`: The directory to start looking in, usually the current one.
+    ///
+    /// # Returns
+    /// The [`LOCAL_FILE_NAME`] file in `dir` or its closest ancestor that has
+    /// one, if any.
+    pub fn local_path(dir: &Path) -> Option<PathBuf> {
+        dir.ancestors()
+            .map(|dir| dir.join(LOCAL_FILE_NAME))
+            .find(|path| path.is_file())
+    }
+
+    /// Returns the configuration files that exist, in the order they are
+    /// applied: the user file, then the repository-local file.
+    pub fn default_paths() -> Vec<PathBuf> {
+        let local = env::current_dir()
+            .ok()
+            .and_then(|dir| Self::local_path(&dir));
+        Self::default_path()
+            .filter(|path| path.exists())
+            .into_iter()
+            .chain(local)
+            .collect()
+    }
+
+    /// Loads the configuration from the default locations.
+    ///
+    /// # Returns
+    /// The configuration of every file in [`ConfigFile::default_paths`],
+    /// merged in order, or an empty configuration if there are none.
+    ///
+    /// # Errors
+    /// Returns an error if a file exists but cannot be read or parsed.
+    pub fn load_default() -> Result<Self> {
+        let mut config = Self::default();
+        for path in Self::default_paths() {
+            config.merge(Self::load(&path)?);
+        }
+        Ok(config)
+    }
+
+    /// Overrides this configuration with every setting `other` gives.
+    ///
+    /// Settings, templates and changelog prefixes given in `other` replace
+    /// those in `self`. Profiles defined in both are merged setting by setting.
+    ///
+    /// # Arguments
+    /// - `other`: The configuration taking precedence.
+    pub fn merge(&mut self, other: ConfigFile) {
+        let mut defaults = self.defaults();
+        defaults.merge(other.defaults());
+        self.roots = defaults.roots;
+        self.depth = defaults.depth;
+        self.include = defaults.include;
+        self.exclude = defaults.exclude;
+        self.default_excludes = defaults.default_excludes;
+        self.nested = defaults.nested;
+        self.submodules = defaults.submodules;
+        self.follow_symlinks = defaults.follow_symlinks;
+        self.identities = defaults.identities;
+        self.format = defaults.format;
+        for (name, profile) in other.profiles {
+            self.profiles.entry(name).or_default().merge(profile);
+        }
+        self.templates.extend(other.templates);
+        if !other.changelog.prefixes.is_empty() {
+            self.changelog = other.changelog;
+        }
+    }
+
+    /// Returns the effective settings with a profile applied.
+    ///
+    /// # Arguments
+    /// - `name`: The name of the profile, or `None` for the top-level settings.
+    ///
+    /// # Errors
+    /// Returns a `ParseError` listing the known names if there is no profile
+    /// called `name`.
+    pub fn profile(&self, name: Option<&str>) -> Result<Profile> {
+        let mut settings = self.defaults();
+        if let Some(name) = name {
+            let profile = self
+                .profiles
+                .get(name)
+                .ok_or_else(|| unknown("profile", name, self.profiles.keys()))?;
+            settings.merge(profile.clone());
+        }
+        Ok(settings)
+    }
+
+    /// Parses the configured identities into an author filter.
+    ///
+    /// # Errors
+    /// Returns a `ParseError` if any identity is not a valid author pattern.
+    pub fn identities(&self) -> Result<AuthorFilter> {
+        AuthorFilter::parse(&self.identities)
+    }
+
+    /// Looks up a named template.
+    ///
+    /// # Arguments
+    /// - `name`: The name of the template.
+    ///
+    /// # Errors
+    /// Returns a `ParseError` listing the known names if there is no template
+    /// called `name`.
+    pub fn template(&self, name: &str) -> Result<&str> {
+        self.templates
+            .get(name)
+            .map(String::as_str)
+            .ok_or_else(|| unknown("template", name, self.templates.keys()))
+    }
+
+    /// Returns the top-level settings.
+    fn defaults(&self) -> Profile {
+        Profile {
+            roots: self.roots.clone(),
+            depth: self.depth,
+            include: self.include.clone(),
+            exclude: self.exclude.clone(),
+            default_excludes: self.default_excludes,
+            nested: self.nested,
+            submodules: self.submodules,
+            follow_symlinks: self.follow_symlinks,
+            identities: self.identities.clone(),
+            format: self.format.clone(),
+        }
+    }
+}
+
+/// Builds the error for a name missing from the config file, listing the
+/// known names.
+fn unknown<'a>(kind: &str, name: &str, known: impl Iterator<Item = &'a String>) -> WalrustError {
+    let names: Vec<_> = known.map(String::as_str).collect();
+    WalrustError::ParseError(format!(
+        "no {} named '{}' in the config file, known {}s: {}",
+        kind,
+        name,
+        kind,
+        match names.is_empty() {
+            true => "none".to_string(),
+            false => names.join(", "),
+        }
+    ))
+}
+
+/// Expands a leading `~` to the home directory and makes a relative path
+/// relative to `base`.
+fn resolve_path(base: &Path, path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), env::var_os("HOME")) {
+        (Ok(rest), Some(home)) => PathBuf::from(home).join(rest),
+        _ => base.join(path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_templates() {
+        let config = ConfigFile::parse(
+            r#"
+            [templates]
+            oneline = "{short_hash} {title}"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.template("oneline").unwrap(), "{short_hash} {title}");
+    }
+
+    #[test]
+    fn test_parse_empty() {
+        assert_eq!(ConfigFile::parse("").unwrap(), ConfigFile::default());
+    }
+
+    #[test]
+    fn test_parse_unknown_key() {
+        let result = ConfigFile::parse("colour = true");
+        assert!(matches!(result, Err(WalrustError::ParseError(_))));
+    }
+
+    #[test]
+    fn test_missing_template() {
+        let config = ConfigFile::parse("[templates]\noneline = \"{title}\"").unwrap();
+        let err = config.template("standup").unwrap_err();
+        assert!(err.to_string().contains("known templates: oneline"));
+    }
+
+    #[test]
+    fn test_parse_identities() {
+        let config =
+            ConfigFile::parse(r#"identities = ["email:me@work.example", "substr:Me"]"#).unwrap();
+        let filter = config.identities().unwrap();
+        assert_eq!(filter.matchers.len(), 2);
+
+        let config = ConfigFile::parse(r#"identities = ["regex:("]"#).unwrap();
+        assert!(config.identities().is_err());
+    }
+
+    #[test]
+    fn test_parse_changelog_prefixes() {
+        let config = ConfigFile::parse(
+            r#"
+            [[changelog.prefixes]]
+            prefix = "Add"
+            heading = "Added"
+
+            [[changelog.prefixes]]
+            prefix = "Fix"
+            heading = "Fixed"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.changelog.prefixes,
+            vec![
+                TitlePrefix::new("Add", "Added"),
+                TitlePrefix::new("Fix", "Fixed")
+            ]
+        );
+
+        let result = ConfigFile::parse("[[changelog.prefixes]]\nprefix = \"Add\"");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "[templates]\nshort = \"{hash}\"").unwrap();
+        let config = ConfigFile::load(&path).unwrap();
+        assert_eq!(config.template("short").unwrap(), "{hash}");
+    }
+
+    #[test]
+    fn test_parse_profiles() {
+        let config = ConfigFile::parse(
+            r#"
+            roots = ["/src", "/work"]
+            depth = 3
+            identities = ["email:me@home.example"]
+
+            exclude = ["archive"]
+            follow_symlinks = true
+
+            [profiles.work]
+            roots = ["/work"]
+            format = "csv"
+            include = ["clients/*"]
+            default_excludes = false
+            submodules = true
+            "#,
+        )
+        .unwrap();
+
+        let defaults = config.profile(None).unwrap();
+        assert_eq!(
+            defaults.roots,
+            vec![PathBuf::from("/src"), PathBuf::from("/work")]
+        );
+        assert_eq!(defaults.depth, Some(3));
+        assert_eq!(defaults.format, None);
+        assert!(defaults.include.is_empty());
+        assert_eq!(defaults.exclude, vec!["archive"]);
+        assert_eq!(defaults.default_excludes, None);
+        assert_eq!(defaults.submodules, None);
+        assert_eq!(defaults.follow_symlinks, Some(true));
+
+        let work = config.profile(Some("work")).unwrap();
+        assert_eq!(work.roots, vec![PathBuf::from("/work")]);
+        assert_eq!(work.depth, Some(3));
+        assert_eq!(work.identities, vec!["email:me@home.example"]);
+        assert_eq!(work.format.as_deref(), Some("csv"));
+        assert_eq!(work.identities().unwrap().matchers.len(), 1);
+        assert_eq!(work.include, vec!["clients/*"]);
+        assert_eq!(work.exclude, vec!["archive"]);
+        assert_eq!(work.default_excludes, Some(false));
+        assert_eq!(work.submodules, Some(true));
+        assert_eq!(work.nested, None);
+        assert_eq!(work.follow_symlinks, Some(true));
+
+        let err = config.profile(Some("home")).unwrap_err();
+        assert!(err.to_string().contains("known profiles: work"));
+        assert!(ConfigFile::parse(
+            "[profiles.work]
+colour = true"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut config = ConfigFile::parse(
+            r#"
+            roots = ["/src"]
+            depth = 3
+            identities = ["email:me@home.example"]
+
+            [profiles.work]
+            roots = ["/work"]
+            depth = 2
+
+            [templates]
+            oneline = "{title}"
+            "#,
+        )
+        .unwrap();
+        config.merge(
+            ConfigFile::parse(
+                r#"
+                depth = 1
+                format = "json"
+
+                [profiles.work]
+                depth = 4
+
+                [templates]
+                short = "{short_hash}"
+                "#,
+            )
+            .unwrap(),
+        );
+
+        assert_eq!(config.roots, vec![PathBuf::from("/src")]);
+        assert_eq!(config.depth, Some(1));
+        assert_eq!(config.identities, vec!["email:me@home.example"]);
+        assert_eq!(config.format.as_deref(), Some("json"));
+        assert_eq!(config.profiles["work"].roots, vec![PathBuf::from("/work")]);
+        assert_eq!(config.profiles["work"].depth, Some(4));
+        assert_eq!(config.templates.len(), 2);
+    }
+
+    #[test]
+    fn test_load_resolves_roots() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(LOCAL_FILE_NAME);
+        fs::write(
+            &path,
+            "roots = [\".\", \"vendor\", \"/abs\"]\n[profiles.p]\nroots = [\"sub\"]",
+        )
+        .unwrap();
+        let config = ConfigFile::load(&path).unwrap();
+        assert_eq!(
+            config.roots,
+            vec![
+                dir.path().join("."),
+                dir.path().join("vendor"),
+                PathBuf::from("/abs")
+            ]
+        );
+        assert_eq!(config.profiles["p"].roots, vec![dir.path().join("sub")]);
+    }
+
+    #[test]
+    fn test_local_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let nested = dir.path().join("a").join("b");
+        fs::create_dir_all(&nested).unwrap();
+        assert_eq!(ConfigFile::local_path(&nested), None);
+
+        fs::write(dir.path().join(LOCAL_FILE_NAME), "depth = 2").unwrap();
+        assert_eq!(
+            ConfigFile::local_path(&nested),
+            Some(dir.path().join(LOCAL_FILE_NAME))
+        );
+
+        fs::write(nested.join(LOCAL_FILE_NAME), "depth = 1").unwrap();
+        assert_eq!(
+            ConfigFile::local_path(&nested),
+            Some(nested.join(LOCAL_FILE_NAME))
+        );
+    }
+}
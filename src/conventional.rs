@@ -0,0 +1,282 @@
+//! The `conventional` module parses commit messages that follow the
+//! [Conventional Commits](https://www.conventionalcommits.org/) specification.
+//!
+//! A conventional title has the form `type(scope)!: description`, where the
+//! scope and the `!` marking a breaking change are optional. The message may end
+//! with footers such as `BREAKING CHANGE: ...` or `Refs: #123`.
+//!
+//! # Example
+//! ```rust
+//! use walrust::conventional::ConventionalCommit;
+//!
+//! let message = "feat(radio)!: Tune to the rebel frequency\n\nRefs: #42\n";
+//! let commit = ConventionalCommit::parse("feat(radio)!: Tune to the rebel frequency", message).unwrap();
+//! assert_eq!(commit.kind, "feat");
+//! assert_eq!(commit.scope.as_deref(), Some("radio"));
+//! assert!(commit.breaking);
+//! assert_eq!(commit.footer("refs"), Some("#42"));
+//!
+//! assert!(ConventionalCommit::parse("Fix the radio", "Fix the radio").is_err());
+//! ```
+
+use crate::{Result, WalrustError};
+
+/// The footer tokens that mark a breaking change.
+const BREAKING_TOKENS: [&str; 2] = ["BREAKING CHANGE", "BREAKING-CHANGE"];
+
+/// A footer of a conventional commit message, such as `Refs: #123`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Footer {
+    /// The footer token, e.g. `Refs` or `BREAKING CHANGE`.
+    pub token: String,
+    /// The footer value. Values spanning several lines keep their line breaks.
+    pub value: String,
+}
+
+/// The parts of a commit message that follows Conventional Commits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConventionalCommit {
+    /// The commit type, e.g. `feat` or `fix`, in lowercase.
+    pub kind: String,
+    /// The scope given in parentheses after the type, if any.
+    pub scope: Option<String>,
+    /// Whether the commit is marked as a breaking change, either with a `!`
+    /// before the colon or with a `BREAKING CHANGE` footer.
+    pub breaking: bool,
+    /// The description following the colon.
+    pub description: String,
+    /// The footers of the message, in order.
+    pub footers: Vec<Footer>,
+}
+
+impl ConventionalCommit {
+    /// Parses a commit's title and message.
+    ///
+    /// # Arguments
+    /// - `title`: The commit title, e.g. `fix(parser): handle empty input`.
+    /// - `message`: The full commit message, whose final paragraph may hold footers.
+    ///
+    /// # Returns
+    /// The parsed commit.
+    ///
+    /// # Errors
+    /// Returns a `ParseError` describing why the title does not follow
+    /// Conventional Commits.
+    pub fn parse(title: &str, message: &str) -> Result<Self> {
+        let invalid = |reason: &str| {
+            WalrustError::ParseError(format!(
+                "'{}' is not a conventional commit: {}",
+                title, reason
+            ))
+        };
+
+        let (prefix, description) = title
+            .split_once(':')
+            .ok_or_else(|| invalid("missing ':' after the type"))?;
+        let description = description
+            .strip_prefix(' ')
+            .map(str::trim)
+            .filter(|d| !d.is_empty())
+            .ok_or_else(|| invalid("missing description after ': '"))?;
+
+        let (prefix, bang) = match prefix.strip_suffix('!') {
+            Some(prefix) => (prefix, true),
+            None => (prefix, false),
+        };
+        let (kind, scope) = match prefix.split_once('(') {
+            Some((kind, scope)) => {
+                let scope = scope
+                    .strip_suffix(')')
+                    .filter(|s| !s.is_empty() && !s.contains(['(', ')']))
+                    .ok_or_else(|| invalid("invalid scope"))?;
+                (kind, Some(scope.to_string()))
+            }
+            None => (prefix, None),
+        };
+        if kind.is_empty()
+            || !kind.starts_with(|c: char| c.is_ascii_alphabetic())
+            || !kind.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        {
+            return Err(invalid("invalid type"));
+        }
+
+        let footers = parse_footers(message);
+        let breaking = bang
+            || footers
+                .iter()
+                .any(|f| BREAKING_TOKENS.contains(&f.token.as_str()));
+        Ok(ConventionalCommit {
+            kind: kind.to_ascii_lowercase(),
+            scope,
+            breaking,
+            description: description.to_string(),
+            footers,
+        })
+    }
+
+    /// Returns the value of the first footer with the given token.
+    ///
+    /// # Arguments
+    /// - `token`: The footer token, compared regardless of case.
+    ///
+    /// # Returns
+    /// The footer value, if the message has such a footer.
+    pub fn footer(&self, token: &str) -> Option<&str> {
+        self.footers
+            .iter()
+            .find(|f| f.token.eq_ignore_ascii_case(token))
+            .map(|f| f.value.as_str())
+    }
+}
+
+/// Parses the footers out of the final paragraph of a commit message.
+///
+/// The first paragraph is the title and never holds footers. The final
+/// paragraph holds footers if its first line is one; lines that do not start a
+/// new footer continue the value of the previous one.
+fn parse_footers(message: &str) -> Vec<Footer> {
+    let paragraphs: Vec<Vec<&str>> = message
+        .lines()
+        .collect::<Vec<_>>()
+        .split(|line| line.trim().is_empty())
+        .filter(|paragraph| !paragraph.is_empty())
+        .map(|paragraph| paragraph.to_vec())
+        .collect();
+    let last = match paragraphs.split_first() {
+        Some((_, [.., last])) => last,
+        _ => return Vec::new(),
+    };
+    if parse_footer_line(last[0]).is_none() {
+        return Vec::new();
+    }
+
+    let mut footers: Vec<Footer> = Vec::new();
+    for line in last {
+        match (parse_footer_line(line), footers.last_mut()) {
+            (Some((token, value)), _) => footers.push(Footer {
+                token: token.to_string(),
+                value: value.to_string(),
+            }),
+            (None, Some(footer)) => {
+                footer.value.push('\n');
+                footer.value.push_str(line);
+            }
+            (None, None) => {}
+        }
+    }
+    footers
+}
+
+/// Splits a line of the form `token: value` or `token #value` into its token
+/// and value. Tokens are a word of letters, digits and `-`, or `BREAKING CHANGE`.
+fn parse_footer_line(line: &str) -> Option<(&str, &str)> {
+    for token in BREAKING_TOKENS {
+        if let Some(value) = line.strip_prefix(token).and_then(|v| v.strip_prefix(": ")) {
+            return Some((token, value));
+        }
+    }
+    let end = line
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-'))
+        .unwrap_or(line.len());
+    let (token, rest) = line.split_at(end);
+    if token.is_empty() {
+        return None;
+    }
+    if let Some(value) = rest.strip_prefix(": ") {
+        Some((token, value))
+    } else if rest.starts_with(" #") {
+        Some((token, &rest[1..]))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(message: &str) -> Result<ConventionalCommit> {
+        ConventionalCommit::parse(message.lines().next().unwrap_or_default(), message)
+    }
+
+    #[test]
+    fn test_parse_title() {
+        let commit = parse("feat: Add the radio").unwrap();
+        assert_eq!(commit.kind, "feat");
+        assert_eq!(commit.scope, None);
+        assert!(!commit.breaking);
+        assert_eq!(commit.description, "Add the radio");
+        assert!(commit.footers.is_empty());
+
+        let commit = parse("Fix(radio-tower)!: Retune the dish").unwrap();
+        assert_eq!(commit.kind, "fix");
+        assert_eq!(commit.scope.as_deref(), Some("radio-tower"));
+        assert!(commit.breaking);
+        assert_eq!(commit.description, "Retune the dish");
+    }
+
+    #[test]
+    fn test_parse_non_conforming() {
+        for title in [
+            "Fix the radio",
+            "feat:missing space",
+            "feat: ",
+            "feat(): empty scope",
+            "feat(radio: unclosed scope",
+            "feat(a)(b): two scopes",
+            ": no type",
+            "two words: description",
+            "1feat: leading digit",
+        ] {
+            assert!(parse(title).is_err(), "{}", title);
+        }
+    }
+
+    #[test]
+    fn test_parse_footers() {
+        let commit = parse(
+            "fix: Retune the dish\n\
+             \n\
+             The old frequency was compromised.\n\
+             Refs: not a footer, not the last paragraph\n\
+             \n\
+             Reviewed-by: Bix\n\
+             Refs #42\n\
+             BREAKING CHANGE: the dish now points\n\
+             at the moon\n",
+        )
+        .unwrap();
+        assert_eq!(
+            commit.footers,
+            vec![
+                Footer {
+                    token: "Reviewed-by".to_string(),
+                    value: "Bix".to_string(),
+                },
+                Footer {
+                    token: "Refs".to_string(),
+                    value: "#42".to_string(),
+                },
+                Footer {
+                    token: "BREAKING CHANGE".to_string(),
+                    value: "the dish now points\nat the moon".to_string(),
+                },
+            ]
+        );
+        assert!(commit.breaking);
+        assert_eq!(commit.footer("refs"), Some("#42"));
+        assert_eq!(commit.footer("Closes"), None);
+    }
+
+    #[test]
+    fn test_parse_footers_needs_body() {
+        let commit = parse("fix: Refs: #42").unwrap();
+        assert!(commit.footers.is_empty());
+
+        let commit = parse("fix: Retune\n\nThe body is not a footer.\n").unwrap();
+        assert!(commit.footers.is_empty());
+
+        let commit = parse("fix: Retune\n\nBREAKING-CHANGE: new dish\n").unwrap();
+        assert!(commit.breaking);
+    }
+}
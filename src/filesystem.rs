@@ -1,6 +1,27 @@
 use std::fs;
 use std::path::{Path, PathBuf};
 
+/// The metadata of a path needed to walk directories safely.
+///
+/// The device and inode identify the file a path resolves to, so a directory
+/// reached again through a symbolic link can be recognised.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Metadata {
+    /// The device holding the file the path resolves to.
+    pub device: u64,
+    /// The inode of the file the path resolves to, unique on its device.
+    pub inode: u64,
+    /// Whether the path itself is a symbolic link.
+    pub is_symlink: bool,
+}
+
+impl Metadata {
+    /// Returns the device and inode identifying the file.
+    pub fn file_id(&self) -> (u64, u64) {
+        (self.device, self.inode)
+    }
+}
+
 /// A trait to abstract filesystem operations.
 ///
 /// This trait provides an abstraction over common filesystem operations,
@@ -67,6 +88,44 @@ pub trait Filesystem {
     /// # Returns
     /// `true` if the path exists, `false` otherwise.
     fn exists(&self, path: &Path) -> bool;
+
+    /// Returns the canonical, absolute form of a path, with all intermediate
+    /// components normalized.
+    ///
+    /// # Arguments
+    /// - `path`: The path to canonicalize.
+    ///
+    /// # Returns
+    /// A `Result` containing the canonical path.
+    ///
+    /// # Errors
+    /// Returns an error if the path does not exist.
+    fn canonicalize(&self, path: &Path) -> std::io::Result<PathBuf>;
+
+    /// Reads the metadata of a path, following symbolic links to identify
+    /// the file they point at.
+    ///
+    /// # Arguments
+    /// - `path`: The path to read.
+    ///
+    /// # Returns
+    /// A `Result` containing the `Metadata` of the path.
+    ///
+    /// # Errors
+    /// Returns an error if the path, or the target of a symbolic link, does
+    /// not exist.
+    ///
+    /// # Example
+    /// ```rust
+    /// use walrust::filesystem::Filesystem;
+    /// use std::path::Path;
+    ///
+    /// let fs = walrust::filesystem::LocalFilesystem::new();
+    /// let here = fs.metadata(Path::new(".")).unwrap();
+    /// let again = fs.metadata(Path::new("./.")).unwrap();
+    /// assert_eq!(here.file_id(), again.file_id());
+    /// ```
+    fn metadata(&self, path: &Path) -> std::io::Result<Metadata>;
 }
 
 /// A concrete implementation of the `Filesystem` trait that interacts with the local filesystem.
@@ -111,6 +170,43 @@ impl Filesystem for LocalFilesystem {
     fn exists(&self, path: &Path) -> bool {
         path.exists()
     }
+
+    fn canonicalize(&self, path: &Path) -> std::io::Result<PathBuf> {
+        fs::canonicalize(path)
+    }
+
+    fn metadata(&self, path: &Path) -> std::io::Result<Metadata> {
+        let is_symlink = fs::symlink_metadata(path)?.file_type().is_symlink();
+        let (device, inode) = file_id(path)?;
+        Ok(Metadata {
+            device,
+            inode,
+            is_symlink,
+        })
+    }
+}
+
+/// Returns the device and inode of the file a path resolves to.
+#[cfg(unix)]
+fn file_id(path: &Path) -> std::io::Result<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+
+    let metadata = fs::metadata(path)?;
+    Ok((metadata.dev(), metadata.ino()))
+}
+
+/// Returns an identity for the file a path resolves to.
+///
+/// Device and inode numbers are not available on this platform, so the file
+/// is identified by a hash of its canonical path instead.
+#[cfg(not(unix))]
+fn file_id(path: &Path) -> std::io::Result<(u64, u64)> {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    let mut hasher = DefaultHasher::new();
+    fs::canonicalize(path)?.hash(&mut hasher);
+    Ok((0, hasher.finish()))
 }
 
 #[cfg(test)]
@@ -182,4 +278,47 @@ mod tests {
         let non_existent_path = temp_dir.path().join("non_existent");
         assert!(!fs.exists(&non_existent_path));
     }
+
+    #[test]
+    fn test_canonicalize() {
+        let fs = LocalFilesystem::new();
+        let temp_dir = tempdir().unwrap();
+        let canonical = fs.canonicalize(temp_dir.path()).unwrap();
+
+        // Check that `.` and `..` components are resolved
+        std::fs::create_dir(temp_dir.path().join("nested")).unwrap();
+        let indirect = temp_dir.path().join("nested").join("..").join(".");
+        assert_eq!(fs.canonicalize(&indirect).unwrap(), canonical);
+
+        // Check that a non-existent path cannot be canonicalized
+        assert!(fs
+            .canonicalize(&temp_dir.path().join("non_existent"))
+            .is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_metadata() {
+        let fs = LocalFilesystem::new();
+        let temp_dir = tempdir().unwrap();
+        let target = temp_dir.path().join("target");
+        let link = temp_dir.path().join("link");
+        std::fs::create_dir(&target).unwrap();
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+
+        // Check that a link is identified as the directory it points at
+        let direct = fs.metadata(&target).unwrap();
+        let linked = fs.metadata(&link).unwrap();
+        assert!(!direct.is_symlink);
+        assert!(linked.is_symlink);
+        assert_eq!(direct.file_id(), linked.file_id());
+        assert_ne!(
+            direct.file_id(),
+            fs.metadata(temp_dir.path()).unwrap().file_id()
+        );
+
+        // Check that a dangling link has no metadata
+        std::fs::remove_dir(&target).unwrap();
+        assert!(fs.metadata(&link).is_err());
+    }
 }
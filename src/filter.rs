@@ -0,0 +1,467 @@
+//! The `filter` module provides composable predicates over commits.
+//!
+//! A [`CommitFilter`] is attached to a [`CommitQuery`](crate::query::CommitQuery)
+//! and evaluated by the repository for every commit in the query's date range.
+//! Filters combine with [`CommitFilter::and`], [`CommitFilter::or`] and `!`.
+//!
+//! Date ranges inside a filter are also pushed down into the revision walk:
+//! when every matching commit must fall within a narrower window than the
+//! query's own, the walk stops as soon as it leaves that window.
+//!
+//! # Example
+//! ```rust
+//! use walrust::author::AuthorFilter;
+//! use walrust::filter::CommitFilter;
+//!
+//! // Non-merge commits by Bix that mention the radio, or any commit by Maarva
+//! let bix = AuthorFilter::parse(["email:bix@ferrix.org"]).unwrap();
+//! let maarva = AuthorFilter::parse(["email:maarva@ferrix.org"]).unwrap();
+//! let filter = CommitFilter::Author(bix)
+//!     .and(CommitFilter::message("(?i)radio").unwrap())
+//!     .and(!CommitFilter::Merge)
+//!     .or(CommitFilter::Author(maarva));
+//! assert!(!filter.needs_paths());
+//! ```
+
+use crate::author::AuthorFilter;
+use crate::commit::{Commit, DateField};
+use crate::grep::{Grep, GrepScope};
+use crate::pathspec::Pathspec;
+use crate::{Result, WalrustError};
+use chrono::{DateTime, Utc};
+use std::ops::Not;
+
+/// A predicate over commits.
+#[derive(Debug, Clone)]
+pub enum CommitFilter {
+    /// Matches commits whose date is within an inclusive range. A missing
+    /// bound leaves that side of the range open.
+    DateRange {
+        /// Whether the range applies to the author or committer date.
+        field: DateField,
+        /// Inclusive start of the range.
+        since: Option<DateTime<Utc>>,
+        /// Inclusive end of the range.
+        until: Option<DateTime<Utc>>,
+    },
+    /// Matches commits whose author matches the filter.
+    Author(AuthorFilter),
+    /// Matches commits whose committer matches the filter.
+    Committer(AuthorFilter),
+    /// Matches commits whose title or message matches the search.
+    Message(Grep),
+    /// Matches commits that change a path matching the Git pathspec, e.g.
+    /// `src/` or `*.rs`.
+    Path(Pathspec),
+    /// Matches merge commits, i.e. commits with more than one parent.
+    /// [`MergeMode`](crate::query::MergeMode) builds its terms from this.
+    Merge,
+    /// Matches commits whose full hash starts with the prefix, ignoring case.
+    HashPrefix(String),
+    /// Matches commits whose title follows Conventional Commits.
+    Conventional,
+    /// Matches conventional commits of the type, e.g. `feat`, ignoring case.
+    Type(String),
+    /// Matches commits that match every filter. An empty list matches all commits.
+    And(Vec<CommitFilter>),
+    /// Matches commits that match any filter. An empty list matches no commits.
+    Or(Vec<CommitFilter>),
+    /// Matches commits that do not match the filter.
+    Not(Box<CommitFilter>),
+}
+
+impl CommitFilter {
+    /// Creates a filter matching full commit messages against a case-sensitive
+    /// regular expression. Use [`CommitFilter::Message`] with a [`Grep`] for
+    /// other options.
+    ///
+    /// # Errors
+    /// Returns a `ParseError` if the regular expression is invalid.
+    pub fn message(pattern: &str) -> Result<Self> {
+        Grep::new(pattern, false, GrepScope::Message).map(CommitFilter::Message)
+    }
+
+    /// Creates a filter matching commits that change a path.
+    ///
+    /// # Errors
+    /// Returns a `ParseError` if the pathspec is invalid.
+    pub fn path(pathspec: &str) -> Result<Self> {
+        Pathspec::new([pathspec]).map(CommitFilter::Path)
+    }
+
+    /// Creates a filter matching commits by hash prefix.
+    ///
+    /// # Errors
+    /// Returns a `ParseError` if the prefix is empty or not hexadecimal.
+    pub fn hash_prefix(prefix: &str) -> Result<Self> {
+        if prefix.is_empty() || !prefix.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(WalrustError::ParseError(format!(
+                "invalid hash prefix '{}', expected hexadecimal digits",
+                prefix
+            )));
+        }
+        Ok(CommitFilter::HashPrefix(prefix.to_ascii_lowercase()))
+    }
+
+    /// Creates a filter matching conventional commits of a type.
+    ///
+    /// # Errors
+    /// Returns a `ParseError` if the type is empty or contains characters other
+    /// than letters, digits and `-`.
+    pub fn conventional_type(kind: &str) -> Result<Self> {
+        if kind.is_empty() || !kind.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+            return Err(WalrustError::ParseError(format!(
+                "invalid commit type '{}', expected letters, digits and '-'",
+                kind
+            )));
+        }
+        Ok(CommitFilter::Type(kind.to_ascii_lowercase()))
+    }
+
+    /// Creates a filter matching conventional commits of any of the types.
+    ///
+    /// Commits that do not follow Conventional Commits also match, so they can
+    /// be reported rather than silently dropped. Use
+    /// [`split_non_conforming`](crate::summary::split_non_conforming) to set
+    /// them apart from the commits of the selected types.
+    ///
+    /// # Errors
+    /// Returns a `ParseError` if any type is invalid, see
+    /// [`CommitFilter::conventional_type`].
+    pub fn types<I, S>(kinds: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let types = kinds
+            .into_iter()
+            .map(|kind| CommitFilter::conventional_type(kind.as_ref()))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(CommitFilter::Or(types).or(!CommitFilter::Conventional))
+    }
+
+    /// Combines this filter with another, matching commits that match both.
+    pub fn and(self, other: CommitFilter) -> CommitFilter {
+        match self {
+            CommitFilter::And(mut filters) => {
+                filters.push(other);
+                CommitFilter::And(filters)
+            }
+            filter => CommitFilter::And(vec![filter, other]),
+        }
+    }
+
+    /// Combines this filter with another, matching commits that match either.
+    pub fn or(self, other: CommitFilter) -> CommitFilter {
+        match self {
+            CommitFilter::Or(mut filters) => {
+                filters.push(other);
+                CommitFilter::Or(filters)
+            }
+            filter => CommitFilter::Or(vec![filter, other]),
+        }
+    }
+
+    /// Tests a commit against the filter.
+    ///
+    /// Path filters only match commits whose [`Commit::paths`] were populated,
+    /// see [`CommitFilter::needs_paths`].
+    ///
+    /// # Arguments
+    /// - `commit`: The commit to test.
+    ///
+    /// # Returns
+    /// `true` if the commit matches.
+    pub fn matches(&self, commit: &Commit) -> bool {
+        match self {
+            CommitFilter::DateRange {
+                field,
+                since,
+                until,
+            } => {
+                let date = commit.date(*field);
+                since.is_none_or(|since| date >= since) && until.is_none_or(|until| date <= until)
+            }
+            CommitFilter::Author(filter) => filter.matches(&commit.author),
+            CommitFilter::Committer(filter) => filter.matches(&commit.committer),
+            CommitFilter::Message(grep) => grep.is_match(commit),
+            CommitFilter::Path(pathspec) => pathspec.matches_any(&commit.paths),
+            CommitFilter::Merge => commit.parents.len() > 1,
+            CommitFilter::Conventional => commit.conventional().is_ok(),
+            CommitFilter::Type(kind) => commit.conventional().is_ok_and(|c| c.kind == *kind),
+            CommitFilter::HashPrefix(prefix) => {
+                commit.hash.full.to_ascii_lowercase().starts_with(prefix)
+            }
+            CommitFilter::And(filters) => filters.iter().all(|f| f.matches(commit)),
+            CommitFilter::Or(filters) => filters.iter().any(|f| f.matches(commit)),
+            CommitFilter::Not(filter) => !filter.matches(commit),
+        }
+    }
+
+    /// Returns `true` if evaluating the filter requires the paths each commit
+    /// changes, which are expensive to compute.
+    pub fn needs_paths(&self) -> bool {
+        match self {
+            CommitFilter::Path(_) => true,
+            CommitFilter::And(filters) | CommitFilter::Or(filters) => {
+                filters.iter().any(|f| f.needs_paths())
+            }
+            CommitFilter::Not(filter) => filter.needs_paths(),
+            _ => false,
+        }
+    }
+
+    /// Returns the narrowest date range that every matching commit falls in.
+    ///
+    /// # Arguments
+    /// - `field`: The commit date the range applies to.
+    ///
+    /// # Returns
+    /// The inclusive `(since, until)` bounds, where `None` means unbounded.
+    pub fn date_bounds(&self, field: DateField) -> (Option<DateTime<Utc>>, Option<DateTime<Utc>>) {
+        match self {
+            CommitFilter::DateRange {
+                field: range_field,
+                since,
+                until,
+            } if *range_field == field => (*since, *until),
+            CommitFilter::And(filters) => filters
+                .iter()
+                .map(|f| f.date_bounds(field))
+                .fold((None, None), |(since, until), (s, u)| {
+                    (since.max(s), until.into_iter().chain(u).min())
+                }),
+            CommitFilter::Or(filters) if !filters.is_empty() => {
+                let bounds: Vec<_> = filters.iter().map(|f| f.date_bounds(field)).collect();
+                let since = bounds.iter().map(|(s, _)| *s).collect::<Option<Vec<_>>>();
+                let until = bounds.iter().map(|(_, u)| *u).collect::<Option<Vec<_>>>();
+                (
+                    since.and_then(|s| s.into_iter().min()),
+                    until.and_then(|u| u.into_iter().max()),
+                )
+            }
+            _ => (None, None),
+        }
+    }
+}
+
+impl Not for CommitFilter {
+    type Output = CommitFilter;
+
+    fn not(self) -> CommitFilter {
+        match self {
+            CommitFilter::Not(filter) => *filter,
+            filter => CommitFilter::Not(Box::new(filter)),
+        }
+    }
+}
+
+impl PartialEq for CommitFilter {
+    fn eq(&self, other: &Self) -> bool {
+        use CommitFilter::*;
+        match (self, other) {
+            (
+                DateRange {
+                    field: a,
+                    since: a_since,
+                    until: a_until,
+                },
+                DateRange {
+                    field: b,
+                    since: b_since,
+                    until: b_until,
+                },
+            ) => a == b && a_since == b_since && a_until == b_until,
+            (Author(a), Author(b)) | (Committer(a), Committer(b)) => a == b,
+            (Message(a), Message(b)) => a == b,
+            (Path(a), Path(b)) => a == b,
+            (HashPrefix(a), HashPrefix(b)) | (Type(a), Type(b)) => a == b,
+            (Merge, Merge) | (Conventional, Conventional) => true,
+            (And(a), And(b)) | (Or(a), Or(b)) => a == b,
+            (Not(a), Not(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commit::{CommitAuthor, CommitHash};
+    use chrono::{FixedOffset, TimeZone};
+
+    fn commit() -> Commit {
+        let date = FixedOffset::east_opt(0)
+            .unwrap()
+            .with_ymd_and_hms(2025, 5, 4, 11, 0, 0)
+            .unwrap();
+        let mut commit = Commit::new(
+            "Fix the radio".to_string(),
+            CommitAuthor::new("Bix Caleen".to_string(), "bix@ferrix.org".to_string()),
+            date,
+            CommitAuthor::new("Maarva Andor".to_string(), "maarva@ferrix.org".to_string()),
+            date,
+            "Fix the radio\n\nIt was broken.".to_string(),
+            CommitHash::new("abcdef1234567890".to_string()),
+        );
+        commit.parents = vec!["1111".to_string()];
+        commit.paths = vec!["src/radio.rs".to_string(), "README.md".to_string()];
+        commit
+    }
+
+    fn utc(day: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 5, day, 0, 0, 0).unwrap()
+    }
+
+    fn range(since: Option<u32>, until: Option<u32>) -> CommitFilter {
+        CommitFilter::DateRange {
+            field: DateField::Committer,
+            since: since.map(utc),
+            until: until.map(utc),
+        }
+    }
+
+    #[test]
+    fn test_date_range() {
+        assert!(range(Some(4), Some(5)).matches(&commit()));
+        assert!(range(None, Some(5)).matches(&commit()));
+        assert!(!range(Some(5), None).matches(&commit()));
+    }
+
+    #[test]
+    fn test_authors() {
+        let bix = AuthorFilter::parse(["substr:Bix"]).unwrap();
+        assert!(CommitFilter::Author(bix.clone()).matches(&commit()));
+        assert!(!CommitFilter::Committer(bix).matches(&commit()));
+    }
+
+    #[test]
+    fn test_message() {
+        assert!(CommitFilter::message("was broken")
+            .unwrap()
+            .matches(&commit()));
+        assert!(!CommitFilter::message("^broken").unwrap().matches(&commit()));
+        assert!(CommitFilter::message("(").is_err());
+    }
+
+    #[test]
+    fn test_path() {
+        assert!(CommitFilter::path("src").unwrap().matches(&commit()));
+        assert!(CommitFilter::path("*.md").unwrap().matches(&commit()));
+        assert!(!CommitFilter::path("docs/").unwrap().matches(&commit()));
+        assert!(CommitFilter::path("v[0-9").is_err());
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut merge = commit();
+        merge.parents.push("2222".to_string());
+        assert!(CommitFilter::Merge.matches(&merge));
+        assert!(!CommitFilter::Merge.matches(&commit()));
+        assert!((!CommitFilter::Merge).matches(&commit()));
+    }
+
+    #[test]
+    fn test_hash_prefix() {
+        assert!(CommitFilter::hash_prefix("ABCDEF")
+            .unwrap()
+            .matches(&commit()));
+        assert!(!CommitFilter::hash_prefix("abd").unwrap().matches(&commit()));
+        assert!(CommitFilter::hash_prefix("xyz").is_err());
+        assert!(CommitFilter::hash_prefix("").is_err());
+    }
+
+    #[test]
+    fn test_conventional_type() {
+        let mut conventional = commit();
+        conventional.title = "fix(radio): Retune the dish".to_string();
+        assert!(!CommitFilter::Conventional.matches(&commit()));
+        assert!(CommitFilter::Conventional.matches(&conventional));
+
+        let fix = CommitFilter::conventional_type("FIX").unwrap();
+        assert_eq!(fix, CommitFilter::Type("fix".to_string()));
+        assert!(fix.matches(&conventional));
+        assert!(!fix.matches(&commit()));
+        assert!(!CommitFilter::conventional_type("feat")
+            .unwrap()
+            .matches(&conventional));
+        assert!(CommitFilter::conventional_type("").is_err());
+        assert!(CommitFilter::conventional_type("fix:").is_err());
+    }
+
+    #[test]
+    fn test_types() {
+        let mut conventional = commit();
+        conventional.title = "fix(radio): Retune the dish".to_string();
+        let types = CommitFilter::types(["feat", "FIX"]).unwrap();
+        assert!(types.matches(&conventional));
+        assert!(types.matches(&commit()));
+
+        conventional.title = "docs: Describe the dish".to_string();
+        assert!(!types.matches(&conventional));
+        assert!(CommitFilter::types(["feat", "fix:"]).is_err());
+    }
+
+    #[test]
+    fn test_combinators() {
+        let yes = CommitFilter::hash_prefix("abc").unwrap();
+        let no = CommitFilter::Merge;
+        assert!(yes.clone().and(!no.clone()).matches(&commit()));
+        assert!(!yes.clone().and(no.clone()).matches(&commit()));
+        assert!(no.clone().or(yes.clone()).matches(&commit()));
+        assert!(!(!yes.clone()).matches(&commit()));
+        assert!(CommitFilter::And(Vec::new()).matches(&commit()));
+        assert!(!CommitFilter::Or(Vec::new()).matches(&commit()));
+    }
+
+    #[test]
+    fn test_and_or_flatten() {
+        let a = CommitFilter::Merge;
+        let b = CommitFilter::hash_prefix("ab").unwrap();
+        let c = CommitFilter::path("src").unwrap();
+        assert_eq!(
+            a.clone().and(b.clone()).and(c.clone()),
+            CommitFilter::And(vec![a.clone(), b.clone(), c.clone()])
+        );
+        assert_eq!(
+            a.clone().or(b.clone()).or(c.clone()),
+            CommitFilter::Or(vec![a.clone(), b, c])
+        );
+        assert_eq!(!!a.clone(), a);
+    }
+
+    #[test]
+    fn test_needs_paths() {
+        assert!(!CommitFilter::Merge.needs_paths());
+        assert!((!CommitFilter::path("src").unwrap()).needs_paths());
+        assert!(CommitFilter::Merge
+            .or(CommitFilter::path("src").unwrap())
+            .needs_paths());
+    }
+
+    #[test]
+    fn test_date_bounds() {
+        let field = DateField::Committer;
+        assert_eq!(
+            range(Some(1), Some(9)).date_bounds(field),
+            (Some(utc(1)), Some(utc(9)))
+        );
+        assert_eq!(
+            range(Some(1), None).date_bounds(DateField::Author),
+            (None, None)
+        );
+
+        let and = range(Some(1), Some(9))
+            .and(range(Some(3), None))
+            .and(CommitFilter::Merge);
+        assert_eq!(and.date_bounds(field), (Some(utc(3)), Some(utc(9))));
+
+        let or = range(Some(1), Some(4)).or(range(Some(3), Some(9)));
+        assert_eq!(or.date_bounds(field), (Some(utc(1)), Some(utc(9))));
+
+        let open = range(Some(1), Some(4)).or(CommitFilter::Merge);
+        assert_eq!(open.date_bounds(field), (None, None));
+
+        assert_eq!((!range(Some(1), Some(4))).date_bounds(field), (None, None));
+    }
+}
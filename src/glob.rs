@@ -0,0 +1,252 @@
+//! The `glob` module matches directory paths against gitignore-style patterns.
+//!
+//! Paths are matched relative to a search root, with `/` separating their
+//! components. The patterns follow the rules of `.gitignore`:
+//!
+//! - `*` matches anything except `/`, `?` matches one character except `/`,
+//!   and `[...]` matches one character of a set, where `[!...]` negates it.
+//! - `**` matches any number of directories when it makes up a whole
+//!   component, as in `**/build`, `vendor/**` or `a/**/b`.
+//! - A pattern without a `/`, such as `node_modules`, matches a directory of
+//!   that name at any depth. A pattern with a leading or inner `/`, such as
+//!   `/vendor` or `third_party/libs`, is anchored to the search root.
+//! - A trailing `/` is ignored, since only directories are matched.
+//! - A `\` matches the character that follows it literally.
+//!
+//! # Example
+//! ```rust
+//! use walrust::glob::Glob;
+//! use std::path::Path;
+//!
+//! let glob = Glob::new("node_modules").unwrap();
+//! assert!(glob.is_match(Path::new("web/app/node_modules")));
+//!
+//! let glob = Glob::new("/work/*").unwrap();
+//! assert!(glob.is_match(Path::new("work/radio")));
+//! assert!(!glob.is_match(Path::new("home/work/radio")));
+//! ```
+
+use crate::{Result, WalrustError};
+use regex::Regex;
+use std::fmt;
+use std::path::{Component, Path};
+use std::str::FromStr;
+
+/// A gitignore-style pattern matched against relative directory paths.
+#[derive(Debug, Clone)]
+pub struct Glob {
+    pattern: String,
+    regex: Regex,
+}
+
+impl Glob {
+    /// Compiles a pattern.
+    ///
+    /// # Arguments
+    /// - `pattern`: The gitignore-style pattern, e.g. `node_modules` or `/vendor/**`.
+    ///
+    /// # Returns
+    /// The compiled `Glob`.
+    ///
+    /// # Errors
+    /// Returns a `ParseError` if the pattern is empty or has an unclosed `[`.
+    pub fn new(pattern: &str) -> Result<Self> {
+        let invalid = |reason: &str| {
+            WalrustError::ParseError(format!("invalid glob '{}': {}", pattern, reason))
+        };
+
+        let trimmed = pattern.strip_suffix('/').unwrap_or(pattern);
+        let anchored = trimmed.contains('/');
+        let body = trimmed.strip_prefix('/').unwrap_or(trimmed);
+        if body.is_empty() {
+            return Err(invalid("empty pattern"));
+        }
+
+        let mut regex = String::from("^");
+        if !anchored {
+            regex.push_str("(?:.*/)?");
+        }
+        let chars: Vec<char> = body.chars().collect();
+        let mut i = 0;
+        while i < chars.len() {
+            let starts_component = i == 0 || chars[i - 1] == '/';
+            match chars[i] {
+                '*' if chars.get(i + 1) == Some(&'*') && starts_component => {
+                    match chars.get(i + 2) {
+                        // `**/` matches zero or more directories
+                        Some('/') => {
+                            regex.push_str("(?:.*/)?");
+                            i += 3;
+                        }
+                        // A trailing `**` matches everything below
+                        None => {
+                            regex.push_str(".*");
+                            i += 2;
+                        }
+                        _ => {
+                            regex.push_str("[^/]*");
+                            i += 2;
+                        }
+                    }
+                    continue;
+                }
+                '*' => regex.push_str("[^/]*"),
+                '?' => regex.push_str("[^/]"),
+                '[' => {
+                    // A `]` first in the set, after any `!`, is literal
+                    let first = i + if chars.get(i + 1) == Some(&'!') { 3 } else { 2 };
+                    let end = chars
+                        .get(first..)
+                        .and_then(|rest| rest.iter().position(|&c| c == ']'))
+                        .map(|p| first + p)
+                        .ok_or_else(|| invalid("unclosed '['"))?;
+                    regex.push('[');
+                    let mut set = &chars[i + 1..end];
+                    if let Some(('!', rest)) = set.split_first() {
+                        regex.push('^');
+                        set = rest;
+                    }
+                    for &c in set {
+                        match c {
+                            '\\' | '[' | ']' | '^' | '&' | '~' => {
+                                regex.push('\\');
+                                regex.push(c);
+                            }
+                            _ => regex.push(c),
+                        }
+                    }
+                    regex.push(']');
+                    i = end;
+                }
+                '\\' => {
+                    if let Some(&c) = chars.get(i + 1) {
+                        regex.push_str(&regex::escape(&c.to_string()));
+                        i += 1;
+                    }
+                }
+                c => regex.push_str(&regex::escape(&c.to_string())),
+            }
+            i += 1;
+        }
+        regex.push('$');
+
+        let regex = Regex::new(&regex).map_err(|err| invalid(&err.to_string()))?;
+        Ok(Glob {
+            pattern: pattern.to_string(),
+            regex,
+        })
+    }
+
+    /// Returns the pattern the glob was compiled from.
+    pub fn as_str(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Tests a directory path against the glob.
+    ///
+    /// # Arguments
+    /// - `path`: The path relative to the search root, e.g. `web/node_modules`.
+    ///
+    /// # Returns
+    /// `true` if the path matches.
+    pub fn is_match(&self, path: &Path) -> bool {
+        let components: Vec<_> = path
+            .components()
+            .filter_map(|component| match component {
+                Component::Normal(name) => Some(name.to_string_lossy()),
+                _ => None,
+            })
+            .collect();
+        self.regex.is_match(&components.join("/"))
+    }
+}
+
+impl fmt::Display for Glob {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.pattern)
+    }
+}
+
+impl FromStr for Glob {
+    type Err = WalrustError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Glob::new(s)
+    }
+}
+
+impl PartialEq for Glob {
+    fn eq(&self, other: &Self) -> bool {
+        self.pattern == other.pattern
+    }
+}
+
+impl Eq for Glob {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(pattern: &str, path: &str) -> bool {
+        Glob::new(pattern).unwrap().is_match(Path::new(path))
+    }
+
+    #[test]
+    fn test_unanchored() {
+        assert!(matches("node_modules", "node_modules"));
+        assert!(matches("node_modules", "web/app/node_modules"));
+        assert!(matches("node_modules/", "web/node_modules"));
+        assert!(!matches("node_modules", "web/node_modules_old"));
+        assert!(!matches("node_modules", "node_modules/left-pad"));
+        assert!(matches("*.bak", "src/old.bak"));
+        assert!(matches("build-?", "build-1"));
+        assert!(!matches("build-?", "build-10"));
+    }
+
+    #[test]
+    fn test_anchored() {
+        assert!(matches("/vendor", "vendor"));
+        assert!(!matches("/vendor", "lib/vendor"));
+        assert!(matches("third_party/libs", "third_party/libs"));
+        assert!(!matches("third_party/libs", "src/third_party/libs"));
+        assert!(matches("work/*", "work/radio"));
+        assert!(!matches("work/*", "work/radio/dish"));
+        assert!(!matches("work/*", "work"));
+    }
+
+    #[test]
+    fn test_double_star() {
+        assert!(matches("**/build", "build"));
+        assert!(matches("**/build", "a/b/build"));
+        assert!(matches("vendor/**", "vendor/a/b"));
+        assert!(!matches("vendor/**", "vendor"));
+        assert!(matches("a/**/b", "a/b"));
+        assert!(matches("a/**/b", "a/x/y/b"));
+        assert!(!matches("a/**/b", "c/a/b"));
+        assert!(matches("a**", "abc"));
+        assert!(!matches("a**", "a/b"));
+    }
+
+    #[test]
+    fn test_character_sets() {
+        assert!(matches("v[0-9]", "v1"));
+        assert!(!matches("v[0-9]", "vx"));
+        assert!(matches("v[!0-9]", "vx"));
+        assert!(!matches("v[!0-9]", "v1"));
+        assert!(matches("[]]", "]"));
+        assert!(matches("[!]]", "a"));
+        assert!(!matches("[!]]", "]"));
+        assert!(matches(r"\*", "*"));
+        assert!(!matches(r"\*", "a"));
+        assert!(matches("a.b", "a.b"));
+        assert!(!matches("a.b", "axb"));
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(Glob::new("").is_err());
+        assert!(Glob::new("/").is_err());
+        assert!(Glob::new("v[0-9").is_err());
+        assert!("target".parse::<Glob>().is_ok());
+    }
+}
@@ -0,0 +1,241 @@
+//! The `grep` module searches commit messages with regular expressions.
+//!
+//! A [`Grep`] matches either the commit title or the full message, optionally
+//! ignoring case, and can [`highlight`] its matches for display.
+//!
+//! # Example
+//! ```rust
+//! use walrust::grep::{highlight, Grep, GrepScope};
+//!
+//! let grep = Grep::new(r"jira-\d+", true, GrepScope::Message).unwrap();
+//! let line = highlight("Fix JIRA-1234 again", &[grep], "[", "]");
+//! assert_eq!(line, "Fix [JIRA-1234] again");
+//! ```
+
+use crate::commit::Commit;
+use crate::{Result, WalrustError};
+use regex::{Regex, RegexBuilder};
+use std::fmt;
+use std::ops::Range;
+use std::str::FromStr;
+
+/// Selects which part of a commit message is searched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GrepScope {
+    /// The first line of the message.
+    Title,
+    /// The full message, including the title.
+    #[default]
+    Message,
+}
+
+impl fmt::Display for GrepScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GrepScope::Title => write!(f, "title"),
+            GrepScope::Message => write!(f, "message"),
+        }
+    }
+}
+
+impl FromStr for GrepScope {
+    type Err = WalrustError;
+
+    /// Parses `"title"` or `"message"` into a `GrepScope`.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "title" => Ok(GrepScope::Title),
+            "message" => Ok(GrepScope::Message),
+            _ => Err(WalrustError::ParseError(format!(
+                "unknown grep scope '{}', expected 'title' or 'message'",
+                s
+            ))),
+        }
+    }
+}
+
+/// A regular expression search over commit messages.
+#[derive(Debug, Clone)]
+pub struct Grep {
+    regex: Regex,
+    ignore_case: bool,
+    scope: GrepScope,
+}
+
+impl Grep {
+    /// Creates a new search.
+    ///
+    /// # Arguments
+    /// - `pattern`: The regular expression to search for.
+    /// - `ignore_case`: Whether to match letters regardless of case.
+    /// - `scope`: Which part of the message to search.
+    ///
+    /// # Errors
+    /// Returns a `ParseError` if the regular expression is invalid.
+    pub fn new(pattern: &str, ignore_case: bool, scope: GrepScope) -> Result<Self> {
+        let regex = RegexBuilder::new(pattern)
+            .case_insensitive(ignore_case)
+            .build()
+            .map_err(|err| {
+                WalrustError::ParseError(format!("invalid grep pattern '{}': {}", pattern, err))
+            })?;
+        Ok(Grep {
+            regex,
+            ignore_case,
+            scope,
+        })
+    }
+
+    /// Returns the regular expression pattern.
+    pub fn pattern(&self) -> &str {
+        self.regex.as_str()
+    }
+
+    /// Returns which part of the message is searched.
+    pub fn scope(&self) -> GrepScope {
+        self.scope
+    }
+
+    /// Tests whether the searched part of a commit message matches.
+    ///
+    /// # Arguments
+    /// - `commit`: The commit to search.
+    ///
+    /// # Returns
+    /// `true` if the pattern is found.
+    pub fn is_match(&self, commit: &Commit) -> bool {
+        match self.scope {
+            GrepScope::Title => self.regex.is_match(&commit.title),
+            GrepScope::Message => self.regex.is_match(&commit.message),
+        }
+    }
+
+    /// Finds every match of the pattern in a piece of text.
+    ///
+    /// # Arguments
+    /// - `text`: The text to search, e.g. a rendered output line.
+    ///
+    /// # Returns
+    /// The byte ranges of the non-overlapping matches, in order.
+    pub fn find_ranges(&self, text: &str) -> Vec<Range<usize>> {
+        self.regex
+            .find_iter(text)
+            .filter(|m| !m.is_empty())
+            .map(|m| m.range())
+            .collect()
+    }
+}
+
+impl PartialEq for Grep {
+    fn eq(&self, other: &Self) -> bool {
+        self.regex.as_str() == other.regex.as_str()
+            && self.ignore_case == other.ignore_case
+            && self.scope == other.scope
+    }
+}
+
+/// Wraps every match of any of the searches in a piece of text.
+///
+/// Overlapping or adjacent matches from different searches are merged so the
+/// markers never nest.
+///
+/// # Arguments
+/// - `text`: The text to highlight.
+/// - `greps`: The searches whose matches are highlighted.
+/// - `before`: The marker inserted before each match, e.g. an ANSI color code.
+/// - `after`: The marker inserted after each match.
+///
+/// # Returns
+/// The text with the markers inserted.
+pub fn highlight(text: &str, greps: &[Grep], before: &str, after: &str) -> String {
+    let mut ranges: Vec<_> = greps.iter().flat_map(|g| g.find_ranges(text)).collect();
+    ranges.sort_by_key(|r| r.start);
+
+    let mut merged: Vec<Range<usize>> = Vec::new();
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+
+    let mut result = String::with_capacity(text.len());
+    let mut position = 0;
+    for range in merged {
+        result.push_str(&text[position..range.start]);
+        result.push_str(before);
+        result.push_str(&text[range.clone()]);
+        result.push_str(after);
+        position = range.end;
+    }
+    result.push_str(&text[position..]);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commit() -> Commit {
+        Commit {
+            title: "Fix the radio".to_string(),
+            message: "Fix the radio\n\nRefs JIRA-1234.".to_string(),
+            ..Commit::default()
+        }
+    }
+
+    #[test]
+    fn test_scope() {
+        let grep = Grep::new("JIRA-\\d+", false, GrepScope::Message).unwrap();
+        assert!(grep.is_match(&commit()));
+        let grep = Grep::new("JIRA-\\d+", false, GrepScope::Title).unwrap();
+        assert!(!grep.is_match(&commit()));
+    }
+
+    #[test]
+    fn test_ignore_case() {
+        let grep = Grep::new("jira", false, GrepScope::Message).unwrap();
+        assert!(!grep.is_match(&commit()));
+        let grep = Grep::new("jira", true, GrepScope::Message).unwrap();
+        assert!(grep.is_match(&commit()));
+    }
+
+    #[test]
+    fn test_invalid_pattern() {
+        assert!(Grep::new("(", false, GrepScope::Message).is_err());
+    }
+
+    #[test]
+    fn test_scope_from_str() {
+        assert_eq!("title".parse::<GrepScope>().unwrap(), GrepScope::Title);
+        assert_eq!("message".parse::<GrepScope>().unwrap(), GrepScope::Message);
+        assert!("body".parse::<GrepScope>().is_err());
+    }
+
+    #[test]
+    fn test_highlight() {
+        let grep = Grep::new("radio", false, GrepScope::Message).unwrap();
+        assert_eq!(
+            highlight("radio, radio", &[grep], "<", ">"),
+            "<radio>, <radio>"
+        );
+    }
+
+    #[test]
+    fn test_highlight_merges_overlaps() {
+        let greps = [
+            Grep::new("the ra", false, GrepScope::Message).unwrap(),
+            Grep::new("radio", false, GrepScope::Message).unwrap(),
+        ];
+        assert_eq!(
+            highlight("Fix the radio", &greps, "<", ">"),
+            "Fix <the radio>"
+        );
+    }
+
+    #[test]
+    fn test_highlight_skips_empty_matches() {
+        let grep = Grep::new("x*", false, GrepScope::Message).unwrap();
+        assert_eq!(highlight("abc", &[grep], "<", ">"), "abc");
+    }
+}
@@ -7,10 +7,23 @@
 //!
 //! # Modules
 //!
+//! - [`author`]: Matches commit authors against user-supplied patterns.
+//! - [`changelog`]: Renders commit query results as a Markdown changelog.
 //! - [`commit`]: Defines the `Commit` struct and related functionality.
+//! - [`config`]: Loads the user configuration file.
+//! - [`conventional`]: Parses Conventional Commits titles and footers.
+//! - [`filter`]: Provides composable predicates over commits.
 //! - [`filesystem`]: Provides abstractions for filesystem operations.
+//! - [`glob`]: Matches directory paths against gitignore-style patterns.
+//! - [`grep`]: Searches commit messages with regular expressions.
+//! - [`pathspec`]: Matches changed paths against Git pathspecs.
+//! - [`query`]: Defines the `CommitQuery` used to select commits from a repository.
+//! - [`report`]: Renders commit query results for other programs.
 //! - [`repository`]: Defines the `GitRepository` trait and its implementations.
 //! - [`repository_locator`]: Provides functionality for locating repositories on the filesystem.
+//! - [`scanner`]: Queries many repositories concurrently.
+//! - [`summary`]: Groups and counts commit query results.
+//! - [`template`]: Renders commits with user-defined output templates.
 //!
 //! # Example
 //!
@@ -29,10 +42,23 @@ use std::fmt;
 use std::io;
 use std::path::PathBuf;
 
+pub mod author;
+pub mod changelog;
 pub mod commit;
+pub mod config;
+pub mod conventional;
 pub mod filesystem;
+pub mod filter;
+pub mod glob;
+pub mod grep;
+pub mod pathspec;
+pub mod query;
+pub mod report;
 pub mod repository;
 pub mod repository_locator;
+pub mod scanner;
+pub mod summary;
+pub mod template;
 
 /// A type alias for results returned by the Walrust library.
 ///
@@ -43,12 +69,14 @@ pub type Result<T> = std::result::Result<T, WalrustError>;
 /// Walrust error types.
 ///
 /// This enum defines the various error types that can occur in the Walrust library.
-/// It provides variants for Git-related errors, IO errors, and invalid paths.
+/// It provides variants for Git-related errors, IO errors, invalid paths, and
+/// values that cannot be parsed.
 ///
 /// # Variants
 /// - `GitError`: Represents errors related to Git operations.
 /// - `IoError`: Represents errors related to IO operations.
 /// - `PathError`: Represents errors related to invalid paths.
+/// - `ParseError`: Represents errors related to parsing user-supplied values.
 ///
 /// # Example
 /// ```rust
@@ -66,6 +94,11 @@ pub enum WalrustError {
     IoError(io::Error),
     /// An error related to invalid paths.
     PathError(PathBuf),
+    /// An error related to parsing user-supplied values.
+    ParseError(String),
+    /// A revision, such as a tag or branch name, that does not exist in a
+    /// repository.
+    RevisionNotFound(String),
 }
 
 impl fmt::Display for WalrustError {
@@ -74,6 +107,8 @@ impl fmt::Display for WalrustError {
             WalrustError::GitError(err) => write!(f, "Git error: {}", err),
             WalrustError::IoError(err) => write!(f, "IO error: {}", err),
             WalrustError::PathError(path) => write!(f, "Invalid path: {}", path.display()),
+            WalrustError::ParseError(msg) => write!(f, "Parse error: {}", msg),
+            WalrustError::RevisionNotFound(rev) => write!(f, "Revision not found: {}", rev),
         }
     }
 }
@@ -104,7 +139,7 @@ mod tests {
 
     #[test]
     fn test_walrust_error_display_io_error() {
-        let io_error = io::Error::new(io::ErrorKind::Other, "IO operation failed");
+        let io_error = io::Error::other("IO operation failed");
         let error = WalrustError::IoError(io_error);
         assert_eq!(format!("{}", error), "IO error: IO operation failed");
     }
@@ -119,23 +154,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_walrust_error_display_parse_error() {
+        let error = WalrustError::ParseError("unexpected value".to_string());
+        assert_eq!(format!("{}", error), "Parse error: unexpected value");
+    }
+
+    #[test]
+    fn test_walrust_error_display_revision_not_found() {
+        let error = WalrustError::RevisionNotFound("v1.2.0".to_string());
+        assert_eq!(format!("{}", error), "Revision not found: v1.2.0");
+    }
+
     #[test]
     fn test_walrust_error_from_git_error() {
         let git_error = git2::Error::from_str("Git operation failed");
         let error: WalrustError = git_error.into();
-        match error {
-            WalrustError::GitError(_) => assert!(true),
-            _ => assert!(false, "Expected WalrustError::GitError"),
-        }
+        assert!(
+            matches!(error, WalrustError::GitError(_)),
+            "Expected WalrustError::GitError"
+        );
     }
 
     #[test]
     fn test_walrust_error_from_io_error() {
-        let io_error = io::Error::new(io::ErrorKind::Other, "IO operation failed");
+        let io_error = io::Error::other("IO operation failed");
         let error: WalrustError = io_error.into();
-        match error {
-            WalrustError::IoError(_) => assert!(true),
-            _ => assert!(false, "Expected WalrustError::IoError"),
-        }
+        assert!(
+            matches!(error, WalrustError::IoError(_)),
+            "Expected WalrustError::IoError"
+        );
     }
 }
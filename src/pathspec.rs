@@ -0,0 +1,324 @@
+//! The `pathspec` module matches the paths a commit changes against Git pathspecs.
+//!
+//! A [`Pathspec`] is compiled once from a list of patterns and can then be
+//! shared between the threads querying repositories. The patterns follow the
+//! rules of `git log -- <pathspec>`:
+//!
+//! - A pattern without wildcards matches the path itself and everything below
+//!   it, so `src` and `src/` both match `src/main.rs`. A trailing `/` only
+//!   matches paths below the directory.
+//! - `*` matches anything, including `/`, so `*.toml` matches
+//!   `deploy/prod.toml`. `?` matches one character and `[...]` one character
+//!   of a set, where `[!...]` or `[^...]` negates it.
+//! - A `\` matches the character that follows it literally.
+//! - The `:!`, `:^` and `:(exclude)` magic prefixes exclude the paths the rest
+//!   of the pattern matches, whatever the other patterns match. A pathspec with
+//!   only exclusions matches every other path. Other magic is rejected.
+//!
+//! An empty `Pathspec` matches every path.
+//!
+//! # Example
+//! ```rust
+//! use walrust::pathspec::Pathspec;
+//!
+//! let pathspec = Pathspec::new(["deploy/", "*.md", ":!deploy/dev.toml"]).unwrap();
+//! assert!(pathspec.is_match("deploy/prod.toml"));
+//! assert!(pathspec.is_match("docs/guide.md"));
+//! assert!(!pathspec.is_match("deploy/dev.toml"));
+//! assert!(!pathspec.is_match("src/main.rs"));
+//! ```
+
+use crate::{Result, WalrustError};
+use regex::Regex;
+use std::fmt;
+
+/// A compiled list of Git pathspecs.
+#[derive(Debug, Clone, Default)]
+pub struct Pathspec {
+    patterns: Vec<String>,
+    /// Each pattern's regular expression and whether it excludes the paths it matches.
+    rules: Vec<(Regex, bool)>,
+}
+
+impl Pathspec {
+    /// Compiles a list of patterns.
+    ///
+    /// # Arguments
+    /// - `patterns`: The Git pathspecs, e.g. `deploy/` or `*.toml`.
+    ///
+    /// # Returns
+    /// The compiled `Pathspec`.
+    ///
+    /// # Errors
+    /// Returns a `ParseError` if a pattern is empty, has an unclosed `[` or uses
+    /// magic other than `exclude`.
+    pub fn new<I, S>(patterns: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let patterns: Vec<String> = patterns.into_iter().map(Into::into).collect();
+        let rules = patterns
+            .iter()
+            .map(|pattern| compile(pattern))
+            .collect::<Result<_>>()?;
+        Ok(Pathspec { patterns, rules })
+    }
+
+    /// Returns the patterns the pathspec was compiled from.
+    pub fn patterns(&self) -> &[String] {
+        &self.patterns
+    }
+
+    /// Returns `true` if the pathspec has no patterns and matches every path.
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// Tests a path against the pathspec.
+    ///
+    /// # Arguments
+    /// - `path`: The path relative to the repository root, e.g. `src/main.rs`.
+    ///
+    /// # Returns
+    /// `true` if the path matches an including pattern, or there are none, and
+    /// matches no excluding pattern.
+    pub fn is_match(&self, path: &str) -> bool {
+        let mut includes = self.rules.iter().filter(|(_, exclude)| !exclude).peekable();
+        let included = includes.peek().is_none() || includes.any(|(regex, _)| regex.is_match(path));
+        included
+            && !self
+                .rules
+                .iter()
+                .any(|(regex, exclude)| *exclude && regex.is_match(path))
+    }
+
+    /// Tests whether any of the paths matches the pathspec.
+    ///
+    /// # Arguments
+    /// - `paths`: The paths relative to the repository root.
+    ///
+    /// # Returns
+    /// `true` if any path matches, or if the pathspec is empty.
+    pub fn matches_any(&self, paths: &[String]) -> bool {
+        self.is_empty() || paths.iter().any(|path| self.is_match(path))
+    }
+}
+
+/// Compiles one pattern to a regular expression matching whole paths.
+///
+/// # Returns
+/// The regular expression and whether the pattern excludes the paths it matches.
+fn compile(pattern: &str) -> Result<(Regex, bool)> {
+    let invalid = |reason: &str| {
+        WalrustError::ParseError(format!("invalid pathspec '{}': {}", pattern, reason))
+    };
+
+    let (body, exclude) = parse_magic(pattern).map_err(|reason| invalid(&reason))?;
+    let (body, directory) = match body.strip_suffix('/') {
+        Some(rest) => (rest, true),
+        None => (body, false),
+    };
+    if body.is_empty() {
+        return Err(invalid("empty pattern"));
+    }
+
+    let mut regex = String::from("^");
+    let mut has_wildcard = false;
+    let chars: Vec<char> = body.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '*' => {
+                has_wildcard = true;
+                regex.push_str(".*");
+            }
+            '?' => {
+                has_wildcard = true;
+                regex.push('.');
+            }
+            '[' => {
+                has_wildcard = true;
+                // A `]` first in the set, after any `!` or `^`, is literal
+                let negated = matches!(chars.get(i + 1), Some('!') | Some('^'));
+                let first = i + if negated { 3 } else { 2 };
+                let end = chars
+                    .get(first..)
+                    .and_then(|rest| rest.iter().position(|&c| c == ']'))
+                    .map(|p| first + p)
+                    .ok_or_else(|| invalid("unclosed '['"))?;
+                regex.push('[');
+                let mut set = &chars[i + 1..end];
+                if negated {
+                    regex.push('^');
+                    set = &set[1..];
+                }
+                for &c in set {
+                    match c {
+                        '\\' | '[' | ']' | '^' | '&' | '~' => {
+                            regex.push('\\');
+                            regex.push(c);
+                        }
+                        _ => regex.push(c),
+                    }
+                }
+                regex.push(']');
+                i = end;
+            }
+            '\\' => {
+                if let Some(&c) = chars.get(i + 1) {
+                    regex.push_str(&regex::escape(&c.to_string()));
+                    i += 1;
+                }
+            }
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+        i += 1;
+    }
+    // Patterns without wildcards also match everything below them
+    match (has_wildcard, directory) {
+        (false, false) => regex.push_str("(?:/.*)?"),
+        (false, true) => regex.push_str("/.*"),
+        (true, _) => {}
+    }
+    regex.push('$');
+
+    let regex = Regex::new(&format!("(?s){}", regex)).map_err(|err| invalid(&err.to_string()))?;
+    Ok((regex, exclude))
+}
+
+/// Splits the magic prefix, e.g. `:!` or `:(exclude)`, from a pattern.
+///
+/// # Returns
+/// The rest of the pattern and whether the magic excludes the paths it matches,
+/// or the reason the magic is invalid.
+fn parse_magic(pattern: &str) -> std::result::Result<(&str, bool), String> {
+    let Some(rest) = pattern.strip_prefix(':') else {
+        return Ok((pattern, false));
+    };
+
+    if let Some(rest) = rest.strip_prefix('(') {
+        let (words, body) = rest
+            .split_once(')')
+            .ok_or_else(|| "unclosed '('".to_string())?;
+        let mut exclude = false;
+        for word in words
+            .split(',')
+            .map(str::trim)
+            .filter(|word| !word.is_empty())
+        {
+            match word {
+                "exclude" => exclude = true,
+                _ => return Err(format!("unsupported magic '{}'", word)),
+            }
+        }
+        return Ok((body, exclude));
+    }
+
+    // The short form is a run of magic characters, optionally ended by a `:`
+    let end = rest
+        .find(|c: char| c == ':' || !c.is_ascii_punctuation())
+        .unwrap_or(rest.len());
+    let (magic, body) = rest.split_at(end);
+    let mut exclude = false;
+    for c in magic.chars() {
+        match c {
+            '!' | '^' => exclude = true,
+            _ => return Err(format!("unsupported magic '{}'", c)),
+        }
+    }
+    Ok((body.strip_prefix(':').unwrap_or(body), exclude))
+}
+
+impl fmt::Display for Pathspec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.patterns.join(" "))
+    }
+}
+
+impl PartialEq for Pathspec {
+    fn eq(&self, other: &Self) -> bool {
+        self.patterns == other.patterns
+    }
+}
+
+impl Eq for Pathspec {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(pattern: &str, path: &str) -> bool {
+        Pathspec::new([pattern]).unwrap().is_match(path)
+    }
+
+    #[test]
+    fn test_prefix() {
+        assert!(matches("src", "src"));
+        assert!(matches("src", "src/main.rs"));
+        assert!(matches("src/", "src/main.rs"));
+        assert!(!matches("src/", "src"));
+        assert!(!matches("src", "srcs/main.rs"));
+        assert!(!matches("src", "lib/src/main.rs"));
+        assert!(matches("deploy/prod.toml", "deploy/prod.toml"));
+    }
+
+    #[test]
+    fn test_wildcards() {
+        assert!(matches("*.toml", "Cargo.toml"));
+        assert!(matches("*.toml", "deploy/prod.toml"));
+        assert!(!matches("*.toml", "deploy/prod.toml.bak"));
+        assert!(matches("src/*.rs", "src/bin/walrust.rs"));
+        assert!(matches("v?.md", "v1.md"));
+        assert!(matches("v[0-9].md", "v1.md"));
+        assert!(!matches("v[!0-9].md", "v1.md"));
+        assert!(matches("v[^0-9].md", "vx.md"));
+        assert!(matches(r"\*.md", "*.md"));
+        assert!(!matches(r"\*.md", "a.md"));
+        assert!(!matches("a.b", "axb"));
+    }
+
+    #[test]
+    fn test_exclude() {
+        for exclude in [
+            ":!src/generated",
+            ":^src/generated",
+            ":(exclude)src/generated",
+        ] {
+            let pathspec = Pathspec::new(["src", exclude]).unwrap();
+            assert!(pathspec.is_match("src/main.rs"));
+            assert!(!pathspec.is_match("src/generated/api.rs"));
+            assert!(!pathspec.is_match("README.md"));
+        }
+
+        let pathspec = Pathspec::new([":!:*.lock"]).unwrap();
+        assert!(pathspec.is_match("src/main.rs"));
+        assert!(!pathspec.is_match("Cargo.lock"));
+
+        // Without magic a leading `!` is part of the path, as in Git
+        assert!(matches("!src", "!src/main.rs"));
+        assert!(!matches("!src", "README.md"));
+        assert!(matches(":src", "src/main.rs"));
+    }
+
+    #[test]
+    fn test_matches_any() {
+        let paths = vec!["README.md".to_string(), "src/lib.rs".to_string()];
+        assert!(Pathspec::default().matches_any(&paths));
+        assert!(Pathspec::new(["src"]).unwrap().matches_any(&paths));
+        assert!(!Pathspec::new(["docs"]).unwrap().matches_any(&paths));
+        assert!(!Pathspec::new(["docs"]).unwrap().matches_any(&[]));
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(Pathspec::new([""]).is_err());
+        assert!(Pathspec::new([":!"]).is_err());
+        assert!(Pathspec::new([":/src"]).is_err());
+        assert!(Pathspec::new([":(icase)src"]).is_err());
+        assert!(Pathspec::new([":(exclude"]).is_err());
+        assert!(Pathspec::new(["v[0-9"]).is_err());
+        let err = Pathspec::new(["src", "v[0-9"]).unwrap_err();
+        assert!(err.to_string().contains("invalid pathspec 'v[0-9'"));
+    }
+}
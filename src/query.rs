@@ -0,0 +1,571 @@
+use crate::commit::{Commit, DateField};
+use crate::filter::CommitFilter;
+use crate::pathspec::Pathspec;
+use crate::WalrustError;
+use chrono::{DateTime, Utc};
+use std::fmt;
+use std::str::FromStr;
+
+/// Selects which references a commit query starts walking from.
+///
+/// Commits reachable from more than one selected reference are only reported
+/// once, with every selected reference that contains them listed in
+/// [`Commit::branches`](crate::commit::Commit::branches).
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum RefSelection {
+    /// Walk from the current HEAD only.
+    #[default]
+    Head,
+    /// Walk from every local branch (`refs/heads/*`).
+    LocalBranches,
+    /// Walk from every reference, including remote-tracking branches and tags.
+    AllRefs,
+    /// Walk from every reference matching one of the glob patterns, such as
+    /// `refs/heads/feature/*`. Patterns that do not start with `refs/` are
+    /// prefixed with it, matching `git log --glob`.
+    Globs(Vec<String>),
+}
+
+/// A range of revisions, like `git log from..to` or `git log from...to`.
+///
+/// Revisions are anything Git can resolve to a commit, such as tags, branch
+/// names, `HEAD` or hashes, and are resolved in each repository separately.
+///
+/// # Example
+/// ```rust
+/// use walrust::query::RevisionRange;
+///
+/// let range: RevisionRange = "v1.2.0..HEAD".parse().unwrap();
+/// assert_eq!(range, RevisionRange::new(Some("v1.2.0".to_string()), "HEAD".to_string()));
+///
+/// let range: RevisionRange = "main...feature".parse().unwrap();
+/// assert!(range.symmetric);
+/// assert_eq!(range.to_string(), "main...feature");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RevisionRange {
+    /// The revision whose history is excluded, or `None` to include the whole
+    /// history of `to`. In a symmetric range, the other side of the range.
+    pub from: Option<String>,
+    /// The revision the walk starts from.
+    pub to: String,
+    /// Whether the range is symmetric, like `from...to`, holding the commits
+    /// reachable from either side but not from both.
+    pub symmetric: bool,
+}
+
+impl RevisionRange {
+    /// Creates a new `RevisionRange` of the commits reachable from `to` but
+    /// not from `from`.
+    ///
+    /// # Arguments
+    /// - `from`: The revision whose history is excluded, if any.
+    /// - `to`: The revision the walk starts from.
+    ///
+    /// # Returns
+    /// A new `RevisionRange` instance.
+    pub fn new(from: Option<String>, to: String) -> Self {
+        Self {
+            from,
+            to,
+            symmetric: false,
+        }
+    }
+
+    /// Creates a new symmetric `RevisionRange` of the commits reachable from
+    /// either revision but not from both.
+    ///
+    /// # Arguments
+    /// - `from`: One side of the range.
+    /// - `to`: The other side of the range.
+    ///
+    /// # Returns
+    /// A new `RevisionRange` instance.
+    pub fn symmetric(from: String, to: String) -> Self {
+        Self {
+            from: Some(from),
+            to,
+            symmetric: true,
+        }
+    }
+}
+
+impl fmt::Display for RevisionRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.from, self.symmetric) {
+            (Some(from), true) => write!(f, "{}...{}", from, self.to),
+            (Some(from), false) => write!(f, "{}..{}", from, self.to),
+            (None, _) => write!(f, "{}", self.to),
+        }
+    }
+}
+
+impl FromStr for RevisionRange {
+    type Err = WalrustError;
+
+    /// Parses `from..to`, `from...to` or a single revision into a
+    /// `RevisionRange`. Like Git, an omitted side of `..` or `...` means `HEAD`.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let invalid = || {
+            WalrustError::ParseError(format!(
+                "invalid revision range '{}', expected 'rev', 'from..to' or 'from...to'",
+                s
+            ))
+        };
+        let side = |rev: &str| match rev.trim() {
+            "" => Ok("HEAD".to_string()),
+            rev if rev.contains("..") => Err(invalid()),
+            rev => Ok(rev.to_string()),
+        };
+        if let Some((from, to)) = s.split_once("...") {
+            Ok(RevisionRange::symmetric(side(from)?, side(to)?))
+        } else if let Some((from, to)) = s.split_once("..") {
+            Ok(RevisionRange::new(Some(side(from)?), side(to)?))
+        } else {
+            match s.trim() {
+                "" => Err(invalid()),
+                rev => Ok(RevisionRange::new(None, rev.to_string())),
+            }
+        }
+    }
+}
+
+/// Selects how merge commits, those with more than one parent, are reported.
+///
+/// A mode is shorthand for a [`CommitFilter::Merge`] term, see
+/// [`MergeMode::filter`], which the query applies along with its own filter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MergeMode {
+    /// Report merges like any other commit.
+    #[default]
+    Include,
+    /// Skip merges, matching `git log --no-merges`.
+    Exclude,
+    /// Report only merges, matching `git log --merges`.
+    Only,
+}
+
+impl MergeMode {
+    /// Builds the filter term for this mode.
+    ///
+    /// # Returns
+    /// `CommitFilter::Merge` or its negation, or `None` if every commit is kept.
+    pub fn filter(&self) -> Option<CommitFilter> {
+        match self {
+            MergeMode::Include => None,
+            MergeMode::Exclude => Some(!CommitFilter::Merge),
+            MergeMode::Only => Some(CommitFilter::Merge),
+        }
+    }
+}
+
+/// Describes which commits to retrieve from a repository.
+///
+/// # Example
+/// ```rust
+/// use walrust::query::{CommitQuery, RefSelection};
+/// use chrono::{Duration, Utc};
+///
+/// let query = CommitQuery::new(Utc::now() - Duration::days(1), Utc::now())
+///     .with_refs(RefSelection::LocalBranches);
+/// assert_eq!(query.refs, RefSelection::LocalBranches);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommitQuery {
+    /// Inclusive start date for the commit range.
+    pub since: DateTime<Utc>,
+    /// Inclusive end date for the commit range.
+    pub until: DateTime<Utc>,
+    /// Whether `since` and `until` apply to the author or committer date.
+    pub date_field: DateField,
+    /// The references to start walking from.
+    pub refs: RefSelection,
+    /// The revision range to walk instead of `refs`, if any.
+    pub range: Option<RevisionRange>,
+    /// An additional predicate commits in the date range must match.
+    pub filter: Option<CommitFilter>,
+    /// Git pathspecs, such as `deploy/` or `*.toml`, of which commits must
+    /// change at least one matching path. An empty pathspec matches every commit.
+    ///
+    /// Each commit is compared against its first parent, and root commits
+    /// against an empty tree. A merge therefore matches when it brings changes
+    /// to a matching path into the first parent's history, and a merge that
+    /// only keeps the first parent's content matches no path.
+    pub paths: Pathspec,
+    /// Whether to compute [`Commit::stats`] for matching commits.
+    pub stats: bool,
+    /// Whether to compute [`Commit::branches`] for matching commits.
+    pub branches: bool,
+    /// How merge commits are reported. The mode's filter term must match in
+    /// addition to `filter`.
+    pub merges: MergeMode,
+    /// Whether to follow only the first parent of merges, matching
+    /// `git log --first-parent`. This reports the mainline history of a branch
+    /// without the commits merged into it. [`Commit::branches`] then only lists
+    /// the references whose first-parent history includes the commit.
+    pub first_parent: bool,
+}
+
+impl CommitQuery {
+    /// Creates a new `CommitQuery` for the given committer date range, walking from HEAD.
+    ///
+    /// # Arguments
+    /// - `since`: Inclusive start date for the commit range.
+    /// - `until`: Inclusive end date for the commit range.
+    ///
+    /// # Returns
+    /// A new `CommitQuery` instance.
+    pub fn new(since: DateTime<Utc>, until: DateTime<Utc>) -> Self {
+        Self {
+            since,
+            until,
+            date_field: DateField::default(),
+            refs: RefSelection::default(),
+            range: None,
+            filter: None,
+            paths: Pathspec::default(),
+            stats: false,
+            branches: true,
+            merges: MergeMode::default(),
+            first_parent: false,
+        }
+    }
+
+    /// Sets which commit date the date range applies to.
+    ///
+    /// # Arguments
+    /// - `date_field`: The author or committer date.
+    ///
+    /// # Returns
+    /// The updated `CommitQuery`.
+    pub fn with_date_field(mut self, date_field: DateField) -> Self {
+        self.date_field = date_field;
+        self
+    }
+
+    /// Sets the references to start walking from.
+    ///
+    /// # Arguments
+    /// - `refs`: The reference selection mode.
+    ///
+    /// # Returns
+    /// The updated `CommitQuery`.
+    pub fn with_refs(mut self, refs: RefSelection) -> Self {
+        self.refs = refs;
+        self
+    }
+
+    /// Sets a revision range to walk instead of the selected references.
+    ///
+    /// The date range still applies to the commits in the revision range.
+    ///
+    /// # Arguments
+    /// - `range`: The revision range.
+    ///
+    /// # Returns
+    /// The updated `CommitQuery`.
+    pub fn with_range(mut self, range: RevisionRange) -> Self {
+        self.range = Some(range);
+        self
+    }
+
+    /// Sets a predicate commits must match in addition to the date range.
+    ///
+    /// # Arguments
+    /// - `filter`: The filter to apply.
+    ///
+    /// # Returns
+    /// The updated `CommitQuery`.
+    pub fn with_filter(mut self, filter: CommitFilter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    /// Sets the pathspecs commits must change a matching path of.
+    ///
+    /// # Arguments
+    /// - `paths`: The compiled Git pathspecs, e.g. `deploy/` or `*.toml`.
+    ///
+    /// # Returns
+    /// The updated `CommitQuery`.
+    pub fn with_paths(mut self, paths: Pathspec) -> Self {
+        self.paths = paths;
+        self
+    }
+
+    /// Sets whether to compute the diff statistics of matching commits.
+    ///
+    /// Statistics require a diff of every matching commit, so they are off by
+    /// default.
+    ///
+    /// # Arguments
+    /// - `stats`: `true` to populate [`Commit::stats`].
+    ///
+    /// # Returns
+    /// The updated `CommitQuery`.
+    pub fn with_stats(mut self, stats: bool) -> Self {
+        self.stats = stats;
+        self
+    }
+
+    /// Sets whether to list the references that contain each matching commit.
+    ///
+    /// Working this out walks the history of every selected reference, which
+    /// is wasted when the references are not shown, so callers that do not
+    /// need [`Commit::branches`] can turn it off.
+    ///
+    /// # Arguments
+    /// - `branches`: `true` to populate [`Commit::branches`].
+    ///
+    /// # Returns
+    /// The updated `CommitQuery`.
+    pub fn with_branches(mut self, branches: bool) -> Self {
+        self.branches = branches;
+        self
+    }
+
+    /// Sets how merge commits are reported.
+    ///
+    /// # Arguments
+    /// - `merges`: The merge handling mode.
+    ///
+    /// # Returns
+    /// The updated `CommitQuery`.
+    pub fn with_merges(mut self, merges: MergeMode) -> Self {
+        self.merges = merges;
+        self
+    }
+
+    /// Sets whether to follow only the first parent of merges.
+    ///
+    /// # Arguments
+    /// - `first_parent`: `true` to skip commits only reachable through a
+    ///   merge's other parents.
+    ///
+    /// # Returns
+    /// The updated `CommitQuery`.
+    pub fn with_first_parent(mut self, first_parent: bool) -> Self {
+        self.first_parent = first_parent;
+        self
+    }
+
+    /// Returns `true` if evaluating the query requires the paths each commit
+    /// changes, see [`Commit::paths`].
+    pub fn needs_paths(&self) -> bool {
+        !self.paths.is_empty() || self.filter.as_ref().is_some_and(|f| f.needs_paths())
+    }
+
+    /// Tests whether a commit matches the query's date range, merge mode, paths
+    /// and filter.
+    ///
+    /// Repositories use this to evaluate a query once the commit's fields are
+    /// known; it does not consider which references were walked.
+    ///
+    /// # Arguments
+    /// - `commit`: The commit to test.
+    ///
+    /// # Returns
+    /// `true` if the commit matches.
+    pub fn matches(&self, commit: &Commit) -> bool {
+        let date = commit.date(self.date_field);
+        date >= self.since
+            && date <= self.until
+            && self.merges.filter().is_none_or(|f| f.matches(commit))
+            && self.paths.matches_any(&commit.paths)
+            && self.filter.as_ref().is_none_or(|f| f.matches(commit))
+    }
+
+    /// Returns the date range the revision walk can be limited to.
+    ///
+    /// This is the query's own range, narrowed by any date ranges on the same
+    /// date field that the filter requires.
+    ///
+    /// # Returns
+    /// The inclusive `(since, until)` bounds.
+    pub fn walk_bounds(&self) -> (DateTime<Utc>, DateTime<Utc>) {
+        let (since, until) = self
+            .filter
+            .as_ref()
+            .map_or((None, None), |f| f.date_bounds(self.date_field));
+        (
+            since.map_or(self.since, |since| since.max(self.since)),
+            until.map_or(self.until, |until| until.min(self.until)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_new_query_defaults_to_head() {
+        let now = Utc::now();
+        let query = CommitQuery::new(now, now);
+        assert_eq!(query.since, now);
+        assert_eq!(query.until, now);
+        assert_eq!(query.date_field, DateField::Committer);
+        assert_eq!(query.refs, RefSelection::Head);
+        assert_eq!(query.filter, None);
+        assert!(query.paths.is_empty());
+        assert!(!query.needs_paths());
+        assert!(!query.stats);
+        assert!(query.branches);
+        assert!(query.clone().with_stats(true).stats);
+        assert_eq!(query.merges, MergeMode::Include);
+        assert!(!query.first_parent);
+        assert!(query.with_first_parent(true).first_parent);
+    }
+
+    #[test]
+    fn test_with_date_field() {
+        let now = Utc::now();
+        let query = CommitQuery::new(now, now).with_date_field(DateField::Author);
+        assert_eq!(query.date_field, DateField::Author);
+    }
+
+    #[test]
+    fn test_with_refs() {
+        let now = Utc::now();
+        let patterns = vec!["refs/heads/feature/*".to_string()];
+        let query = CommitQuery::new(now, now).with_refs(RefSelection::Globs(patterns.clone()));
+        assert_eq!(query.refs, RefSelection::Globs(patterns));
+    }
+
+    #[test]
+    fn test_with_range() {
+        let now = Utc::now();
+        let range = RevisionRange::new(Some("v1.0".to_string()), "HEAD".to_string());
+        assert_eq!(range.to_string(), "v1.0..HEAD");
+        assert_eq!(
+            RevisionRange::new(None, "main".to_string()).to_string(),
+            "main"
+        );
+
+        let query = CommitQuery::new(now, now);
+        assert_eq!(query.range, None);
+        assert_eq!(query.with_range(range.clone()).range, Some(range));
+    }
+
+    #[test]
+    fn test_revision_range_from_str() {
+        let parse = |s: &str| s.parse::<RevisionRange>();
+        assert_eq!(
+            parse("v1.2.0..HEAD").unwrap(),
+            RevisionRange::new(Some("v1.2.0".to_string()), "HEAD".to_string())
+        );
+        assert_eq!(
+            parse("v1.2.0..").unwrap(),
+            RevisionRange::new(Some("v1.2.0".to_string()), "HEAD".to_string())
+        );
+        assert_eq!(
+            parse("main...feature").unwrap(),
+            RevisionRange::symmetric("main".to_string(), "feature".to_string())
+        );
+        assert_eq!(
+            parse("...feature").unwrap(),
+            RevisionRange::symmetric("HEAD".to_string(), "feature".to_string())
+        );
+        assert_eq!(
+            parse("v1.3.0").unwrap(),
+            RevisionRange::new(None, "v1.3.0".to_string())
+        );
+        for range in ["main...feature", "v1.2.0..v1.3.0", "HEAD~3"] {
+            assert_eq!(parse(range).unwrap().to_string(), range);
+        }
+        for invalid in ["", " ", "a..b..c", "a...b...c"] {
+            assert!(parse(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_walk_bounds() {
+        let since = Utc.with_ymd_and_hms(2025, 5, 1, 0, 0, 0).unwrap();
+        let until = Utc.with_ymd_and_hms(2025, 5, 31, 0, 0, 0).unwrap();
+        let narrow = Utc.with_ymd_and_hms(2025, 5, 15, 0, 0, 0).unwrap();
+        let query = CommitQuery::new(since, until);
+        assert_eq!(query.walk_bounds(), (since, until));
+
+        let query = query.with_filter(CommitFilter::DateRange {
+            field: DateField::Committer,
+            since: Some(narrow),
+            until: Some(until + chrono::Duration::days(1)),
+        });
+        assert_eq!(query.walk_bounds(), (narrow, until));
+
+        let query = query.with_date_field(DateField::Author);
+        assert_eq!(query.walk_bounds(), (since, until));
+    }
+
+    #[test]
+    fn test_matches() {
+        let date = Utc.with_ymd_and_hms(2025, 5, 4, 0, 0, 0).unwrap();
+        let commit = Commit {
+            committer_date: date.fixed_offset(),
+            hash: crate::commit::CommitHash::new("abcdef1234567890".to_string()),
+            ..Commit::default()
+        };
+        let query = CommitQuery::new(date, date);
+        assert!(query.matches(&commit));
+        assert!(!CommitQuery::new(date + chrono::Duration::seconds(1), date).matches(&commit));
+        assert!(!query
+            .clone()
+            .with_filter(CommitFilter::Merge)
+            .matches(&commit));
+    }
+
+    #[test]
+    fn test_merge_mode() {
+        let date = Utc.with_ymd_and_hms(2025, 5, 4, 0, 0, 0).unwrap();
+        let commit = |parents: usize| Commit {
+            committer_date: date.fixed_offset(),
+            parents: vec!["0".repeat(40); parents],
+            ..Commit::default()
+        };
+        let query = CommitQuery::new(date, date);
+        for (mode, root, single, merge) in [
+            (MergeMode::Include, true, true, true),
+            (MergeMode::Exclude, true, true, false),
+            (MergeMode::Only, false, false, true),
+        ] {
+            let query = query.clone().with_merges(mode);
+            assert_eq!(query.merges, mode);
+            let filtered = CommitQuery::new(date, date);
+            let filtered = match mode.filter() {
+                Some(filter) => filtered.with_filter(filter),
+                None => filtered,
+            };
+            for parents in 0..3 {
+                assert_eq!(
+                    query.matches(&commit(parents)),
+                    filtered.matches(&commit(parents))
+                );
+            }
+            assert_eq!(query.matches(&commit(0)), root);
+            assert_eq!(query.matches(&commit(1)), single);
+            assert_eq!(query.matches(&commit(2)), merge);
+        }
+    }
+
+    #[test]
+    fn test_with_paths() {
+        let date = Utc.with_ymd_and_hms(2025, 5, 4, 0, 0, 0).unwrap();
+        let commit = Commit {
+            committer_date: date.fixed_offset(),
+            paths: vec!["deploy/prod.toml".to_string()],
+            ..Commit::default()
+        };
+        let paths = |specs: &[&str]| Pathspec::new(specs.iter().copied()).unwrap();
+        let query = CommitQuery::new(date, date).with_paths(paths(&["src/", "deploy/"]));
+        assert_eq!(query.paths.patterns(), ["src/", "deploy/"]);
+        assert!(query.needs_paths());
+        assert!(query.matches(&commit));
+        assert!(CommitQuery::new(date, date)
+            .with_paths(paths(&["*.toml"]))
+            .matches(&commit));
+        assert!(!CommitQuery::new(date, date)
+            .with_paths(paths(&["docs"]))
+            .matches(&commit));
+    }
+}
@@ -0,0 +1,434 @@
+//! The `report` module renders commit query results for other programs.
+//!
+//! Reports are written for a list of repositories paired with the commits
+//! found in each. Machine-readable formats carry every field of each commit
+//! along with the repository name, path and HEAD.
+//!
+//! # Delimited output
+//!
+//! [`write_delimited`] writes one row per commit with a header row naming the
+//! selected [`Column`]s. CSV fields are quoted following RFC 4180, so
+//! multi-line commit messages survive a round trip through a spreadsheet. TSV
+//! fields escape tabs, newlines and backslashes as `\t`, `\n`, `\r` and `\\`.
+//!
+//! # JSON schema
+//!
+//! With the `json` feature enabled, [`write_json`] writes an array with one
+//! object per repository, and [`write_ndjson`] writes one object per commit
+//! per line:
+//!
+//! ```text
+//! // write_json
+//! [{"repository": {"name", "path", "head", "parent"}, "commits": [<commit>, ...]}, ...]
+//! // write_ndjson
+//! {"repository": {"name", "path", "head", "parent"}, "commit": <commit>}
+//! ```
+//!
+//! The repository's `parent` names the repository it is a submodule of, and is
+//! `null` otherwise. Each `<commit>` has the fields `title`, `author` (`name`,
+//! `email`), `author_date`, `committer` (`name`, `email`), `committer_date`,
+//! `message`, `hash` (`short`, `full`), `branches`, `parents`, `paths` and
+//! `stats` (`insertions`, `deletions`, `files` of `path`, `insertions`,
+//! `deletions`). Every field is always present: `paths` is `[]` and `stats` is
+//! `null` when the query did not compute them. Dates are RFC 3339 strings in
+//! the timezone they were recorded in.
+
+use crate::commit::{Commit, DateField, DiffStats, DisplayTimezone};
+use crate::repository::{GitRepository, Repository};
+use crate::{Result, WalrustError};
+use std::fmt;
+use std::io::Write;
+use std::str::FromStr;
+
+/// The repository fields included in machine-readable reports.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+pub struct RepositoryInfo {
+    /// The name of the repository.
+    pub name: String,
+    /// The path to the repository.
+    pub path: String,
+    /// The commit hash at HEAD.
+    pub head: String,
+    /// The name of the repository this one is a submodule of, if any.
+    pub parent: Option<String>,
+}
+
+impl RepositoryInfo {
+    /// Collects the reported fields of a repository.
+    ///
+    /// # Arguments
+    /// - `repo`: The repository to describe.
+    ///
+    /// # Returns
+    /// A new `RepositoryInfo` instance.
+    pub fn new<G: GitRepository>(repo: &Repository<G>) -> Self {
+        Self {
+            name: repo.name.clone(),
+            path: repo.uri.to_string_lossy().to_string(),
+            head: repo.vcs.head(),
+            parent: repo.parent.clone(),
+        }
+    }
+}
+
+/// A repository and its commits, as written by [`write_json`].
+#[cfg(feature = "json")]
+#[derive(serde::Serialize)]
+struct RepositoryReport<'a> {
+    repository: RepositoryInfo,
+    commits: &'a [Commit],
+}
+
+/// A single commit and its repository, as written by [`write_ndjson`].
+#[cfg(feature = "json")]
+#[derive(serde::Serialize)]
+struct CommitRecord<'a> {
+    repository: &'a RepositoryInfo,
+    commit: &'a Commit,
+}
+
+/// Writes the results as a pretty-printed JSON array.
+///
+/// # Arguments
+/// - `writer`: The destination for the report.
+/// - `results`: The repositories and their commits.
+///
+/// # Errors
+/// Returns an error if writing to `writer` fails.
+#[cfg(feature = "json")]
+pub fn write_json<G: GitRepository, W: Write>(
+    mut writer: W,
+    results: &[(Repository<G>, Vec<Commit>)],
+) -> Result<()> {
+    let reports: Vec<_> = results
+        .iter()
+        .map(|(repo, commits)| RepositoryReport {
+            repository: RepositoryInfo::new(repo),
+            commits,
+        })
+        .collect();
+    serde_json::to_writer_pretty(&mut writer, &reports).map_err(std::io::Error::from)?;
+    writeln!(writer)?;
+    Ok(())
+}
+
+/// Writes the results as newline-delimited JSON, one commit per line.
+///
+/// # Arguments
+/// - `writer`: The destination for the report.
+/// - `results`: The repositories and their commits.
+///
+/// # Errors
+/// Returns an error if writing to `writer` fails.
+#[cfg(feature = "json")]
+pub fn write_ndjson<G: GitRepository, W: Write>(
+    mut writer: W,
+    results: &[(Repository<G>, Vec<Commit>)],
+) -> Result<()> {
+    for (repo, commits) in results {
+        let repository = RepositoryInfo::new(repo);
+        for commit in commits {
+            let record = CommitRecord {
+                repository: &repository,
+                commit,
+            };
+            serde_json::to_writer(&mut writer, &record).map_err(std::io::Error::from)?;
+            writeln!(writer)?;
+        }
+    }
+    Ok(())
+}
+
+/// Options controlling how commit dates are rendered in human-readable reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ReportOptions {
+    /// The commit date used for the generic `date` field.
+    pub date_field: DateField,
+    /// The timezone dates are displayed in.
+    pub timezone: DisplayTimezone,
+}
+
+/// A column in a delimited report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Column {
+    /// The repository name.
+    Repo,
+    /// The repository path.
+    Path,
+    /// The commit hash at the repository HEAD.
+    Head,
+    /// The full commit hash.
+    Hash,
+    /// The short commit hash.
+    ShortHash,
+    /// The commit title.
+    Title,
+    /// The full commit message.
+    Message,
+    /// The author in `Name <email>` format.
+    Author,
+    /// The author name.
+    AuthorName,
+    /// The author email.
+    AuthorEmail,
+    /// The author date.
+    AuthorDate,
+    /// The committer in `Name <email>` format.
+    Committer,
+    /// The committer name.
+    CommitterName,
+    /// The committer email.
+    CommitterEmail,
+    /// The committer date.
+    CommitterDate,
+    /// The author or committer date, as selected by [`ReportOptions::date_field`].
+    Date,
+    /// The refs that contain the commit, separated by `", "`.
+    Branches,
+    /// The number of files changed, if the query computed diff statistics.
+    FilesChanged,
+    /// The number of lines added, if the query computed diff statistics.
+    Insertions,
+    /// The number of lines removed, if the query computed diff statistics.
+    Deletions,
+}
+
+impl Column {
+    /// Every column, in the order they are documented.
+    pub const ALL: [Column; 20] = [
+        Column::Repo,
+        Column::Path,
+        Column::Head,
+        Column::Hash,
+        Column::ShortHash,
+        Column::Title,
+        Column::Message,
+        Column::Author,
+        Column::AuthorName,
+        Column::AuthorEmail,
+        Column::AuthorDate,
+        Column::Committer,
+        Column::CommitterName,
+        Column::CommitterEmail,
+        Column::CommitterDate,
+        Column::Date,
+        Column::Branches,
+        Column::FilesChanged,
+        Column::Insertions,
+        Column::Deletions,
+    ];
+
+    /// The columns written when none are selected.
+    pub const DEFAULT: [Column; 5] = [
+        Column::Repo,
+        Column::Hash,
+        Column::Author,
+        Column::Date,
+        Column::Title,
+    ];
+
+    /// Returns the name of the column, as used in header rows and on the command line.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Column::Repo => "repo",
+            Column::Path => "path",
+            Column::Head => "head",
+            Column::Hash => "hash",
+            Column::ShortHash => "short_hash",
+            Column::Title => "title",
+            Column::Message => "message",
+            Column::Author => "author",
+            Column::AuthorName => "author_name",
+            Column::AuthorEmail => "author_email",
+            Column::AuthorDate => "author_date",
+            Column::Committer => "committer",
+            Column::CommitterName => "committer_name",
+            Column::CommitterEmail => "committer_email",
+            Column::CommitterDate => "committer_date",
+            Column::Date => "date",
+            Column::Branches => "branches",
+            Column::FilesChanged => "files_changed",
+            Column::Insertions => "insertions",
+            Column::Deletions => "deletions",
+        }
+    }
+
+    /// Returns the value of the column for a commit.
+    ///
+    /// # Arguments
+    /// - `repo`: The repository the commit belongs to.
+    /// - `commit`: The commit to read the value from.
+    /// - `options`: The options controlling date rendering.
+    ///
+    /// # Returns
+    /// The unescaped value of the column.
+    pub fn value(&self, repo: &RepositoryInfo, commit: &Commit, options: &ReportOptions) -> String {
+        match self {
+            Column::Repo => repo.name.clone(),
+            Column::Path => repo.path.clone(),
+            Column::Head => repo.head.clone(),
+            Column::Hash => commit.hash.full.clone(),
+            Column::ShortHash => commit.hash.short.clone(),
+            Column::Title => commit.title.clone(),
+            Column::Message => commit.message.clone(),
+            Column::Author => commit.author.to_string(),
+            Column::AuthorName => commit.author.name.clone(),
+            Column::AuthorEmail => commit.author.email.clone(),
+            Column::AuthorDate => options.timezone.format(&commit.author_date),
+            Column::Committer => commit.committer.to_string(),
+            Column::CommitterName => commit.committer.name.clone(),
+            Column::CommitterEmail => commit.committer.email.clone(),
+            Column::CommitterDate => options.timezone.format(&commit.committer_date),
+            Column::Date => options.timezone.format(&commit.date(options.date_field)),
+            Column::Branches => commit.branches.join(", "),
+            Column::FilesChanged => stat(commit, DiffStats::files_changed),
+            Column::Insertions => stat(commit, |stats| stats.insertions),
+            Column::Deletions => stat(commit, |stats| stats.deletions),
+        }
+    }
+}
+
+/// Formats one of a commit's diff statistics, or an empty string if it has none.
+fn stat(commit: &Commit, value: impl Fn(&DiffStats) -> usize) -> String {
+    commit
+        .stats
+        .as_ref()
+        .map(|stats| value(stats).to_string())
+        .unwrap_or_default()
+}
+
+impl fmt::Display for Column {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Column {
+    type Err = WalrustError;
+
+    /// Parses a column name such as `"hash"` or `"author_email"` into a `Column`.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Column::ALL
+            .iter()
+            .find(|column| column.name() == s)
+            .copied()
+            .ok_or_else(|| {
+                let names: Vec<_> = Column::ALL.iter().map(|c| c.name()).collect();
+                WalrustError::ParseError(format!(
+                    "unknown column '{}', expected one of: {}",
+                    s,
+                    names.join(", ")
+                ))
+            })
+    }
+}
+
+/// The field separator used by [`write_delimited`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Delimiter {
+    /// Comma-separated values, quoted following RFC 4180.
+    Comma,
+    /// Tab-separated values, with special characters backslash-escaped.
+    Tab,
+}
+
+impl Delimiter {
+    /// Escapes a single field for this delimiter.
+    ///
+    /// # Arguments
+    /// - `field`: The raw field value.
+    ///
+    /// # Returns
+    /// The field, quoted or escaped as needed.
+    pub fn escape(&self, field: &str) -> String {
+        match self {
+            Delimiter::Comma => {
+                if field.contains([',', '"', '\n', '\r']) {
+                    format!("\"{}\"", field.replace('"', "\"\""))
+                } else {
+                    field.to_string()
+                }
+            }
+            Delimiter::Tab => field
+                .replace('\\', "\\\\")
+                .replace('\t', "\\t")
+                .replace('\n', "\\n")
+                .replace('\r', "\\r"),
+        }
+    }
+
+    /// Returns the separator character.
+    fn separator(&self) -> &'static str {
+        match self {
+            Delimiter::Comma => ",",
+            Delimiter::Tab => "\t",
+        }
+    }
+}
+
+/// Writes the results as delimited rows, one per commit, after a header row.
+///
+/// # Arguments
+/// - `writer`: The destination for the report.
+/// - `results`: The repositories and their commits.
+/// - `delimiter`: The field separator and escaping rules.
+/// - `columns`: The columns to write, in order.
+/// - `options`: The options controlling date rendering.
+///
+/// # Errors
+/// Returns an error if writing to `writer` fails.
+pub fn write_delimited<G: GitRepository, W: Write>(
+    mut writer: W,
+    results: &[(Repository<G>, Vec<Commit>)],
+    delimiter: Delimiter,
+    columns: &[Column],
+    options: &ReportOptions,
+) -> Result<()> {
+    let header: Vec<_> = columns.iter().map(|c| delimiter.escape(c.name())).collect();
+    writeln!(writer, "{}", header.join(delimiter.separator()))?;
+
+    for (repo, commits) in results {
+        let repository = RepositoryInfo::new(repo);
+        for commit in commits {
+            let row: Vec<_> = columns
+                .iter()
+                .map(|c| delimiter.escape(&c.value(&repository, commit, options)))
+                .collect();
+            writeln!(writer, "{}", row.join(delimiter.separator()))?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_column_from_str() {
+        for column in Column::ALL {
+            assert_eq!(column.name().parse::<Column>().unwrap(), column);
+        }
+        assert!("sha".parse::<Column>().is_err());
+    }
+
+    #[test]
+    fn test_csv_escape() {
+        let csv = Delimiter::Comma;
+        assert_eq!(csv.escape("plain"), "plain");
+        assert_eq!(csv.escape("a,b"), "\"a,b\"");
+        assert_eq!(csv.escape("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv.escape("line 1\nline 2"), "\"line 1\nline 2\"");
+        assert_eq!(csv.escape(""), "");
+    }
+
+    #[test]
+    fn test_tsv_escape() {
+        let tsv = Delimiter::Tab;
+        assert_eq!(tsv.escape("plain"), "plain");
+        assert_eq!(tsv.escape("a\tb"), "a\\tb");
+        assert_eq!(tsv.escape("line 1\r\nline 2"), "line 1\\r\\nline 2");
+        assert_eq!(tsv.escape("C:\\path"), "C:\\\\path");
+    }
+}
@@ -1,19 +1,26 @@
-use crate::commit::{Commit, CommitAuthor, CommitHash};
+use crate::commit::{Commit, CommitAuthor, CommitHash, DateField, DiffStats, FileStats};
+use crate::query::{CommitQuery, RefSelection, RevisionRange};
 use crate::{Result, WalrustError};
-use chrono::{DateTime, Utc};
-use git2::Repository as LibGitRepository;
-use std::path::PathBuf;
+use chrono::{DateTime, FixedOffset};
+use git2::{
+    BranchType, DiffDelta, ErrorCode, Mailmap, Oid, Patch, Repository as LibGitRepository,
+    RepositoryOpenFlags, Signature,
+};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
 
 /// A trait representing a Git repository.
 ///
 /// This trait provides methods for interacting with a Git repository,
 /// including checking if a path is a repository, creating a new instance,
-/// retrieving the current HEAD, and getting commits within a date range.
+/// retrieving the current HEAD, and getting commits matching a query.
 ///
 /// # Example
 /// ```rust
 /// use walrust::repository::{GitRepository, LocalGitRepository};
-/// use std::path::PathBuf;
+/// use std::path::{Path, PathBuf};
 ///
 /// let repo = LocalGitRepository::new(&PathBuf::from(".")).unwrap();
 /// println!("HEAD: {}", repo.head());
@@ -31,7 +38,7 @@ pub trait GitRepository {
     /// If the repository cannot be opened, an error is returned.
     /// If the path is not a valid Git repository, an error is returned.
     /// If the path does not exist, an error is returned.
-    fn new(path: &PathBuf) -> Result<Self>
+    fn new(path: &Path) -> Result<Self>
     where
         Self: Sized;
 
@@ -42,47 +49,116 @@ pub trait GitRepository {
     /// A string representing the current HEAD commit hash.
     fn head(&self) -> String;
 
-    /// Get the commits in the repository between two dates.
+    /// Get the commits in the repository matching a query.
     ///
     /// # Arguments
     ///
-    /// * `since` - Inclusive start date for the commit range.
-    /// * `until` - Inclusive end date for the commit range.
+    /// * `query` - The date range, filter, and references or revision range to walk.
     ///
     /// # Returns
     ///
-    /// A vector of commits within the specified date range.
+    /// A vector of commits whose author or committer date, as selected by the
+    /// query, is within the specified date range, that pass the query's merge
+    /// mode, that change the query's paths, that match the query's filter, and
+    /// that are reachable from the selected references or in the revision
+    /// range, following only first parents if the query asks to. Each commit
+    /// is reported once, with the selected references that contain it listed
+    /// in `Commit::branches` unless the query turns that off.
     ///
     /// # Errors
     ///
-    /// Returns an error if the commit retrieval fails.
-    fn get_commits(&self, since: DateTime<Utc>, until: DateTime<Utc>) -> Result<Vec<Commit>>;
+    /// Returns `WalrustError::RevisionNotFound` if a revision of the query's
+    /// range does not exist in the repository, or another error if the commit
+    /// retrieval fails.
+    fn get_commits(&self, query: &CommitQuery) -> Result<Vec<Commit>>;
+
+    /// Get the submodules of the repository.
+    ///
+    /// # Returns
+    ///
+    /// The paths of the configured submodules, relative to the working
+    /// directory, whether or not they are checked out. The default
+    /// implementation reports none.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the submodule configuration cannot be read.
+    fn submodules(&self) -> Result<Vec<PathBuf>> {
+        Ok(Vec::new())
+    }
+
+    /// Get the Git directory shared by every worktree of the repository.
+    ///
+    /// # Returns
+    ///
+    /// The `.git` directory of the main worktree, or the repository itself
+    /// when it is bare, so linked worktrees of the same repository report the
+    /// same directory. The default implementation returns `None`, leaving the
+    /// repository to be identified by its path.
+    fn common_dir(&self) -> Option<PathBuf> {
+        None
+    }
+
+    /// Check whether the repository is a linked worktree of another, as
+    /// created by `git worktree add`.
+    ///
+    /// # Returns
+    ///
+    /// `true` for linked worktrees. The default implementation returns `false`.
+    fn is_linked_worktree(&self) -> bool {
+        false
+    }
 }
 
+/// The number of consecutive commits older than the start of a query that are
+/// inspected before a revision walk stops.
+///
+/// Commits are walked newest first, but a rebased or cherry-picked commit can
+/// carry a date older than commits behind it. Looking past a bounded run of
+/// old commits keeps the walk fast while tolerating out-of-order dates.
+const LOOKAHEAD_LIMIT: usize = 32;
+
 /// A Git repository on the local filesystem.
 ///
 /// This struct provides an implementation of the `GitRepository` trait
-/// for repositories stored on the local filesystem.
+/// for repositories stored on the local filesystem. It is `Send` but not
+/// `Sync`, so it can be moved to a worker thread but not shared between them.
 ///
 /// # Example
 /// ```rust
 /// use walrust::repository::{GitRepository, LocalGitRepository};
-/// use std::path::PathBuf;
+/// use std::path::{Path, PathBuf};
 ///
 /// let repo = LocalGitRepository::new(&PathBuf::from(".")).unwrap();
 /// println!("HEAD: {}", repo.head());
 /// ```
 pub struct LocalGitRepository {
     git: LibGitRepository,
+    /// Whether authors and committers are resolved through the mailmap.
+    use_mailmap: bool,
+    /// The contents of a mailmap file applied on top of the repository's own.
+    global_mailmap: Option<String>,
 }
 
 impl GitRepository for LocalGitRepository {
-    fn new(path: &PathBuf) -> Result<Self> {
-        if !path.join(".git").exists() {
-            return Err(WalrustError::PathError(path.clone()));
-        }
-        let git = LibGitRepository::open(path).map_err(|e| WalrustError::GitError(e))?;
-        Ok(LocalGitRepository { git })
+    /// Opens a working tree whose `.git` is a directory or a `gitdir:` file,
+    /// such as a linked worktree or a submodule, or a bare repository. Parent
+    /// directories are not searched.
+    fn new(path: &Path) -> Result<Self> {
+        let git = LibGitRepository::open_ext(
+            path,
+            RepositoryOpenFlags::NO_SEARCH,
+            std::iter::empty::<&OsStr>(),
+        )
+        .map_err(|err| match err.code() {
+            ErrorCode::NotFound => WalrustError::PathError(path.to_path_buf()),
+            _ => WalrustError::GitError(err),
+        })?;
+        Ok(LocalGitRepository {
+            git,
+            use_mailmap: false,
+            global_mailmap: None,
+        })
     }
 
     fn head(&self) -> String {
@@ -93,49 +169,536 @@ impl GitRepository for LocalGitRepository {
             .unwrap_or_else(|_| "HEAD".to_string())
     }
 
-    fn get_commits(&self, since: DateTime<Utc>, until: DateTime<Utc>) -> Result<Vec<Commit>> {
+    fn get_commits(&self, query: &CommitQuery) -> Result<Vec<Commit>> {
+        let (tips, hidden) = match &query.range {
+            Some(range) => self.resolve_range(range)?,
+            None => (self.resolve_refs(&query.refs)?, Vec::new()),
+        };
+        let labels = ref_labels(&tips);
+        let mailmap = self.mailmap()?;
+        let (since, until) = query.walk_bounds();
+        let needs_paths = query.needs_paths();
+
         let mut revwalk = self.git.revwalk()?;
-        revwalk.push_head()?; // Start from HEAD
+        for oid in tips.values() {
+            revwalk.push(*oid)?;
+        }
+        for oid in &hidden {
+            revwalk.hide(*oid)?;
+        }
         revwalk.set_sorting(git2::Sort::TIME)?; // Sort commits by time (newest to oldest)
+        if query.first_parent {
+            revwalk.simplify_first_parent()?;
+        }
         let mut commits = Vec::new();
+        let mut oids = Vec::new();
+        let mut older_streak = 0;
 
         for oid in revwalk {
             let oid = oid?;
             let commit = self.git.find_commit(oid)?;
 
-            let commit_time = commit.time().seconds();
-            let offset = commit.time().offset_minutes();
-            let commit_date =
-                DateTime::from_timestamp(commit_time, 0).ok_or(WalrustError::GitError(
-                    git2::Error::from_str("Failed to convert commit time to DateTime"),
-                ))?;
-            let commit_date = commit_date + chrono::Duration::minutes(offset as i64);
+            let author_date = to_datetime(commit.author().when())?;
+            let committer_date = to_datetime(commit.committer().when())?;
+            let commit_date = match query.date_field {
+                DateField::Author => author_date,
+                DateField::Committer => committer_date,
+            };
 
-            // Stop processing if the commit is older than the `since` date
-            if commit_date < since {
-                break;
+            // Stop processing once enough consecutive commits are older than the
+            // `since` date, so a single out-of-order commit cannot hide newer ones.
+            // The walk is sorted by committer date, so that is what decides when to
+            // stop, whichever date the query filters on.
+            if committer_date < since {
+                older_streak += 1;
+                if older_streak >= LOOKAHEAD_LIMIT {
+                    break;
+                }
+                continue;
             }
+            older_streak = 0;
 
             // Only include commits within the date range
-            if commit_date <= until {
+            if commit_date >= since && commit_date <= until {
                 let commit_hash = CommitHash::new(commit.id().to_string());
-                let commit_author = CommitAuthor::new(
-                    commit.author().name().unwrap_or_default().to_string(),
-                    commit.author().email().unwrap_or_default().to_string(),
-                );
+                let (commit_author, commit_committer) = match &mailmap {
+                    Some(mailmap) => (
+                        to_author(&commit.author_with_mailmap(mailmap)?),
+                        to_author(&commit.committer_with_mailmap(mailmap)?),
+                    ),
+                    None => (to_author(&commit.author()), to_author(&commit.committer())),
+                };
 
-                commits.push(Commit::new(
+                let mut result = Commit::new(
                     commit.summary().unwrap_or_default().to_string(),
                     commit_author,
-                    commit_date,
+                    author_date,
+                    commit_committer,
+                    committer_date,
                     commit.message().unwrap_or_default().to_string(),
                     commit_hash,
-                ));
+                );
+                result.parents = commit.parent_ids().map(|id| id.to_string()).collect();
+                if needs_paths {
+                    result.paths = self.changed_paths(&commit)?;
+                }
+                if !query.matches(&result) {
+                    continue;
+                }
+                if query.stats {
+                    result.stats = Some(self.diff_stats(&commit)?);
+                }
+                commits.push(result);
+                oids.push(oid);
+            }
+        }
+
+        if query.branches && !commits.is_empty() {
+            let mut containing = self.containing_refs(&oids, &tips, &hidden, query.first_parent)?;
+            for (commit, oid) in commits.iter_mut().zip(&oids) {
+                let mut names: Vec<_> = containing
+                    .remove(oid)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|name| labels[name].clone())
+                    .collect();
+                names.sort();
+                commit.branches = names;
             }
         }
 
         Ok(commits)
     }
+
+    fn submodules(&self) -> Result<Vec<PathBuf>> {
+        Ok(self
+            .git
+            .submodules()?
+            .iter()
+            .map(|submodule| submodule.path().to_path_buf())
+            .collect())
+    }
+
+    /// Reads the `commondir` file linked worktrees keep in their Git
+    /// directory, which points at the main repository's.
+    fn common_dir(&self) -> Option<PathBuf> {
+        let git_dir = self.git.path();
+        match fs::read_to_string(git_dir.join("commondir")) {
+            Ok(common) => Some(git_dir.join(common.trim())),
+            Err(_) => Some(git_dir.to_path_buf()),
+        }
+    }
+
+    fn is_linked_worktree(&self) -> bool {
+        self.git.is_worktree()
+    }
+}
+
+impl LocalGitRepository {
+    /// Resolves commit authors and committers through a mailmap.
+    ///
+    /// The repository's mailmap is read the same way Git does, from `.mailmap`
+    /// and the `mailmap.file` and `mailmap.blob` settings. If `global` is given,
+    /// its entries are added to the repository's mailmap and take precedence
+    /// over entries for the same identity.
+    ///
+    /// # Arguments
+    ///
+    /// * `global` - The contents of an extra mailmap file, if any.
+    pub fn enable_mailmap(&mut self, global: Option<String>) {
+        self.use_mailmap = true;
+        self.global_mailmap = global;
+    }
+
+    /// Loads the mailmap used to resolve identities.
+    ///
+    /// # Returns
+    ///
+    /// The mailmap, or `None` if mailmap support is disabled.
+    fn mailmap(&self) -> Result<Option<Mailmap>> {
+        if !self.use_mailmap {
+            return Ok(None);
+        }
+        let mut mailmap = self.git.mailmap()?;
+        if let Some(global) = &self.global_mailmap {
+            add_mailmap_entries(&mut mailmap, global)?;
+        }
+        Ok(Some(mailmap))
+    }
+
+    /// Resolves a reference selection to the commits it points at.
+    ///
+    /// # Arguments
+    ///
+    /// * `refs` - The reference selection mode.
+    ///
+    /// # Returns
+    ///
+    /// A map from full reference name to the commit it points at.
+    fn resolve_refs(&self, refs: &RefSelection) -> Result<BTreeMap<String, Oid>> {
+        let mut tips = BTreeMap::new();
+        match refs {
+            RefSelection::Head => insert_tip(&mut tips, &self.git.head()?),
+            RefSelection::LocalBranches => {
+                for branch in self.git.branches(Some(BranchType::Local))? {
+                    let (branch, _) = branch?;
+                    insert_tip(&mut tips, branch.get());
+                }
+            }
+            RefSelection::AllRefs => {
+                for reference in self.git.references()? {
+                    insert_tip(&mut tips, &reference?);
+                }
+                if self.git.head_detached()? {
+                    insert_tip(&mut tips, &self.git.head()?);
+                }
+            }
+            RefSelection::Globs(patterns) => {
+                for pattern in patterns {
+                    let pattern = match pattern.starts_with("refs/") {
+                        true => pattern.clone(),
+                        false => format!("refs/{}", pattern),
+                    };
+                    for reference in self.git.references_glob(&pattern)? {
+                        insert_tip(&mut tips, &reference?);
+                    }
+                }
+            }
+        }
+        Ok(tips)
+    }
+
+    /// Resolves a revision, such as a tag, branch or hash, to a commit.
+    ///
+    /// # Arguments
+    ///
+    /// * `revision` - The revision to resolve.
+    ///
+    /// # Returns
+    ///
+    /// The id of the commit the revision points at.
+    ///
+    /// # Errors
+    ///
+    /// Returns `WalrustError::RevisionNotFound` if the repository has no such
+    /// revision.
+    fn resolve_revision(&self, revision: &str) -> Result<Oid> {
+        let object = self
+            .git
+            .revparse_single(revision)
+            .map_err(|err| match err.code() {
+                ErrorCode::NotFound | ErrorCode::InvalidSpec => {
+                    WalrustError::RevisionNotFound(revision.to_string())
+                }
+                _ => WalrustError::GitError(err),
+            })?;
+        Ok(object.peel_to_commit()?.id())
+    }
+
+    /// Resolves a revision range to the commits a walk starts from and the
+    /// commits whose history it excludes.
+    ///
+    /// A symmetric range starts from both sides and excludes the history of
+    /// their merge bases. Sides without a common ancestor exclude nothing.
+    ///
+    /// # Arguments
+    ///
+    /// * `range` - The revision range to resolve.
+    ///
+    /// # Returns
+    ///
+    /// The tips to walk from, keyed by revision, and the commits to hide.
+    ///
+    /// # Errors
+    ///
+    /// Returns `WalrustError::RevisionNotFound` if either side of the range
+    /// does not exist in the repository.
+    fn resolve_range(&self, range: &RevisionRange) -> Result<(BTreeMap<String, Oid>, Vec<Oid>)> {
+        let to = self.resolve_revision(&range.to)?;
+        let mut tips = BTreeMap::from([(range.to.clone(), to)]);
+        let Some(from_name) = &range.from else {
+            return Ok((tips, Vec::new()));
+        };
+        let from = self.resolve_revision(from_name)?;
+        if !range.symmetric {
+            return Ok((tips, vec![from]));
+        }
+
+        tips.insert(from_name.clone(), from);
+        let bases = match self.git.merge_bases(from, to) {
+            Ok(bases) => bases.iter().copied().collect(),
+            Err(err) if err.code() == ErrorCode::NotFound => Vec::new(),
+            Err(err) => return Err(err.into()),
+        };
+        Ok((tips, bases))
+    }
+
+    /// Compares a commit against its first parent.
+    ///
+    /// Root commits are compared against an empty tree, so every file they add
+    /// is part of the diff.
+    ///
+    /// # Arguments
+    ///
+    /// * `commit` - The commit to inspect.
+    ///
+    /// # Returns
+    ///
+    /// The diff from the first parent's tree to the commit's tree.
+    fn first_parent_diff(&self, commit: &git2::Commit) -> Result<git2::Diff<'_>> {
+        let parent_tree = match commit.parent(0) {
+            Ok(parent) => Some(parent.tree()?),
+            Err(_) => None,
+        };
+        Ok(self
+            .git
+            .diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), None)?)
+    }
+
+    /// Lists the paths a commit changes relative to its first parent.
+    ///
+    /// # Arguments
+    ///
+    /// * `commit` - The commit to inspect.
+    ///
+    /// # Returns
+    ///
+    /// The changed paths, relative to the repository root.
+    fn changed_paths(&self, commit: &git2::Commit) -> Result<Vec<String>> {
+        let diff = self.first_parent_diff(commit)?;
+        Ok(diff.deltas().map(|delta| delta_path(&delta)).collect())
+    }
+
+    /// Counts the lines a commit adds and removes relative to its first parent.
+    ///
+    /// # Arguments
+    ///
+    /// * `commit` - The commit to inspect.
+    ///
+    /// # Returns
+    ///
+    /// The statistics of every changed file. Binary files have no line counts.
+    fn diff_stats(&self, commit: &git2::Commit) -> Result<DiffStats> {
+        let diff = self.first_parent_diff(commit)?;
+        let mut files = Vec::new();
+        for (index, delta) in diff.deltas().enumerate() {
+            let (insertions, deletions) = match Patch::from_diff(&diff, index)? {
+                Some(patch) => {
+                    let (_, insertions, deletions) = patch.line_stats()?;
+                    (insertions, deletions)
+                }
+                None => (0, 0),
+            };
+            files.push(FileStats {
+                path: delta_path(&delta),
+                insertions,
+                deletions,
+            });
+        }
+        Ok(DiffStats::new(files))
+    }
+
+    /// Works out which references contain each of the given commits.
+    ///
+    /// The history is walked once from every reference, children before
+    /// parents, carrying the set of references each commit is reachable from
+    /// down to its parents. The walk stops once every commit in `oids` has
+    /// been reached. With `first_parent` set, a reference only contains the
+    /// commits on its first-parent history, as in the commit walk itself.
+    ///
+    /// # Arguments
+    ///
+    /// * `oids` - The commits to look for.
+    /// * `tips` - The resolved references, keyed by full name.
+    /// * `hidden` - The commits whose history the walk excludes.
+    /// * `first_parent` - Whether to follow only the first parent of merges.
+    ///
+    /// # Returns
+    ///
+    /// A map from each commit to the full names of the references whose
+    /// history includes it.
+    fn containing_refs<'a>(
+        &self,
+        oids: &[Oid],
+        tips: &'a BTreeMap<String, Oid>,
+        hidden: &[Oid],
+        first_parent: bool,
+    ) -> Result<HashMap<Oid, Vec<&'a String>>> {
+        let names: Vec<_> = tips.keys().collect();
+        let words = names.len().div_ceil(64);
+        let mut pending: HashSet<Oid> = oids.iter().copied().collect();
+        let mut reachable: HashMap<Oid, Vec<u64>> = HashMap::new();
+        for (index, tip) in tips.values().enumerate() {
+            let bits = reachable.entry(*tip).or_insert_with(|| vec![0; words]);
+            bits[index / 64] |= 1 << (index % 64);
+        }
+
+        let mut revwalk = self.git.revwalk()?;
+        for oid in tips.values() {
+            revwalk.push(*oid)?;
+        }
+        for oid in hidden {
+            revwalk.hide(*oid)?;
+        }
+        revwalk.set_sorting(git2::Sort::TOPOLOGICAL)?;
+        if first_parent {
+            revwalk.simplify_first_parent()?;
+        }
+
+        let mut containing = HashMap::new();
+        for oid in revwalk {
+            if pending.is_empty() {
+                break;
+            }
+            let oid = oid?;
+            let Some(bits) = reachable.remove(&oid) else {
+                continue;
+            };
+            if pending.remove(&oid) {
+                let refs = (0..names.len())
+                    .filter(|index| bits[index / 64] & (1 << (index % 64)) != 0)
+                    .map(|index| names[index])
+                    .collect();
+                containing.insert(oid, refs);
+            }
+            let commit = self.git.find_commit(oid)?;
+            let parents = commit
+                .parent_ids()
+                .take(if first_parent { 1 } else { usize::MAX });
+            for parent in parents {
+                let parent_bits = reachable.entry(parent).or_insert_with(|| vec![0; words]);
+                for (word, bit) in parent_bits.iter_mut().zip(&bits) {
+                    *word |= bit;
+                }
+            }
+        }
+        Ok(containing)
+    }
+}
+
+/// Converts a git timestamp to a `DateTime` in the timezone it was recorded in.
+///
+/// # Errors
+/// Returns an error if the timestamp or its offset is out of range.
+fn to_datetime(time: git2::Time) -> Result<DateTime<FixedOffset>> {
+    let offset = FixedOffset::east_opt(time.offset_minutes() * 60);
+    let date = DateTime::from_timestamp(time.seconds(), 0);
+    match (date, offset) {
+        (Some(date), Some(offset)) => Ok(date.with_timezone(&offset)),
+        _ => Err(WalrustError::GitError(git2::Error::from_str(
+            "Failed to convert commit time to DateTime",
+        ))),
+    }
+}
+
+/// Returns the path a diff delta changes, preferring the new path of a rename.
+fn delta_path(delta: &DiffDelta) -> String {
+    delta
+        .new_file()
+        .path()
+        .or(delta.old_file().path())
+        .map(|path| path.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// Converts a git signature to a `CommitAuthor`.
+fn to_author(signature: &Signature) -> CommitAuthor {
+    CommitAuthor::new(
+        signature.name().unwrap_or_default().to_string(),
+        signature.email().unwrap_or_default().to_string(),
+    )
+}
+
+/// Adds the entries of a mailmap file to a mailmap, replacing entries for the
+/// same identity.
+///
+/// Each line maps the identity in its last `<email>`, optionally with the name
+/// before it, to the name and email before that, as in
+/// `Proper Name <proper@email> Commit Name <commit@email>`. A line with a
+/// single `Proper Name <commit@email>` only replaces the name. Comments and
+/// malformed lines are skipped, as Git does.
+///
+/// # Errors
+/// Returns an error if an entry cannot be added.
+fn add_mailmap_entries(mailmap: &mut Mailmap, contents: &str) -> Result<()> {
+    fn non_empty(text: &str) -> Option<&str> {
+        Some(text.trim()).filter(|text| !text.is_empty())
+    }
+    for line in contents.lines() {
+        let line = line.trim_start();
+        if line.starts_with('#') {
+            continue;
+        }
+        let mut identities = Vec::new();
+        let mut rest = line;
+        while let Some((name, tail)) = rest.split_once('<') {
+            let Some((email, tail)) = tail.split_once('>') else {
+                break;
+            };
+            identities.push((non_empty(name), email.trim()));
+            rest = tail;
+        }
+        match identities.as_slice() {
+            [(name, email)] => mailmap.add_entry(*name, None, None, email)?,
+            [(real_name, real_email), (replace_name, replace_email), ..] => mailmap.add_entry(
+                *real_name,
+                non_empty(real_email),
+                *replace_name,
+                replace_email,
+            )?,
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Records the commit a reference points at under its full name.
+///
+/// Full names keep references that share a short name, such as the branch
+/// `refs/heads/v1` and the tag `refs/tags/v1`, apart.
+/// References that do not point at a commit (e.g. tags of trees) are skipped.
+fn insert_tip(tips: &mut BTreeMap<String, Oid>, reference: &git2::Reference) {
+    if let (Some(name), Ok(commit)) = (reference.name(), reference.peel_to_commit()) {
+        tips.insert(name.to_string(), commit.id());
+    }
+}
+
+/// Chooses the name each reference is displayed under.
+///
+/// References are shown by their short name, e.g. `main` for
+/// `refs/heads/main`. When several references share a short name, each is
+/// shown relative to `refs/` instead, e.g. `heads/v1` and `tags/v1`, as Git
+/// does for ambiguous names. Names that are not full reference names, such
+/// as the revisions of a range, are shown as they are.
+///
+/// # Arguments
+///
+/// * `tips` - The resolved references, keyed by full name.
+///
+/// # Returns
+///
+/// A map from full reference name to display name.
+fn ref_labels(tips: &BTreeMap<String, Oid>) -> BTreeMap<String, String> {
+    let short = |name: &str| {
+        ["refs/heads/", "refs/tags/", "refs/remotes/", "refs/"]
+            .iter()
+            .find_map(|prefix| name.strip_prefix(prefix))
+            .unwrap_or(name)
+            .to_string()
+    };
+    let mut counts = BTreeMap::new();
+    for name in tips.keys() {
+        *counts.entry(short(name)).or_insert(0) += 1;
+    }
+    tips.keys()
+        .map(|name| {
+            let label = match counts[&short(name)] {
+                1 => short(name),
+                _ => name.strip_prefix("refs/").unwrap_or(name).to_string(),
+            };
+            (name.clone(), label)
+        })
+        .collect()
 }
 
 /// A generic repository abstraction.
@@ -148,6 +711,8 @@ pub struct Repository<G: GitRepository = LocalGitRepository> {
     pub uri: PathBuf,
     /// The name of the repository.
     pub name: String,
+    /// The name of the repository this one is a submodule of, if any.
+    pub parent: Option<String>,
     /// Underlying VCS object.
     pub vcs: G,
 }
@@ -162,20 +727,40 @@ impl<G: GitRepository> Repository<G> {
     /// # Returns
     ///
     /// A `Result` containing the new instance of `Repository` or an error.
-    pub fn new(uri: &PathBuf) -> Result<Self> {
+    pub fn new(uri: &Path) -> Result<Self> {
         let name = uri
             .file_name()
             .and_then(|n| n.to_str())
-            .ok_or_else(|| WalrustError::PathError(uri.clone()))?
+            .ok_or_else(|| WalrustError::PathError(uri.to_path_buf()))?
             .to_string();
         let vcs = G::new(uri)?;
         Ok(Repository {
-            uri: uri.clone(),
+            uri: uri.to_path_buf(),
             name,
+            parent: None,
             vcs,
         })
     }
 
+    /// Labels the repository as a submodule of another.
+    ///
+    /// The name becomes the parent's name followed by the submodule's path,
+    /// e.g. `station/libs/radio`, so results tell submodules apart.
+    ///
+    /// # Arguments
+    ///
+    /// * `parent` - The name of the parent repository.
+    /// * `path` - The path of the submodule in the parent's working directory.
+    ///
+    /// # Returns
+    ///
+    /// The labelled `Repository`.
+    pub fn with_parent(mut self, parent: &str, path: &Path) -> Self {
+        self.name = format!("{}/{}", parent, path.display());
+        self.parent = Some(parent.to_string());
+        self
+    }
+
     /// Get the URI of the repository.
     ///
     /// # Returns
@@ -194,21 +779,20 @@ impl<G: GitRepository> Repository<G> {
         &self.name
     }
 
-    /// Get the commits in the repository between two optional dates.
+    /// Get the commits in the repository matching a query.
     ///
     /// # Arguments
     ///
-    /// * `since` - Optional start date for the commit range (defaults to 30 days ago).
-    /// * `until` - Optional end date for the commit range (defaults to now).
+    /// * `query` - The date range and references to walk.
     ///
     /// # Returns
     ///
-    /// A vector of commits within the specified date range.
+    /// A vector of commits matching the query.
     ///
     /// # Errors
     ///
     /// Returns an error if the commit retrieval fails.
-    pub fn get_commits(&self, since: DateTime<Utc>, until: DateTime<Utc>) -> Result<Vec<Commit>> {
-        self.vcs.get_commits(since, until)
+    pub fn get_commits(&self, query: &CommitQuery) -> Result<Vec<Commit>> {
+        self.vcs.get_commits(query)
     }
 }
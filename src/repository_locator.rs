@@ -1,34 +1,165 @@
+//! The `repository_locator` module provides functionality for locating repositories
+//! in a given directory. It uses abstractions for filesystem operations and
+//! repository detection to support extensibility and testing.
+//!
+//! # Key Components
+//! - `RepositoryLocator`: The main struct for locating repositories.
+//! - `DiscoveryFilter`: Include and exclude patterns for the directories searched.
+//! - `read_repository_list`: Reads an explicit list of repository paths.
+//!
+//! # Example
+//! ```rust
+//! use walrust::repository_locator::GitRepositoryLocator;
+//! use std::path::Path;
+//!
+//! let locator = GitRepositoryLocator::new(Path::new("/path/to/search"), 3);
+//! let repositories = locator.locate().unwrap();
+//! for repo in repositories {
+//!     println!("Found repository: {}", repo.uri.display());
+//! }
+//! ```
+
 use crate::Result;
 use crate::{
     filesystem::{Filesystem, LocalFilesystem},
+    glob::Glob,
     repository::{GitRepository, LocalGitRepository, Repository},
 };
+use std::collections::HashMap;
+use std::fs;
 use std::path::{Path, PathBuf};
 
-/// The `repository_locator` module provides functionality for locating repositories
-/// in a given directory. It uses abstractions for filesystem operations and
-/// repository detection to support extensibility and testing.
+/// The directories skipped by default when searching for repositories: those
+/// holding dependencies, build output and virtual environments.
+pub const DEFAULT_EXCLUDES: [&str; 5] =
+    ["node_modules", "target", "vendor", ".venv", "__pycache__"];
+
+/// Include and exclude patterns deciding which directories are searched for
+/// repositories, see [`crate::glob`].
 ///
-/// # Key Components
-/// - `RepositoryLocator`: The main struct for locating repositories.
+/// Patterns are matched against paths relative to the search root. Excluded
+/// directories are skipped together with everything below them. When there
+/// are include patterns, only repositories matching one of them are reported.
+/// A search root itself is never filtered.
 ///
 /// # Example
 /// ```rust
-/// use walrust::repository_locator::GitRepositoryLocator;
+/// use walrust::repository_locator::DiscoveryFilter;
 /// use std::path::Path;
 ///
-/// let locator = GitRepositoryLocator::new(Path::new("/path/to/search"), 3);
-/// let repositories = locator.locate().unwrap();
-/// for repo in repositories {
-///     println!("Found repository: {}", repo.uri.display());
-/// }
+/// let filter = DiscoveryFilter::new(["work/*"], ["/archive"])
+///     .unwrap()
+///     .with_default_excludes();
+/// assert!(filter.is_excluded(Path::new("web/node_modules")));
+/// assert!(filter.is_excluded(Path::new("archive")));
+/// assert!(filter.is_included(Path::new("work/radio")));
+/// assert!(!filter.is_included(Path::new("home/radio")));
 /// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiscoveryFilter {
+    /// The patterns of which a repository must match one to be reported. An
+    /// empty list reports every repository.
+    pub include: Vec<Glob>,
+    /// The patterns of directories that are not searched.
+    pub exclude: Vec<Glob>,
+}
+
+impl DiscoveryFilter {
+    /// Creates a filter from include and exclude patterns, without the
+    /// default excludes.
+    ///
+    /// # Arguments
+    /// - `include`: The patterns of repositories to report.
+    /// - `exclude`: The patterns of directories to skip.
+    ///
+    /// # Returns
+    /// A new `DiscoveryFilter` instance.
+    ///
+    /// # Errors
+    /// Returns a `ParseError` if any pattern is invalid.
+    pub fn new<I, E>(include: I, exclude: E) -> Result<Self>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+        E: IntoIterator,
+        E::Item: AsRef<str>,
+    {
+        Ok(Self {
+            include: include
+                .into_iter()
+                .map(|pattern| Glob::new(pattern.as_ref()))
+                .collect::<Result<_>>()?,
+            exclude: exclude
+                .into_iter()
+                .map(|pattern| Glob::new(pattern.as_ref()))
+                .collect::<Result<_>>()?,
+        })
+    }
+
+    /// Adds the [`DEFAULT_EXCLUDES`] to the exclude patterns.
+    ///
+    /// # Returns
+    /// The updated `DiscoveryFilter`.
+    pub fn with_default_excludes(mut self) -> Self {
+        for pattern in DEFAULT_EXCLUDES {
+            let glob = Glob::new(pattern).expect("default excludes are valid");
+            if !self.exclude.contains(&glob) {
+                self.exclude.push(glob);
+            }
+        }
+        self
+    }
+
+    /// Tests whether a directory is skipped.
+    ///
+    /// # Arguments
+    /// - `path`: The directory, relative to the search root.
+    ///
+    /// # Returns
+    /// `true` if the directory matches an exclude pattern.
+    pub fn is_excluded(&self, path: &Path) -> bool {
+        self.exclude.iter().any(|glob| glob.is_match(path))
+    }
+
+    /// Tests whether a repository is reported.
+    ///
+    /// # Arguments
+    /// - `path`: The repository, relative to the search root.
+    ///
+    /// # Returns
+    /// `true` if there are no include patterns or the path matches one.
+    pub fn is_included(&self, path: &Path) -> bool {
+        self.include.is_empty() || self.include.iter().any(|glob| glob.is_match(path))
+    }
+}
 
-/// A struct to locate repositories in a given path.
+impl Default for DiscoveryFilter {
+    /// Returns a filter that only skips the [`DEFAULT_EXCLUDES`].
+    fn default() -> Self {
+        Self {
+            include: Vec::new(),
+            exclude: Vec::new(),
+        }
+        .with_default_excludes()
+    }
+}
+
+/// A struct to locate repositories in one or more paths.
+///
+/// This struct recursively searches for repositories in the given directories.
+/// It supports configurable search depth and a [`DiscoveryFilter`] of the
+/// directories searched, and can be extended with custom filesystem or
+/// repository implementations. Repositories found through more than one root
+/// are reported once.
 ///
-/// This struct recursively searches for repositories in a given directory It
-/// supports configurable search depth and can be extended with custom
-/// filesystem or repository implementations.
+/// By default the search stops at each repository it finds. It can instead
+/// keep searching inside repositories for nested ones, and enumerate the
+/// submodules of every repository it finds.
+///
+/// Symbolic links to directories below the search roots are skipped unless
+/// following them is requested. Directories are identified by device and
+/// inode, so a link back to a directory being searched is never followed
+/// into a loop.
 ///
 /// # Type Parameters
 /// - `F`: The filesystem abstraction to use (default: `LocalFilesystem`).
@@ -49,31 +180,197 @@ pub struct RepositoryLocator<F: Filesystem = LocalFilesystem, G: GitRepository =
 {
     /// The filesystem to use for operations.
     filesystem: F,
-    /// The root path to start searching for repositories.
-    search_root: PathBuf,
+    /// The root paths to start searching for repositories.
+    search_roots: Vec<PathBuf>,
     /// The maximum depth to search for repositories.
     search_depth: usize,
+    /// Whether the roots are an explicit list of repositories, which are
+    /// reported when they are not repositories instead of being searched.
+    explicit: bool,
+    /// The patterns deciding which directories are searched.
+    filter: DiscoveryFilter,
+    /// Whether to keep searching inside the repositories found.
+    nested: bool,
+    /// Whether to report the submodules of the repositories found.
+    submodules: bool,
+    /// Whether to search directories reached through symbolic links.
+    follow_symlinks: bool,
     phantom: std::marker::PhantomData<G>,
 }
 
 impl<F: Filesystem, G: GitRepository> RepositoryLocator<F, G> {
+    /// Creates a locator searching a single root.
+    ///
+    /// # Arguments
+    /// - `search_root`: The directory to start searching in.
+    /// - `search_depth`: The maximum depth to search below the root.
+    ///
+    /// # Returns
+    /// A new `RepositoryLocator` instance.
     pub fn new(search_root: &Path, search_depth: usize) -> Self {
+        Self::from_roots([search_root], search_depth)
+    }
+
+    /// Creates a locator searching several roots.
+    ///
+    /// # Arguments
+    /// - `search_roots`: The directories to start searching in. Roots may
+    ///   overlap.
+    /// - `search_depth`: The maximum depth to search below each root.
+    ///
+    /// # Returns
+    /// A new `RepositoryLocator` instance.
+    ///
+    /// # Example
+    /// ```rust
+    /// use walrust::repository_locator::GitRepositoryLocator;
+    ///
+    /// let locator = GitRepositoryLocator::from_roots(["/path/to/src", "/path/to/work"], 3);
+    /// let repositories = locator.locate().unwrap();
+    /// ```
+    pub fn from_roots<I, P>(search_roots: I, search_depth: usize) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
         Self {
             filesystem: F::new(),
-            search_root: search_root.to_path_buf(),
+            search_roots: search_roots
+                .into_iter()
+                .map(|root| root.as_ref().to_path_buf())
+                .collect(),
             search_depth,
+            explicit: false,
+            filter: DiscoveryFilter::default(),
+            nested: false,
+            submodules: false,
+            follow_symlinks: false,
             phantom: std::marker::PhantomData,
         }
     }
 
-    /// Locates repositories in the configured search root.
+    /// Creates a locator for an explicit list of repositories.
     ///
-    /// This method starts the recursive search for repositories from the
-    /// configured `search_root` and up to the specified `search_depth`.
+    /// The paths are not searched below. Paths that are not repositories are
+    /// reported and skipped.
+    ///
+    /// # Arguments
+    /// - `paths`: The paths of the repositories, see [`read_repository_list`].
     ///
     /// # Returns
-    /// A `Result` containing a vector of `Repository<G>` objects if successful,
-    /// or an error if the search fails.
+    /// A new `RepositoryLocator` instance.
+    pub fn from_list<I, P>(paths: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        Self {
+            explicit: true,
+            ..Self::from_roots(paths, 0)
+        }
+    }
+
+    /// Sets the patterns deciding which directories are searched.
+    ///
+    /// The filter is not applied to explicit lists of repositories.
+    ///
+    /// # Arguments
+    /// - `filter`: The include and exclude patterns. Defaults to
+    ///   [`DiscoveryFilter::default`].
+    ///
+    /// # Returns
+    /// The updated `RepositoryLocator`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use walrust::repository_locator::{DiscoveryFilter, GitRepositoryLocator};
+    /// use std::path::Path;
+    ///
+    /// let filter = DiscoveryFilter::new(["work/*"], ["*.bak"]).unwrap();
+    /// let locator = GitRepositoryLocator::new(Path::new("/path/to/search"), 3).with_filter(filter);
+    /// let repositories = locator.locate().unwrap();
+    /// ```
+    pub fn with_filter(mut self, filter: DiscoveryFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Sets whether to keep searching inside the repositories found, for
+    /// repositories nested in them such as monorepo checkouts.
+    ///
+    /// # Arguments
+    /// - `nested`: `true` to search inside repositories, up to the search
+    ///   depth. Defaults to `false`.
+    ///
+    /// # Returns
+    /// The updated `RepositoryLocator`.
+    pub fn with_nested(mut self, nested: bool) -> Self {
+        self.nested = nested;
+        self
+    }
+
+    /// Sets whether to report the submodules of the repositories found.
+    ///
+    /// Submodules are enumerated through Git, regardless of the search depth,
+    /// and named after their parent, e.g. `station/libs/radio`. Submodules of
+    /// submodules are reported too. Submodules that are not checked out, or
+    /// whose directories are excluded, are skipped.
+    ///
+    /// # Arguments
+    /// - `submodules`: `true` to report submodules. Defaults to `false`.
+    ///
+    /// # Returns
+    /// The updated `RepositoryLocator`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use walrust::repository_locator::GitRepositoryLocator;
+    /// use std::path::Path;
+    ///
+    /// let locator = GitRepositoryLocator::new(Path::new("/path/to/search"), 3)
+    ///     .with_nested(true)
+    ///     .with_submodules(true);
+    /// for repo in locator.locate().unwrap() {
+    ///     match &repo.parent {
+    ///         Some(parent) => println!("{} (submodule of {})", repo.name, parent),
+    ///         None => println!("{}", repo.name),
+    ///     }
+    /// }
+    /// ```
+    pub fn with_submodules(mut self, submodules: bool) -> Self {
+        self.submodules = submodules;
+        self
+    }
+
+    /// Sets whether to search directories reached through symbolic links.
+    ///
+    /// The search roots are followed either way. Links that lead back to a
+    /// directory being searched are skipped, so link cycles cannot repeat
+    /// the search down to the search depth.
+    ///
+    /// # Arguments
+    /// - `follow_symlinks`: `true` to follow symbolic links to directories.
+    ///   Defaults to `false`.
+    ///
+    /// # Returns
+    /// The updated `RepositoryLocator`.
+    pub fn with_follow_symlinks(mut self, follow_symlinks: bool) -> Self {
+        self.follow_symlinks = follow_symlinks;
+        self
+    }
+
+    /// Locates repositories in the configured search roots.
+    ///
+    /// This method starts the recursive search for repositories from each of
+    /// the configured `search_roots` and up to the specified `search_depth`.
+    /// Repositories with the same canonical path are only reported once, as
+    /// found through the first root. Worktrees sharing a common Git directory
+    /// are also reported once, as the main worktree when it is found and
+    /// otherwise as the first linked worktree found.
+    ///
+    /// # Returns
+    /// A `Result` containing a vector of `Repository<G>` objects sorted by
+    /// path if successful, or an error if the search fails.
     ///
     /// # Errors
     /// - Returns an error if the filesystem operations fail (e.g., reading directories).
@@ -89,26 +386,63 @@ impl<F: Filesystem, G: GitRepository> RepositoryLocator<F, G> {
     /// }
     /// ```
     pub fn locate(&self) -> Result<Vec<Repository<G>>> {
-        self.locate_recursive(&self.search_root, self.search_depth)
+        let mut repositories: Vec<Repository<G>> = Vec::new();
+        let mut seen = HashMap::new();
+        for root in &self.search_roots {
+            let found = match self.explicit {
+                true => match self.try_make_listed_repo(root) {
+                    Some(repo) => self.with_submodules_of(root, repo),
+                    None => Vec::new(),
+                },
+                false => self.locate_recursive(root, root, self.search_depth, &mut Vec::new())?,
+            };
+            for repo in found {
+                let key = repo.vcs.common_dir().unwrap_or_else(|| repo.uri.clone());
+                let key = self.filesystem.canonicalize(&key).unwrap_or(key);
+                match seen.get(&key) {
+                    Some(&index) => {
+                        let kept: &Repository<G> = &repositories[index];
+                        if kept.vcs.is_linked_worktree() && !repo.vcs.is_linked_worktree() {
+                            repositories[index] = repo;
+                        }
+                    }
+                    None => {
+                        seen.insert(key, repositories.len());
+                        repositories.push(repo);
+                    }
+                }
+            }
+        }
+        repositories.sort_by(|a, b| a.uri.cmp(&b.uri));
+        Ok(repositories)
     }
 
     /// Recursively locates repositories in the given path.
     ///
     /// This method is called internally by `locate` to perform a depth-first
     /// search for repositories. It checks each directory to determine if it
-    /// is a repository or contains subdirectories to search further.
+    /// is a repository or contains subdirectories to search further, skipping
+    /// directories the filter excludes. Repositories are only searched when
+    /// nested repositories are requested, and bare repositories never are.
+    /// Symbolic links are skipped unless they are followed, and directories
+    /// already being searched are skipped to break link cycles.
     ///
     /// # Arguments
+    /// - `root`: The search root the filter patterns are relative to.
     /// - `search_root`: The current directory to search.
     /// - `search_depth`: The remaining depth to search.
+    /// - `ancestors`: The device and inode of each directory being searched,
+    ///   from the root down to the parent of `search_root`.
     ///
     /// # Returns
     /// A `Result` containing a vector of `Repository<G>` objects if successful,
     /// or an error if the search fails.
     fn locate_recursive(
         &self,
+        root: &Path,
         search_root: &Path,
         search_depth: usize,
+        ancestors: &mut Vec<(u64, u64)>,
     ) -> Result<Vec<Repository<G>>> {
         let mut repositories: Vec<Repository<G>> = Vec::new();
 
@@ -116,29 +450,99 @@ impl<F: Filesystem, G: GitRepository> RepositoryLocator<F, G> {
             return Ok(repositories);
         }
 
+        let relative = search_root.strip_prefix(root).unwrap_or(search_root);
+        let is_root = relative.as_os_str().is_empty();
+        if !is_root && self.filter.is_excluded(relative) {
+            return Ok(repositories);
+        }
+
+        let metadata = match self.filesystem.metadata(search_root) {
+            Ok(metadata) => metadata,
+            Err(_) => return Ok(repositories),
+        };
+        if metadata.is_symlink && !is_root && !self.follow_symlinks {
+            return Ok(repositories);
+        }
+        if ancestors.contains(&metadata.file_id()) {
+            eprintln!("Skipping directory loop: {}", search_root.display());
+            return Ok(repositories);
+        }
+
         // Happy path - the current search root is a repository
-        match self.try_make_repo(search_root) {
-            Some(repo) => {
-                repositories.push(repo);
+        if let Some(repo) = self.try_make_repo(search_root) {
+            if is_root || self.filter.is_included(relative) {
+                repositories.extend(self.with_submodules_of(root, repo));
+            }
+            if !self.nested || self.is_bare_repository(search_root) {
                 return Ok(repositories);
             }
-            None => {
-                // If the search depth is 0, stop searching further
-                if search_depth == 0 {
-                    return Ok(repositories);
-                }
+        }
 
-                // Otherwise, recursively search for repositories
-                for entry in self.filesystem.read_dir(search_root).unwrap() {
-                    let entry_path = entry.as_path();
-                    repositories.extend(self.locate_recursive(entry_path, search_depth - 1)?);
-                }
+        // If the search depth is 0, stop searching further
+        if search_depth == 0 {
+            return Ok(repositories);
+        }
+
+        // Otherwise, recursively search for repositories
+        ancestors.push(metadata.file_id());
+        for entry in self.filesystem.read_dir(search_root).unwrap() {
+            if entry.file_name().is_some_and(|name| name == ".git") {
+                continue;
             }
+            let entry_path = entry.as_path();
+            repositories.extend(self.locate_recursive(
+                root,
+                entry_path,
+                search_depth - 1,
+                ancestors,
+            )?);
         }
+        ancestors.pop();
 
         Ok(repositories)
     }
 
+    /// Expands a repository into itself followed by its submodules, when
+    /// submodules are requested.
+    ///
+    /// # Arguments
+    /// - `root`: The search root the filter patterns are relative to.
+    /// - `repo`: The repository found.
+    ///
+    /// # Returns
+    /// The repository, followed by each checked-out submodule that is not
+    /// excluded, each followed by its own submodules.
+    fn with_submodules_of(&self, root: &Path, repo: Repository<G>) -> Vec<Repository<G>> {
+        let paths = match self.submodules {
+            true => repo.vcs.submodules().unwrap_or_else(|err| {
+                eprintln!(
+                    "Failed to list submodules of {}: {}",
+                    repo.uri.display(),
+                    err
+                );
+                Vec::new()
+            }),
+            false => Vec::new(),
+        };
+        let (uri, name) = (repo.uri.clone(), repo.name.clone());
+        let mut repositories = vec![repo];
+        for path in paths {
+            let submodule_uri = uri.join(&path);
+            let relative = submodule_uri.strip_prefix(root).unwrap_or(&path);
+            let excluded = relative
+                .ancestors()
+                .any(|dir| !dir.as_os_str().is_empty() && self.filter.is_excluded(dir));
+            if excluded {
+                continue;
+            }
+            if let Some(submodule) = self.try_make_repo(&submodule_uri) {
+                let submodule = submodule.with_parent(&name, &path);
+                repositories.extend(self.with_submodules_of(root, submodule));
+            }
+        }
+        repositories
+    }
+
     /// Attempts to create a repository object from the given path.
     ///
     /// This method checks if the path is a valid repository and creates
@@ -152,12 +556,11 @@ impl<F: Filesystem, G: GitRepository> RepositoryLocator<F, G> {
     /// An `Option<Repository<G>>` containing the repository object if successful,
     /// or `None` if the path is not a valid repository.
     fn try_make_repo(&self, path: &Path) -> Option<Repository<G>> {
-        let expect_git_path = path.join(".git");
-        if !self.filesystem.exists(&expect_git_path) {
+        if !self.is_repository(path) {
             return None;
         }
 
-        match Repository::new(&path.to_path_buf()) {
+        match Repository::new(path) {
             Ok(repo) => Some(repo),
             Err(_) => {
                 eprintln!("Failed to create repository from path: {}", path.display());
@@ -165,6 +568,90 @@ impl<F: Filesystem, G: GitRepository> RepositoryLocator<F, G> {
             }
         }
     }
+
+    /// Attempts to create a repository object from a listed path, reporting
+    /// the path if it is not a repository.
+    ///
+    /// # Arguments
+    /// - `path`: The listed path.
+    ///
+    /// # Returns
+    /// An `Option<Repository<G>>` containing the repository object if
+    /// successful, or `None` if the path is not a valid repository.
+    fn try_make_listed_repo(&self, path: &Path) -> Option<Repository<G>> {
+        if !self.filesystem.is_dir(path) {
+            eprintln!("Listed repository not found: {}", path.display());
+            return None;
+        }
+        if !self.is_repository(path) {
+            eprintln!("Listed path is not a repository: {}", path.display());
+            return None;
+        }
+        self.try_make_repo(path)
+    }
+
+    /// Checks whether a directory looks like a repository.
+    ///
+    /// # Arguments
+    /// - `path`: The directory to check.
+    ///
+    /// # Returns
+    /// `true` if the directory has a `.git` entry, which is a directory for
+    /// ordinary checkouts and a `gitdir:` file for linked worktrees and
+    /// submodules, or if it is a bare repository.
+    fn is_repository(&self, path: &Path) -> bool {
+        self.filesystem.exists(&path.join(".git")) || self.is_bare_repository(path)
+    }
+
+    /// Checks whether a directory looks like a bare repository, such as
+    /// `radio.git` on a mirror host.
+    ///
+    /// # Arguments
+    /// - `path`: The directory to check.
+    ///
+    /// # Returns
+    /// `true` if the directory has a `HEAD` file and `objects` and `refs`
+    /// directories.
+    fn is_bare_repository(&self, path: &Path) -> bool {
+        self.filesystem.exists(&path.join("HEAD"))
+            && self.filesystem.is_dir(&path.join("objects"))
+            && self.filesystem.is_dir(&path.join("refs"))
+    }
+}
+
+/// Reads an explicit list of repository paths.
+///
+/// The file lists one path per line. Blank lines and lines starting with `#`
+/// are ignored, and relative paths are relative to the directory holding the
+/// file.
+///
+/// # Arguments
+/// - `path`: The path of the list file.
+///
+/// # Returns
+/// The repository paths, in the order listed.
+///
+/// # Errors
+/// Returns an `IoError` if the file cannot be read.
+///
+/// # Example
+/// ```rust
+/// use walrust::repository_locator::{read_repository_list, GitRepositoryLocator};
+/// use std::path::Path;
+///
+/// if let Ok(paths) = read_repository_list(Path::new("repos.txt")) {
+///     let repositories = GitRepositoryLocator::from_list(paths).locate().unwrap();
+/// }
+/// ```
+pub fn read_repository_list(path: &Path) -> Result<Vec<PathBuf>> {
+    let text = fs::read_to_string(path)?;
+    let base = path.parent().unwrap_or(Path::new(""));
+    Ok(text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| base.join(line))
+        .collect())
 }
 
 /// A type alias for a `RepositoryLocator` with default implementations.
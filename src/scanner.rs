@@ -0,0 +1,91 @@
+//! The `scanner` module queries many repositories concurrently.
+//!
+//! Repositories are handed out to a pool of worker threads one at a time, so
+//! each `Repository<G>` only needs to be `Send`. Results are returned sorted by
+//! repository path regardless of the order in which the workers finish.
+//!
+//! # Example
+//! ```rust
+//! use walrust::query::CommitQuery;
+//! use walrust::repository_locator::GitRepositoryLocator;
+//! use walrust::scanner::scan_repositories;
+//! use chrono::{Duration, Utc};
+//! use std::path::Path;
+//!
+//! let locator = GitRepositoryLocator::new(Path::new("/path/to/search"), 3);
+//! let repositories = locator.locate().unwrap();
+//! let query = CommitQuery::new(Utc::now() - Duration::days(1), Utc::now());
+//! for (repo, commits) in scan_repositories(repositories, &query, 4) {
+//!     println!("{}: {} commits", repo.name, commits.map(|c| c.len()).unwrap_or(0));
+//! }
+//! ```
+
+use crate::commit::Commit;
+use crate::query::CommitQuery;
+use crate::repository::{GitRepository, Repository};
+use crate::Result;
+use std::sync::Mutex;
+use std::thread;
+
+/// A repository paired with the outcome of querying its commits.
+pub type ScanResult<G> = (Repository<G>, Result<Vec<Commit>>);
+
+/// Queries the commits of many repositories using a pool of worker threads.
+///
+/// # Arguments
+/// - `repositories`: The repositories to query.
+/// - `query`: The query to run against every repository.
+/// - `jobs`: The number of worker threads. `0` uses the available parallelism
+///   of the machine, and `1` queries the repositories on the calling thread.
+///
+/// # Returns
+/// Each repository with the result of its query, sorted by repository path.
+/// A failure in one repository does not affect the others.
+pub fn scan_repositories<G: GitRepository + Send>(
+    mut repositories: Vec<Repository<G>>,
+    query: &CommitQuery,
+    jobs: usize,
+) -> Vec<ScanResult<G>> {
+    repositories.sort_by(|a, b| a.uri.cmp(&b.uri));
+
+    let jobs = match jobs {
+        0 => thread::available_parallelism().map_or(1, |n| n.get()),
+        n => n,
+    }
+    .min(repositories.len());
+
+    if jobs <= 1 {
+        return repositories
+            .into_iter()
+            .map(|repo| {
+                let commits = repo.get_commits(query);
+                (repo, commits)
+            })
+            .collect();
+    }
+
+    let count = repositories.len();
+    let queue = Mutex::new(repositories.into_iter().enumerate());
+    let results: Mutex<Vec<Option<ScanResult<G>>>> = Mutex::new((0..count).map(|_| None).collect());
+
+    thread::scope(|scope| {
+        for _ in 0..jobs {
+            scope.spawn(|| loop {
+                // Release the queue lock before querying so workers run concurrently
+                let next = queue.lock().unwrap().next();
+                let Some((index, repo)) = next else {
+                    break;
+                };
+                let commits = repo.get_commits(query);
+                results.lock().unwrap()[index] = Some((repo, commits));
+            });
+        }
+    });
+
+    results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .flatten()
+        .collect()
+}
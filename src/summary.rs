@@ -0,0 +1,309 @@
+//! The `summary` module groups and counts commit query results.
+//!
+//! Both functions work on the `(Repository, Vec<Commit>)` pairs collected from
+//! a scan, so tools other than the `walrust` binary can reuse them.
+//!
+//! # Example
+//! ```rust
+//! use walrust::report::ReportOptions;
+//! use walrust::repository_locator::GitRepositoryLocator;
+//! use walrust::query::CommitQuery;
+//! use walrust::scanner::scan_repositories;
+//! use walrust::summary::{group_commits, GroupBy};
+//! use chrono::{Duration, Utc};
+//! use std::path::Path;
+//!
+//! let locator = GitRepositoryLocator::new(Path::new("/path/to/search"), 3);
+//! let query = CommitQuery::new(Utc::now() - Duration::days(7), Utc::now());
+//! let results: Vec<_> = scan_repositories(locator.locate().unwrap(), &query, 0)
+//!     .into_iter()
+//!     .filter_map(|(repo, commits)| Some((repo, commits.ok()?)))
+//!     .collect();
+//! for group in group_commits(&results, GroupBy::Day, &ReportOptions::default()) {
+//!     println!("{}: {} commits", group.key, group.commits.len());
+//! }
+//! ```
+
+use crate::commit::{Commit, DiffStats};
+use crate::report::ReportOptions;
+use crate::repository::{GitRepository, Repository};
+use crate::WalrustError;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::AddAssign;
+use std::str::FromStr;
+
+/// Selects how commits are grouped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupBy {
+    /// One group per repository, in repository order.
+    Repo,
+    /// One group per author, most active first.
+    Author,
+    /// One group per calendar day, oldest first.
+    Day,
+    /// One group per ISO 8601 week, oldest first.
+    Week,
+    /// One group per Conventional Commits type, most common first. Commits
+    /// that do not follow Conventional Commits are grouped under
+    /// [`NON_CONFORMING`].
+    Type,
+}
+
+/// The type key of commits that do not follow Conventional Commits.
+pub const NON_CONFORMING: &str = "non-conforming";
+
+impl fmt::Display for GroupBy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GroupBy::Repo => write!(f, "repo"),
+            GroupBy::Author => write!(f, "author"),
+            GroupBy::Day => write!(f, "day"),
+            GroupBy::Week => write!(f, "week"),
+            GroupBy::Type => write!(f, "type"),
+        }
+    }
+}
+
+impl FromStr for GroupBy {
+    type Err = WalrustError;
+
+    /// Parses `"repo"`, `"author"`, `"day"`, `"week"` or `"type"` into a `GroupBy`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "repo" => Ok(GroupBy::Repo),
+            "author" => Ok(GroupBy::Author),
+            "day" => Ok(GroupBy::Day),
+            "week" => Ok(GroupBy::Week),
+            "type" => Ok(GroupBy::Type),
+            _ => Err(WalrustError::ParseError(format!(
+                "unknown grouping '{}', expected 'repo', 'author', 'day', 'week' or 'type'",
+                s
+            ))),
+        }
+    }
+}
+
+/// A group of commits sharing the same key.
+pub struct CommitGroup<'a, G: GitRepository> {
+    /// The value shared by the commits, e.g. a repository name or `2025-05-04`.
+    pub key: String,
+    /// The commits in the group, each with the repository it came from.
+    pub commits: Vec<(&'a Repository<G>, &'a Commit)>,
+}
+
+impl GroupBy {
+    /// Returns the group key of a commit.
+    ///
+    /// Days and weeks are taken from the date selected by `options.date_field`
+    /// in the timezone selected by `options.timezone`.
+    ///
+    /// # Arguments
+    /// - `repo`: The repository the commit belongs to.
+    /// - `commit`: The commit to classify.
+    /// - `options`: The options selecting the date and timezone.
+    ///
+    /// # Returns
+    /// The repository name, `Name <email>`, `YYYY-MM-DD`, `YYYY-Www`, or the
+    /// commit type.
+    pub fn key<G: GitRepository>(
+        &self,
+        repo: &Repository<G>,
+        commit: &Commit,
+        options: &ReportOptions,
+    ) -> String {
+        let date = || options.timezone.convert(&commit.date(options.date_field));
+        match self {
+            GroupBy::Repo => repo.name.clone(),
+            GroupBy::Author => commit.author.to_string(),
+            GroupBy::Day => date().format("%Y-%m-%d").to_string(),
+            GroupBy::Week => date().format("%G-W%V").to_string(),
+            GroupBy::Type => commit_type(commit),
+        }
+    }
+}
+
+/// Groups commit query results.
+///
+/// # Arguments
+/// - `results`: The repositories and their commits.
+/// - `group_by`: How to group the commits.
+/// - `options`: The options selecting the date and timezone for `Day` and `Week`.
+///
+/// # Returns
+/// The non-empty groups. Repository groups keep the order of `results`, author
+/// and type groups are ordered by descending commit count then name, and day
+/// and week groups are ordered oldest first. Commits keep their order within a group.
+pub fn group_commits<'a, G: GitRepository>(
+    results: &'a [(Repository<G>, Vec<Commit>)],
+    group_by: GroupBy,
+    options: &ReportOptions,
+) -> Vec<CommitGroup<'a, G>> {
+    let mut groups: Vec<CommitGroup<'a, G>> = Vec::new();
+    let mut index: BTreeMap<String, usize> = BTreeMap::new();
+
+    for (repo, commits) in results {
+        for commit in commits {
+            let key = group_by.key(repo, commit, options);
+            let position = *index.entry(key.clone()).or_insert_with(|| {
+                groups.push(CommitGroup {
+                    key,
+                    commits: Vec::new(),
+                });
+                groups.len() - 1
+            });
+            groups[position].commits.push((repo, commit));
+        }
+    }
+
+    match group_by {
+        GroupBy::Repo => {}
+        GroupBy::Author | GroupBy::Type => groups.sort_by(|a, b| {
+            b.commits
+                .len()
+                .cmp(&a.commits.len())
+                .then_with(|| a.key.cmp(&b.key))
+        }),
+        GroupBy::Day | GroupBy::Week => groups.sort_by(|a, b| a.key.cmp(&b.key)),
+    }
+    groups
+}
+
+/// Diff statistics summed over many commits.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DiffTotals {
+    /// The number of commits with statistics.
+    pub commits: usize,
+    /// The number of files changed, counted once per commit that changes them.
+    pub files_changed: usize,
+    /// The number of lines added.
+    pub insertions: usize,
+    /// The number of lines removed.
+    pub deletions: usize,
+}
+
+impl AddAssign<&DiffStats> for DiffTotals {
+    fn add_assign(&mut self, stats: &DiffStats) {
+        self.commits += 1;
+        self.files_changed += stats.files_changed();
+        self.insertions += stats.insertions;
+        self.deletions += stats.deletions;
+    }
+}
+
+/// Totals for a set of commit query results.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Summary {
+    /// The total number of commits.
+    pub commits: usize,
+    /// The commit count of every repository, in repository order.
+    pub repositories: Vec<(String, usize)>,
+    /// The commit count of every author, most active first.
+    pub authors: Vec<(String, usize)>,
+    /// The commit count of every Conventional Commits type, most common first,
+    /// with non-conforming commits counted under [`NON_CONFORMING`].
+    pub types: Vec<(String, usize)>,
+    /// The summed diff statistics, if any commit has them.
+    pub stats: Option<DiffTotals>,
+}
+
+/// Counts commits per repository, author and type.
+///
+/// # Arguments
+/// - `results`: The repositories and their commits.
+///
+/// # Returns
+/// The totals. Repositories without commits are included with a count of zero.
+pub fn summarize<G: GitRepository>(results: &[(Repository<G>, Vec<Commit>)]) -> Summary {
+    let mut authors: BTreeMap<String, usize> = BTreeMap::new();
+    let mut types: BTreeMap<String, usize> = BTreeMap::new();
+    let mut stats: Option<DiffTotals> = None;
+    for commit in results.iter().flat_map(|(_, commits)| commits) {
+        *authors.entry(commit.author.to_string()).or_default() += 1;
+        *types.entry(commit_type(commit)).or_default() += 1;
+        if let Some(commit_stats) = &commit.stats {
+            *stats.get_or_insert_with(DiffTotals::default) += commit_stats;
+        }
+    }
+    let most_first = |counts: BTreeMap<String, usize>| {
+        let mut counts: Vec<_> = counts.into_iter().collect();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        counts
+    };
+
+    Summary {
+        commits: results.iter().map(|(_, commits)| commits.len()).sum(),
+        repositories: results
+            .iter()
+            .map(|(repo, commits)| (repo.name.clone(), commits.len()))
+            .collect(),
+        authors: most_first(authors),
+        types: most_first(types),
+        stats,
+    }
+}
+
+/// Moves the commits that do not follow Conventional Commits out of query results.
+///
+/// A [`CommitFilter::types`](crate::filter::CommitFilter::types) filter lets
+/// non-conforming commits through so they are not silently dropped. Summarize
+/// the results first to count them under [`NON_CONFORMING`], then use this to
+/// report them apart from the commits of the selected types.
+///
+/// # Arguments
+/// - `results`: The repositories and their commits.
+///
+/// # Returns
+/// The removed commits, each with the name of its repository, in result order.
+pub fn split_non_conforming<G: GitRepository>(
+    results: &mut [(Repository<G>, Vec<Commit>)],
+) -> Vec<(String, Commit)> {
+    let mut removed = Vec::new();
+    for (repo, commits) in results.iter_mut() {
+        let (conforming, non_conforming): (Vec<_>, Vec<_>) = std::mem::take(commits)
+            .into_iter()
+            .partition(|commit| commit.conventional().is_ok());
+        *commits = conforming;
+        removed.extend(
+            non_conforming
+                .into_iter()
+                .map(|commit| (repo.name.clone(), commit)),
+        );
+    }
+    removed
+}
+
+/// Returns the Conventional Commits type of a commit, or [`NON_CONFORMING`].
+fn commit_type(commit: &Commit) -> String {
+    commit
+        .conventional()
+        .map_or_else(|_| NON_CONFORMING.to_string(), |c| c.kind)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_group_by_from_str() {
+        assert_eq!("repo".parse::<GroupBy>().unwrap(), GroupBy::Repo);
+        assert_eq!("author".parse::<GroupBy>().unwrap(), GroupBy::Author);
+        assert_eq!("day".parse::<GroupBy>().unwrap(), GroupBy::Day);
+        assert_eq!("week".parse::<GroupBy>().unwrap(), GroupBy::Week);
+        assert_eq!("type".parse::<GroupBy>().unwrap(), GroupBy::Type);
+        assert!("month".parse::<GroupBy>().is_err());
+    }
+
+    #[test]
+    fn test_group_by_display_round_trip() {
+        for group_by in [
+            GroupBy::Repo,
+            GroupBy::Author,
+            GroupBy::Day,
+            GroupBy::Week,
+            GroupBy::Type,
+        ] {
+            assert_eq!(group_by.to_string().parse::<GroupBy>().unwrap(), group_by);
+        }
+    }
+}
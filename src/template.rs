@@ -0,0 +1,249 @@
+//! The `template` module renders commits with user-defined output templates.
+//!
+//! Templates are plain text with `{name}` placeholders, in the spirit of
+//! `git log --pretty=format:`. Placeholder names are the same as the report
+//! [`Column`] names, e.g. `{short_hash}`, `{author_email}` or `{repo}`. Use
+//! `{{` and `}}` for literal braces.
+//!
+//! # Example
+//! ```rust
+//! use walrust::commit::Commit;
+//! use walrust::report::{ReportOptions, RepositoryInfo};
+//! use walrust::template::Template;
+//!
+//! let template: Template = "{repo}: {title}".parse().unwrap();
+//! let repo = RepositoryInfo {
+//!     name: "walrust".to_string(),
+//!     path: "/src/walrust".to_string(),
+//!     head: "HEAD".to_string(),
+//!     parent: None,
+//! };
+//! let commit = Commit {
+//!     title: "Initial commit".to_string(),
+//!     ..Commit::default()
+//! };
+//! let line = template.render(&repo, &commit, &ReportOptions::default());
+//! assert_eq!(line, "walrust: Initial commit");
+//! ```
+
+use crate::commit::Commit;
+use crate::report::{Column, ReportOptions, RepositoryInfo};
+use crate::WalrustError;
+use std::str::FromStr;
+
+/// A piece of a parsed template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    /// Text copied to the output as-is.
+    Literal(String),
+    /// A placeholder replaced with the value of a field.
+    Field(Column),
+}
+
+/// A parsed output template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    segments: Vec<Segment>,
+}
+
+impl Template {
+    /// Renders the template for a single commit.
+    ///
+    /// # Arguments
+    /// - `repo`: The repository the commit belongs to.
+    /// - `commit`: The commit to render.
+    /// - `options`: The options controlling date rendering.
+    ///
+    /// # Returns
+    /// The rendered text, without a trailing newline.
+    pub fn render(
+        &self,
+        repo: &RepositoryInfo,
+        commit: &Commit,
+        options: &ReportOptions,
+    ) -> String {
+        self.segments
+            .iter()
+            .map(|segment| match segment {
+                Segment::Literal(text) => text.clone(),
+                Segment::Field(column) => column.value(repo, commit, options),
+            })
+            .collect()
+    }
+
+    /// Returns `true` if the template has a placeholder for `column`.
+    pub fn uses(&self, column: Column) -> bool {
+        self.segments.contains(&Segment::Field(column))
+    }
+}
+
+impl FromStr for Template {
+    type Err = WalrustError;
+
+    /// Parses a template string.
+    ///
+    /// # Errors
+    /// Returns a `ParseError` naming the offending placeholder if the template
+    /// contains an unknown placeholder or an unbalanced brace.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let mut segments = Vec::new();
+        let mut literal = String::new();
+        let mut chars = s.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '{' => {
+                    let mut name = String::new();
+                    let mut closed = false;
+                    for c in chars.by_ref() {
+                        match c {
+                            '}' => {
+                                closed = true;
+                                break;
+                            }
+                            _ => name.push(c),
+                        }
+                    }
+                    if !closed {
+                        return Err(WalrustError::ParseError(format!(
+                            "unterminated placeholder '{{{}' in template",
+                            name
+                        )));
+                    }
+                    let column = name.parse::<Column>().map_err(|_| {
+                        let names: Vec<_> = Column::ALL.iter().map(|c| c.name()).collect();
+                        WalrustError::ParseError(format!(
+                            "unknown placeholder '{{{}}}' in template, expected one of: {}",
+                            name,
+                            names.join(", ")
+                        ))
+                    })?;
+                    if !literal.is_empty() {
+                        segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                    }
+                    segments.push(Segment::Field(column));
+                }
+                '}' => {
+                    return Err(WalrustError::ParseError(
+                        "unmatched '}' in template, use '}}' for a literal brace".to_string(),
+                    ));
+                }
+                _ => literal.push(c),
+            }
+        }
+        if !literal.is_empty() {
+            segments.push(Segment::Literal(literal));
+        }
+        Ok(Template { segments })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commit::{CommitAuthor, CommitHash, DisplayTimezone};
+    use chrono::{FixedOffset, TimeZone};
+
+    fn repo() -> RepositoryInfo {
+        RepositoryInfo {
+            name: "ferrix".to_string(),
+            path: "/repos/ferrix".to_string(),
+            head: "0123456789abcdef".to_string(),
+            parent: None,
+        }
+    }
+
+    fn commit() -> Commit {
+        let date = FixedOffset::east_opt(3600)
+            .unwrap()
+            .with_ymd_and_hms(2025, 5, 4, 11, 0, 0)
+            .unwrap();
+        Commit::new(
+            "Fix the ship".to_string(),
+            CommitAuthor::new(
+                "Cassian Andor".to_string(),
+                "cassian@ferrix.org".to_string(),
+            ),
+            date,
+            CommitAuthor::new("Bix Caleen".to_string(), "bix@ferrix.org".to_string()),
+            date,
+            "Fix the ship\n\nIt was broken.".to_string(),
+            CommitHash::new("abcdef1234567890".to_string()),
+        )
+    }
+
+    #[test]
+    fn test_render_fields() {
+        let template: Template =
+            "{short_hash} {author_name} <{author_email}> {title} [{repo} {path}]"
+                .parse()
+                .unwrap();
+        assert_eq!(
+            template.render(&repo(), &commit(), &ReportOptions::default()),
+            "abcdef1 Cassian Andor <cassian@ferrix.org> Fix the ship [ferrix /repos/ferrix]"
+        );
+    }
+
+    #[test]
+    fn test_render_dates() {
+        let template: Template = "{date}|{author_date}".parse().unwrap();
+        let options = ReportOptions {
+            timezone: DisplayTimezone::Utc,
+            ..ReportOptions::default()
+        };
+        assert_eq!(
+            template.render(&repo(), &commit(), &options),
+            "2025-05-04T10:00:00+00:00|2025-05-04T10:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn test_escaped_braces() {
+        let template: Template = "{{{hash}}} }}{{".parse().unwrap();
+        assert_eq!(
+            template.render(&repo(), &commit(), &ReportOptions::default()),
+            "{abcdef1234567890} }{"
+        );
+    }
+
+    #[test]
+    fn test_literal_only() {
+        let template: Template = "no placeholders".parse().unwrap();
+        assert_eq!(
+            template.render(&repo(), &commit(), &ReportOptions::default()),
+            "no placeholders"
+        );
+    }
+
+    #[test]
+    fn test_uses() {
+        let template: Template = "{short_hash} ({branches})".parse().unwrap();
+        assert!(template.uses(Column::Branches));
+        assert!(!template.uses(Column::Title));
+    }
+
+    #[test]
+    fn test_unknown_placeholder() {
+        let err = "{hash} {sha}".parse::<Template>().unwrap_err();
+        assert!(err.to_string().contains("unknown placeholder '{sha}'"));
+    }
+
+    #[test]
+    fn test_unterminated_placeholder() {
+        let err = "{hash".parse::<Template>().unwrap_err();
+        assert!(err.to_string().contains("unterminated placeholder '{hash'"));
+    }
+
+    #[test]
+    fn test_unmatched_closing_brace() {
+        assert!("hash}".parse::<Template>().is_err());
+    }
+}
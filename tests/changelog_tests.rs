@@ -0,0 +1,160 @@
+mod temp_git_repository;
+use chrono::DateTime;
+use temp_git_repository::TempGitRepository;
+use walrust::changelog::{Changelog, ChangelogGrouping, TitlePrefix};
+use walrust::commit::Commit;
+use walrust::query::{CommitQuery, RevisionRange};
+use walrust::repository::{LocalGitRepository, Repository};
+
+/// A fixed point in time used as the base for commit dates in these tests.
+const BASE_TIME: i64 = 1_746_000_000;
+
+/// Creates a repository with a `v1.0` tag followed by the given commit titles,
+/// one second apart, on `main`.
+fn create_tagged_repository(titles: &[&str]) -> TempGitRepository {
+    let repo = TempGitRepository::new();
+    let v1 = repo.commit("refs/heads/main", "feat: Initial release", BASE_TIME);
+    repo.tag("v1.0", v1);
+    for (i, title) in titles.iter().enumerate() {
+        repo.commit("refs/heads/main", title, BASE_TIME + 1 + i as i64);
+    }
+    repo
+}
+
+/// Queries every repository for the commits in `v1.0..HEAD`.
+fn query_range(repos: &[&TempGitRepository]) -> Vec<(Repository, Vec<Commit>)> {
+    let since = DateTime::from_timestamp(0, 0).unwrap();
+    let until = DateTime::from_timestamp(BASE_TIME * 2, 0).unwrap();
+    let range = RevisionRange::new(Some("v1.0".to_string()), "HEAD".to_string());
+    let query = CommitQuery::new(since, until).with_range(range);
+    repos
+        .iter()
+        .map(|temp| {
+            let repo = Repository::<LocalGitRepository>::new(temp.path()).unwrap();
+            let commits = repo.get_commits(&query).unwrap();
+            (repo, commits)
+        })
+        .collect()
+}
+
+/// Renders a changelog to a string.
+fn render(changelog: &Changelog, results: &[(Repository, Vec<Commit>)]) -> String {
+    let mut output = Vec::new();
+    changelog
+        .write_markdown(&mut output, "Changelog v1.0..HEAD", results)
+        .unwrap();
+    String::from_utf8(output).unwrap()
+}
+
+/// Replaces the short hash of every commit with `hash`, so the expected output
+/// does not depend on the generated hashes.
+fn replace_hashes(output: String, results: &[(Repository, Vec<Commit>)]) -> String {
+    let mut output = output;
+    for commit in results.iter().flat_map(|(_, commits)| commits) {
+        output = output.replace(&commit.hash.short, "hash");
+    }
+    output
+}
+
+/// Returns the name a repository is reported under.
+fn name(repo: &TempGitRepository) -> String {
+    repo.path()
+        .file_name()
+        .unwrap()
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Tests a changelog grouped by Conventional Commits type across two
+/// repositories, excluding the history of the start of the range.
+#[test]
+fn test_changelog_by_type() {
+    let radio = create_tagged_repository(&[
+        "fix(radio): Retune the dish",
+        "feat!: Broadcast on every frequency",
+        "chore: Sweep the yard",
+        "Hide the relics",
+    ]);
+    let shop = create_tagged_repository(&["feat(shop): Sell antiques"]);
+    let quiet = create_tagged_repository(&[]);
+    let results = query_range(&[&radio, &shop, &quiet]);
+
+    let output = render(&Changelog::new(ChangelogGrouping::Type), &results);
+    assert_eq!(
+        replace_hashes(output, &results),
+        format!(
+            "# Changelog v1.0..HEAD\n\
+             \n\
+             ## {}\n\
+             \n\
+             ### Breaking Changes\n\
+             \n\
+             - Broadcast on every frequency (hash)\n\
+             \n\
+             ### Features\n\
+             \n\
+             - Broadcast on every frequency (hash)\n\
+             \n\
+             ### Bug Fixes\n\
+             \n\
+             - **radio:** Retune the dish (hash)\n\
+             \n\
+             ### Other Changes\n\
+             \n\
+             - Hide the relics (hash)\n\
+             - Sweep the yard (hash)\n\
+             \n\
+             ## {}\n\
+             \n\
+             ### Features\n\
+             \n\
+             - **shop:** Sell antiques (hash)\n",
+            name(&radio),
+            name(&shop)
+        )
+    );
+}
+
+/// Tests a changelog grouped by configured title prefixes.
+#[test]
+fn test_changelog_by_prefix() {
+    let repo = create_tagged_repository(&["Add the radio", "Fix the dish", "Address the crowd"]);
+    let results = query_range(&[&repo]);
+    let changelog = Changelog::new(ChangelogGrouping::Prefix).with_prefixes(vec![
+        TitlePrefix::new("Add", "Added"),
+        TitlePrefix::new("Fix", "Fixed"),
+    ]);
+
+    let output = render(&changelog, &results);
+    assert_eq!(
+        replace_hashes(output, &results),
+        format!(
+            "# Changelog v1.0..HEAD\n\
+             \n\
+             ## {}\n\
+             \n\
+             ### Added\n\
+             \n\
+             - Add the radio (hash)\n\
+             \n\
+             ### Fixed\n\
+             \n\
+             - Fix the dish (hash)\n\
+             \n\
+             ### Other Changes\n\
+             \n\
+             - Address the crowd (hash)\n",
+            name(&repo)
+        )
+    );
+}
+
+/// Tests that a changelog without commits says so.
+#[test]
+fn test_changelog_no_changes() {
+    let repo = create_tagged_repository(&[]);
+    let results = query_range(&[&repo]);
+
+    let output = render(&Changelog::new(ChangelogGrouping::Type), &results);
+    assert_eq!(output, "# Changelog v1.0..HEAD\n\nNo changes.\n");
+}
@@ -0,0 +1,861 @@
+mod temp_git_repository;
+use chrono::{DateTime, Duration};
+use git2::{Signature, Time};
+use temp_git_repository::TempGitRepository;
+use walrust::commit::{Commit, CommitAuthor, DateField, FileStats};
+use walrust::filter::CommitFilter;
+use walrust::pathspec::Pathspec;
+use walrust::query::{CommitQuery, MergeMode, RefSelection, RevisionRange};
+use walrust::repository::{GitRepository, LocalGitRepository};
+use walrust::WalrustError;
+
+/// A fixed point in time used as the base for commit dates in these tests.
+const BASE_TIME: i64 = 1_746_000_000;
+
+/// Opens the temporary repository and runs a query over all of its history.
+///
+/// # Arguments
+/// * `repo` - The temporary repository to query.
+/// * `refs` - The references to walk.
+///
+/// # Panics
+/// This function will panic if the repository cannot be opened or queried.
+fn get_all_commits(repo: &TempGitRepository, refs: RefSelection) -> Vec<Commit> {
+    let local = LocalGitRepository::new(repo.path()).unwrap();
+    let since = DateTime::from_timestamp(0, 0).unwrap();
+    let until = DateTime::from_timestamp(BASE_TIME * 2, 0).unwrap();
+    local
+        .get_commits(&CommitQuery::new(since, until).with_refs(refs))
+        .unwrap()
+}
+
+/// Returns the titles of the given commits, in order.
+fn titles(commits: &[Commit]) -> Vec<&str> {
+    commits.iter().map(|c| c.title.as_str()).collect()
+}
+
+/// Creates a repository with the following history:
+///
+/// ```text
+/// main:        c1 -- c2
+///                \
+/// feature/x:      f1
+/// fix/y:       c1
+/// ```
+fn create_branched_repository() -> TempGitRepository {
+    let repo = TempGitRepository::new();
+    let c1 = repo.commit("refs/heads/main", "c1", BASE_TIME);
+    repo.branch("feature/x", c1);
+    repo.branch("fix/y", c1);
+    repo.commit("refs/heads/main", "c2", BASE_TIME + 20);
+    repo.commit("refs/heads/feature/x", "f1", BASE_TIME + 10);
+    repo
+}
+
+/// Tests that only commits reachable from HEAD are reported by default.
+#[test]
+fn test_get_commits_head_only() {
+    let repo = create_branched_repository();
+
+    let commits = get_all_commits(&repo, RefSelection::Head);
+
+    assert_eq!(titles(&commits), vec!["c2", "c1"]);
+    assert_eq!(commits[0].branches, vec!["main"]);
+    assert_eq!(commits[1].branches, vec!["main"]);
+}
+
+/// Tests that all local branches are walked and shared commits are reported once.
+///
+/// This test verifies that a commit reachable from several branches appears
+/// only once, with every branch that contains it attached.
+#[test]
+fn test_get_commits_local_branches() {
+    let repo = create_branched_repository();
+
+    let commits = get_all_commits(&repo, RefSelection::LocalBranches);
+
+    assert_eq!(titles(&commits), vec!["c2", "f1", "c1"]);
+    assert_eq!(commits[0].branches, vec!["main"]);
+    assert_eq!(commits[1].branches, vec!["feature/x"]);
+    assert_eq!(commits[2].branches, vec!["feature/x", "fix/y", "main"]);
+}
+
+/// Tests that all refs, including tags, are walked.
+#[test]
+fn test_get_commits_all_refs() {
+    let repo = create_branched_repository();
+    let f1 = repo.git.refname_to_id("refs/heads/feature/x").unwrap();
+    let f1 = repo.git.find_object(f1, None).unwrap();
+    repo.git.tag_lightweight("v1.0", &f1, false).unwrap();
+
+    let commits = get_all_commits(&repo, RefSelection::AllRefs);
+
+    assert_eq!(titles(&commits), vec!["c2", "f1", "c1"]);
+    assert_eq!(commits[1].branches, vec!["feature/x", "v1.0"]);
+}
+
+/// Tests that the containing references are left empty when not asked for.
+#[test]
+fn test_get_commits_without_branches() {
+    let repo = create_branched_repository();
+    let local = LocalGitRepository::new(repo.path()).unwrap();
+    let since = DateTime::from_timestamp(0, 0).unwrap();
+    let until = DateTime::from_timestamp(BASE_TIME * 2, 0).unwrap();
+    let query = CommitQuery::new(since, until)
+        .with_refs(RefSelection::LocalBranches)
+        .with_branches(false);
+
+    let commits = local.get_commits(&query).unwrap();
+
+    assert_eq!(titles(&commits), vec!["c2", "f1", "c1"]);
+    assert!(commits.iter().all(|c| c.branches.is_empty()));
+}
+
+/// Tests that references sharing a short name are all walked and told apart.
+///
+/// This is a regression test for tips keyed by short name, where the tag `v1`
+/// replaced the branch `v1` and the branch's commits were never walked.
+#[test]
+fn test_get_commits_ambiguous_ref_names() {
+    let repo = create_branched_repository();
+    let c1 = repo.git.refname_to_id("refs/heads/fix/y").unwrap();
+    let f1 = repo.git.refname_to_id("refs/heads/feature/x").unwrap();
+    repo.branch("v1", f1);
+    repo.tag("v1", c1);
+
+    let commits = get_all_commits(&repo, RefSelection::AllRefs);
+
+    assert_eq!(titles(&commits), vec!["c2", "f1", "c1"]);
+    assert_eq!(commits[1].branches, vec!["feature/x", "heads/v1"]);
+    assert_eq!(
+        commits[2].branches,
+        vec!["feature/x", "fix/y", "heads/v1", "main", "tags/v1"]
+    );
+}
+
+/// Tests that only refs matching the glob patterns are walked.
+#[test]
+fn test_get_commits_globs() {
+    let repo = create_branched_repository();
+
+    let commits = get_all_commits(
+        &repo,
+        RefSelection::Globs(vec!["refs/heads/feature/*".to_string()]),
+    );
+    assert_eq!(titles(&commits), vec!["f1", "c1"]);
+    assert_eq!(commits[1].branches, vec!["feature/x"]);
+
+    // Patterns without the `refs/` prefix are relative to `refs/`
+    let commits = get_all_commits(&repo, RefSelection::Globs(vec!["heads/fix/*".to_string()]));
+    assert_eq!(titles(&commits), vec!["c1"]);
+    assert_eq!(commits[0].branches, vec!["fix/y"]);
+}
+
+/// Tests that a glob matching no refs yields no commits.
+#[test]
+fn test_get_commits_globs_no_match() {
+    let repo = create_branched_repository();
+
+    let commits = get_all_commits(
+        &repo,
+        RefSelection::Globs(vec!["refs/heads/missing/*".to_string()]),
+    );
+    assert!(commits.is_empty());
+}
+
+/// Tests that a commit with an out-of-order date does not hide newer commits.
+///
+/// This is a regression test for a walk that stopped at the first commit older
+/// than `since`. The history is linear, but the rebased commits carry dates far
+/// older than the commits that precede them.
+#[test]
+fn test_get_commits_out_of_order_dates() {
+    let repo = TempGitRepository::new();
+    let day = Duration::days(1).num_seconds();
+    repo.commit("refs/heads/main", "ancient", BASE_TIME - 30 * day);
+    repo.commit("refs/heads/main", "recent 1", BASE_TIME + 10);
+    repo.commit("refs/heads/main", "rebased 1", BASE_TIME - 20 * day);
+    repo.commit("refs/heads/main", "rebased 2", BASE_TIME - 21 * day);
+    repo.commit("refs/heads/main", "recent 2", BASE_TIME + 20);
+    repo.commit("refs/heads/main", "rebased 3", BASE_TIME - 10 * day);
+    repo.commit("refs/heads/main", "recent 3", BASE_TIME + 30);
+
+    let local = LocalGitRepository::new(repo.path()).unwrap();
+    let since = DateTime::from_timestamp(BASE_TIME, 0).unwrap();
+    let until = DateTime::from_timestamp(BASE_TIME + day, 0).unwrap();
+    let commits = local.get_commits(&CommitQuery::new(since, until)).unwrap();
+
+    assert_eq!(titles(&commits), vec!["recent 3", "recent 2", "recent 1"]);
+}
+
+/// Tests that author and committer identities and dates are recorded separately.
+#[test]
+fn test_get_commits_author_and_committer() {
+    let repo = TempGitRepository::new();
+    let author = Signature::new("Bix Caleen", "bix@ferrix.org", &Time::new(BASE_TIME, 0)).unwrap();
+    let committer =
+        Signature::new("Brasso", "brasso@ferrix.org", &Time::new(BASE_TIME + 60, 0)).unwrap();
+    repo.commit_as("refs/heads/main", "rebased", &author, &committer);
+
+    let commits = get_all_commits(&repo, RefSelection::Head);
+
+    assert_eq!(commits.len(), 1);
+    assert_eq!(
+        commits[0].author,
+        CommitAuthor::new("Bix Caleen".to_string(), "bix@ferrix.org".to_string())
+    );
+    assert_eq!(commits[0].author_date.timestamp(), BASE_TIME);
+    assert_eq!(
+        commits[0].committer,
+        CommitAuthor::new("Brasso".to_string(), "brasso@ferrix.org".to_string())
+    );
+    assert_eq!(commits[0].committer_date.timestamp(), BASE_TIME + 60);
+}
+
+/// Tests that the date range can be applied to either the author or committer date.
+///
+/// A rebased commit keeps its old author date but gets a new committer date, so
+/// it only falls in a recent window when filtering by committer date.
+#[test]
+fn test_get_commits_date_field() {
+    let repo = TempGitRepository::new();
+    let day = Duration::days(1).num_seconds();
+    let author = Signature::new(
+        "Bix Caleen",
+        "bix@ferrix.org",
+        &Time::new(BASE_TIME - 7 * day, 0),
+    )
+    .unwrap();
+    let committer =
+        Signature::new("Bix Caleen", "bix@ferrix.org", &Time::new(BASE_TIME, 0)).unwrap();
+    repo.commit_as("refs/heads/main", "rebased", &author, &committer);
+
+    let local = LocalGitRepository::new(repo.path()).unwrap();
+    let since = DateTime::from_timestamp(BASE_TIME - day, 0).unwrap();
+    let until = DateTime::from_timestamp(BASE_TIME + day, 0).unwrap();
+
+    let query = CommitQuery::new(since, until).with_date_field(DateField::Committer);
+    assert_eq!(titles(&local.get_commits(&query).unwrap()), vec!["rebased"]);
+
+    let query = CommitQuery::new(since, until).with_date_field(DateField::Author);
+    assert!(local.get_commits(&query).unwrap().is_empty());
+}
+
+/// Tests that filtering on author date does not stop the walk at rebased commits.
+///
+/// This is a regression test for a walk that decided when to stop using the
+/// filtered date instead of the committer date it is sorted by. The rebased
+/// commits are walked first and have old author dates, but the commit behind
+/// them was authored inside the window.
+#[test]
+fn test_get_commits_author_date_after_rebase() {
+    let repo = TempGitRepository::new();
+    let day = Duration::days(1).num_seconds();
+    repo.commit("refs/heads/main", "inside", BASE_TIME);
+    let author = Signature::new(
+        "Bix Caleen",
+        "bix@ferrix.org",
+        &Time::new(BASE_TIME - 30 * day, 0),
+    )
+    .unwrap();
+    for i in 0..40 {
+        let committer = Signature::new(
+            "Brasso",
+            "brasso@ferrix.org",
+            &Time::new(BASE_TIME + 60 + i, 0),
+        )
+        .unwrap();
+        repo.commit_as(
+            "refs/heads/main",
+            &format!("rebased {}", i),
+            &author,
+            &committer,
+        );
+    }
+
+    let local = LocalGitRepository::new(repo.path()).unwrap();
+    let since = DateTime::from_timestamp(BASE_TIME - day, 0).unwrap();
+    let until = DateTime::from_timestamp(BASE_TIME + day, 0).unwrap();
+    let query = CommitQuery::new(since, until).with_date_field(DateField::Author);
+
+    assert_eq!(titles(&local.get_commits(&query).unwrap()), vec!["inside"]);
+}
+
+/// Tests that commit dates keep their recorded timezone offset.
+///
+/// This test verifies that the stored date is the true instant, in the author's
+/// zone, and that range filtering compares true instants.
+#[test]
+fn test_get_commits_preserves_timezone() {
+    let repo = TempGitRepository::new();
+    let author = Signature::new(
+        "Bix Caleen",
+        "bix@ferrix.org",
+        &Time::new(BASE_TIME, -8 * 60),
+    )
+    .unwrap();
+    repo.commit_as("refs/heads/main", "pacific", &author, &author);
+
+    let local = LocalGitRepository::new(repo.path()).unwrap();
+    let since = DateTime::from_timestamp(BASE_TIME, 0).unwrap();
+    let commits = local.get_commits(&CommitQuery::new(since, since)).unwrap();
+
+    assert_eq!(titles(&commits), vec!["pacific"]);
+    assert_eq!(commits[0].author_date.timestamp(), BASE_TIME);
+    assert_eq!(commits[0].author_date.offset().local_minus_utc(), -8 * 3600);
+
+    let since = DateTime::from_timestamp(BASE_TIME + 1, 0).unwrap();
+    let until = DateTime::from_timestamp(BASE_TIME + 8 * 3600, 0).unwrap();
+    assert!(local
+        .get_commits(&CommitQuery::new(since, until))
+        .unwrap()
+        .is_empty());
+}
+
+/// Creates a repository with one commit by an old identity and a `.mailmap`
+/// mapping it to a new name and email.
+fn create_mailmap_repository() -> TempGitRepository {
+    let repo = TempGitRepository::new();
+    let old = Signature::new("Bix", "bix@old.example", &Time::new(BASE_TIME, 0)).unwrap();
+    let committer =
+        Signature::new("Maarva", "maarva@old.example", &Time::new(BASE_TIME, 0)).unwrap();
+    repo.commit_as("refs/heads/main", "mapped", &old, &committer);
+    std::fs::write(
+        repo.path().join(".mailmap"),
+        "Bix Caleen <bix@ferrix.org> <bix@old.example>\n",
+    )
+    .unwrap();
+    repo
+}
+
+/// Tests that identities are reported as recorded unless the mailmap is enabled.
+#[test]
+fn test_get_commits_ignores_mailmap_by_default() {
+    let repo = create_mailmap_repository();
+
+    let commits = get_all_commits(&repo, RefSelection::Head);
+
+    assert_eq!(
+        commits[0].author,
+        CommitAuthor::new("Bix".to_string(), "bix@old.example".to_string())
+    );
+}
+
+/// Tests that the repository's `.mailmap` resolves authors when enabled.
+#[test]
+fn test_get_commits_with_mailmap() {
+    let repo = create_mailmap_repository();
+    let mut local = LocalGitRepository::new(repo.path()).unwrap();
+    local.enable_mailmap(None);
+
+    let since = DateTime::from_timestamp(0, 0).unwrap();
+    let commits = local
+        .get_commits(&CommitQuery::new(
+            since,
+            DateTime::from_timestamp(BASE_TIME, 0).unwrap(),
+        ))
+        .unwrap();
+
+    assert_eq!(
+        commits[0].author,
+        CommitAuthor::new("Bix Caleen".to_string(), "bix@ferrix.org".to_string())
+    );
+    assert_eq!(
+        commits[0].committer,
+        CommitAuthor::new("Maarva".to_string(), "maarva@old.example".to_string())
+    );
+}
+
+/// Tests that a global mailmap applies alongside, and overrides, the repository's own.
+#[test]
+fn test_get_commits_with_global_mailmap() {
+    let repo = create_mailmap_repository();
+    let mut local = LocalGitRepository::new(repo.path()).unwrap();
+    local.enable_mailmap(Some(
+        "Maarva Andor <maarva@ferrix.org> <maarva@old.example>\n\
+         B. Caleen <bix@ferrix.org> <bix@old.example>\n"
+            .to_string(),
+    ));
+
+    let since = DateTime::from_timestamp(0, 0).unwrap();
+    let commits = local
+        .get_commits(&CommitQuery::new(
+            since,
+            DateTime::from_timestamp(BASE_TIME, 0).unwrap(),
+        ))
+        .unwrap();
+
+    assert_eq!(
+        commits[0].author,
+        CommitAuthor::new("B. Caleen".to_string(), "bix@ferrix.org".to_string())
+    );
+    assert_eq!(
+        commits[0].committer,
+        CommitAuthor::new("Maarva Andor".to_string(), "maarva@ferrix.org".to_string())
+    );
+}
+
+/// Runs a query over the history of a repository up to `until` seconds since
+/// the Unix epoch.
+fn get_commits_until(local: &LocalGitRepository, until: i64) -> Vec<Commit> {
+    let since = DateTime::from_timestamp(0, 0).unwrap();
+    let until = DateTime::from_timestamp(until, 0).unwrap();
+    local.get_commits(&CommitQuery::new(since, until)).unwrap()
+}
+
+/// Tests that a global mailmap adds to a `mailmap.file` set in the repository's config.
+#[test]
+fn test_get_commits_with_global_mailmap_and_config() {
+    let repo = TempGitRepository::new();
+    let old = Signature::new("Bix", "bix@old.example", &Time::new(BASE_TIME, 0)).unwrap();
+    let committer =
+        Signature::new("Maarva", "maarva@old.example", &Time::new(BASE_TIME, 0)).unwrap();
+    repo.commit_as("refs/heads/main", "mapped", &old, &committer);
+    let configured = repo.path().join("configured.mailmap");
+    std::fs::write(
+        &configured,
+        "Bix Caleen <bix@ferrix.org> <bix@old.example>\n",
+    )
+    .unwrap();
+    repo.git
+        .config()
+        .unwrap()
+        .set_str("mailmap.file", configured.to_str().unwrap())
+        .unwrap();
+
+    let mut local = LocalGitRepository::new(repo.path()).unwrap();
+    local.enable_mailmap(Some(
+        "# Global identities\nMaarva Andor <maarva@ferrix.org> <maarva@old.example>\n".to_string(),
+    ));
+    let commits = get_commits_until(&local, BASE_TIME);
+
+    assert_eq!(
+        commits[0].author,
+        CommitAuthor::new("Bix Caleen".to_string(), "bix@ferrix.org".to_string())
+    );
+    assert_eq!(
+        commits[0].committer,
+        CommitAuthor::new("Maarva Andor".to_string(), "maarva@ferrix.org".to_string())
+    );
+}
+
+/// Tests that a global mailmap adds to the `.mailmap` committed to a bare repository.
+#[test]
+fn test_get_commits_with_global_mailmap_in_bare_repository() {
+    let repo = TempGitRepository::new_bare();
+    repo.commit_files(
+        "refs/heads/main",
+        "mapped",
+        BASE_TIME,
+        &[(
+            ".mailmap",
+            "Test Person <test@ferrix.org> <test@example.com>\n",
+        )],
+    );
+
+    let mut local = LocalGitRepository::new(repo.path()).unwrap();
+    local.enable_mailmap(Some("Someone <someone@ferrix.org>\n".to_string()));
+    let commits = get_commits_until(&local, BASE_TIME);
+
+    assert_eq!(
+        commits[0].author,
+        CommitAuthor::new("Test Person".to_string(), "test@ferrix.org".to_string())
+    );
+}
+
+/// Creates a repository with the following history, where `m` merges `f1`:
+///
+/// ```text
+/// main:        c1 (src/lib.rs) -- c2 (README.md) -- m
+///                \                                 /
+/// feature/x:      f1 (src/lib.rs, docs/guide.md) --
+/// ```
+fn create_merged_repository() -> TempGitRepository {
+    let repo = TempGitRepository::new();
+    let c1 = repo.commit_files("refs/heads/main", "c1", BASE_TIME, &[("src/lib.rs", "1")]);
+    repo.branch("feature/x", c1);
+    repo.commit_files(
+        "refs/heads/main",
+        "c2",
+        BASE_TIME + 10,
+        &[("README.md", "2")],
+    );
+    let f1 = repo.commit_files(
+        "refs/heads/feature/x",
+        "f1",
+        BASE_TIME + 20,
+        &[("src/lib.rs", "3"), ("docs/guide.md", "3")],
+    );
+    repo.merge("refs/heads/main", "m", BASE_TIME + 30, f1);
+    repo
+}
+
+/// Runs a query with a filter over all of the repository's history.
+fn get_filtered_commits(repo: &TempGitRepository, filter: CommitFilter) -> Vec<Commit> {
+    let local = LocalGitRepository::new(repo.path()).unwrap();
+    let since = DateTime::from_timestamp(0, 0).unwrap();
+    let until = DateTime::from_timestamp(BASE_TIME * 2, 0).unwrap();
+    local
+        .get_commits(&CommitQuery::new(since, until).with_filter(filter))
+        .unwrap()
+}
+
+/// Tests that every commit records its parents.
+#[test]
+fn test_get_commits_parents() {
+    let repo = create_merged_repository();
+
+    let commits = get_all_commits(&repo, RefSelection::Head);
+
+    assert_eq!(titles(&commits), vec!["m", "f1", "c2", "c1"]);
+    assert_eq!(
+        commits[0].parents,
+        vec![commits[2].hash.full.clone(), commits[1].hash.full.clone()]
+    );
+    assert!(commits[3].parents.is_empty());
+    assert!(commits[0].paths.is_empty());
+}
+
+/// Tests that merge filters select merges or non-merges.
+#[test]
+fn test_get_commits_merge_filter() {
+    let repo = create_merged_repository();
+
+    assert_eq!(
+        titles(&get_filtered_commits(&repo, CommitFilter::Merge)),
+        vec!["m"]
+    );
+    assert_eq!(
+        titles(&get_filtered_commits(&repo, !CommitFilter::Merge)),
+        vec!["f1", "c2", "c1"]
+    );
+}
+
+/// Tests that merge modes skip or keep only merges, and that first-parent
+/// walks skip the commits merged into the branch.
+#[test]
+fn test_get_commits_merge_modes() {
+    let repo = create_merged_repository();
+    let local = LocalGitRepository::new(repo.path()).unwrap();
+    let since = DateTime::from_timestamp(0, 0).unwrap();
+    let until = DateTime::from_timestamp(BASE_TIME * 2, 0).unwrap();
+    let query = CommitQuery::new(since, until);
+    let get = |query: CommitQuery| -> Vec<String> {
+        let commits = local.get_commits(&query).unwrap();
+        commits.into_iter().map(|c| c.title).collect()
+    };
+
+    assert_eq!(
+        get(query.clone().with_merges(MergeMode::Exclude)),
+        vec!["f1", "c2", "c1"]
+    );
+    assert_eq!(get(query.clone().with_merges(MergeMode::Only)), vec!["m"]);
+    assert_eq!(
+        get(query.clone().with_first_parent(true)),
+        vec!["m", "c2", "c1"]
+    );
+    assert_eq!(
+        get(query
+            .with_first_parent(true)
+            .with_merges(MergeMode::Exclude)),
+        vec!["c2", "c1"]
+    );
+}
+
+/// Tests that first-parent walks only report a branch as containing the commits
+/// on its first-parent history.
+#[test]
+fn test_get_commits_first_parent_branches() {
+    let repo = create_merged_repository();
+    let local = LocalGitRepository::new(repo.path()).unwrap();
+    let since = DateTime::from_timestamp(0, 0).unwrap();
+    let until = DateTime::from_timestamp(BASE_TIME * 2, 0).unwrap();
+    let query = CommitQuery::new(since, until).with_refs(RefSelection::LocalBranches);
+    let branches = |query: &CommitQuery, title: &str| -> Vec<String> {
+        let commits = local.get_commits(query).unwrap();
+        let commit = commits.into_iter().find(|c| c.title == title).unwrap();
+        commit.branches
+    };
+
+    assert_eq!(branches(&query, "f1"), vec!["feature/x", "main"]);
+    let query = query.with_first_parent(true);
+    assert_eq!(branches(&query, "f1"), vec!["feature/x"]);
+    assert_eq!(branches(&query, "c1"), vec!["feature/x", "main"]);
+    assert_eq!(branches(&query, "m"), vec!["main"]);
+}
+
+/// Tests that a revision range walks the commits reachable from its end but not
+/// from its start, and still applies the date range.
+#[test]
+fn test_get_commits_with_range() {
+    let repo = create_merged_repository();
+    let c1 = repo.git.revparse_single("main~2").unwrap().id();
+    repo.tag("v1.0", c1);
+    let local = LocalGitRepository::new(repo.path()).unwrap();
+    let since = DateTime::from_timestamp(0, 0).unwrap();
+    let until = DateTime::from_timestamp(BASE_TIME * 2, 0).unwrap();
+    let query = |from: Option<&str>, to: &str| {
+        let range = RevisionRange::new(from.map(str::to_string), to.to_string());
+        CommitQuery::new(since, until).with_range(range)
+    };
+
+    let commits = local.get_commits(&query(Some("v1.0"), "main")).unwrap();
+    assert_eq!(titles(&commits), vec!["m", "f1", "c2"]);
+    assert_eq!(commits[0].branches, vec!["main"]);
+
+    let commits = local
+        .get_commits(&query(Some("v1.0"), "feature/x"))
+        .unwrap();
+    assert_eq!(titles(&commits), vec!["f1"]);
+
+    let commits = local.get_commits(&query(None, "v1.0")).unwrap();
+    assert_eq!(titles(&commits), vec!["c1"]);
+
+    let late = DateTime::from_timestamp(BASE_TIME + 15, 0).unwrap();
+    let range = query(Some("v1.0"), "main").range.unwrap();
+    let commits = local
+        .get_commits(&CommitQuery::new(late, until).with_range(range))
+        .unwrap();
+    assert_eq!(titles(&commits), vec!["m", "f1"]);
+
+    assert!(matches!(
+        local.get_commits(&query(Some("v9.9"), "main")),
+        Err(WalrustError::RevisionNotFound(rev)) if rev == "v9.9"
+    ));
+    assert!(matches!(
+        local.get_commits(&query(None, "release/2.0")),
+        Err(WalrustError::RevisionNotFound(rev)) if rev == "release/2.0"
+    ));
+}
+
+/// Tests that a symmetric range walks the commits reachable from either side
+/// but not from both, listing the side each commit is reachable from.
+#[test]
+fn test_get_commits_with_symmetric_range() {
+    let repo = create_merged_repository();
+    let local = LocalGitRepository::new(repo.path()).unwrap();
+    let since = DateTime::from_timestamp(0, 0).unwrap();
+    let until = DateTime::from_timestamp(BASE_TIME * 2, 0).unwrap();
+    let query = |range: &str| CommitQuery::new(since, until).with_range(range.parse().unwrap());
+
+    let commits = local.get_commits(&query("main~1...feature/x")).unwrap();
+    assert_eq!(titles(&commits), vec!["f1", "c2"]);
+    assert_eq!(commits[0].branches, vec!["feature/x"]);
+    assert_eq!(commits[1].branches, vec!["main~1"]);
+
+    let commits = local.get_commits(&query("feature/x...main")).unwrap();
+    assert_eq!(titles(&commits), vec!["m", "c2"]);
+
+    let late = DateTime::from_timestamp(BASE_TIME + 15, 0).unwrap();
+    let range = "main~1...feature/x".parse().unwrap();
+    let commits = local
+        .get_commits(&CommitQuery::new(late, until).with_range(range))
+        .unwrap();
+    assert_eq!(titles(&commits), vec!["f1"]);
+
+    let orphan = repo.commit("refs/heads/orphan", "o1", BASE_TIME + 40);
+    repo.tag("orphan-tag", orphan);
+    let commits = local.get_commits(&query("orphan-tag...feature/x")).unwrap();
+    assert_eq!(titles(&commits), vec!["o1", "f1", "c1"]);
+
+    assert!(matches!(
+        local.get_commits(&query("main...v9.9")),
+        Err(WalrustError::RevisionNotFound(rev)) if rev == "v9.9"
+    ));
+}
+
+/// Tests that path filters match the paths changed against the first parent.
+///
+/// The merge keeps the tree of `main`, so it changes nothing relative to its
+/// first parent and never matches.
+#[test]
+fn test_get_commits_path_filter() {
+    let repo = create_merged_repository();
+
+    let commits = get_filtered_commits(&repo, CommitFilter::path("src/").unwrap());
+    assert_eq!(titles(&commits), vec!["f1", "c1"]);
+    assert_eq!(commits[1].paths, vec!["src/lib.rs"]);
+
+    let commits = get_filtered_commits(&repo, CommitFilter::path("*.md").unwrap());
+    assert_eq!(titles(&commits), vec!["f1", "c2"]);
+}
+
+/// Tests that query paths keep commits changing any of the pathspecs, and that
+/// merges are compared against their first parent.
+#[test]
+fn test_get_commits_with_paths() {
+    let repo = TempGitRepository::new();
+    let c1 = repo.commit_files("refs/heads/main", "c1", BASE_TIME, &[("src/lib.rs", "1")]);
+    repo.branch("deploy", c1);
+    repo.commit_files(
+        "refs/heads/main",
+        "c2",
+        BASE_TIME + 10,
+        &[("src/lib.rs", "2")],
+    );
+    let d1 = repo.commit_files(
+        "refs/heads/deploy",
+        "d1",
+        BASE_TIME + 20,
+        &[("deploy/prod.toml", "3")],
+    );
+    repo.merge_changes("refs/heads/main", "m", BASE_TIME + 30, d1);
+    let local = LocalGitRepository::new(repo.path()).unwrap();
+    let since = DateTime::from_timestamp(0, 0).unwrap();
+    let until = DateTime::from_timestamp(BASE_TIME * 2, 0).unwrap();
+    let query = CommitQuery::new(since, until);
+
+    let commits = local
+        .get_commits(
+            &query
+                .clone()
+                .with_paths(Pathspec::new(["deploy/"]).unwrap()),
+        )
+        .unwrap();
+    assert_eq!(titles(&commits), vec!["m", "d1"]);
+    assert_eq!(commits[0].paths, vec!["deploy/prod.toml"]);
+
+    let commits = local
+        .get_commits(
+            &query
+                .clone()
+                .with_paths(Pathspec::new(["*.toml", "src/lib.rs"]).unwrap()),
+        )
+        .unwrap();
+    assert_eq!(titles(&commits), vec!["m", "d1", "c2", "c1"]);
+
+    let commits = local
+        .get_commits(&query.with_paths(Pathspec::new(["docs"]).unwrap()))
+        .unwrap();
+    assert!(commits.is_empty());
+}
+
+/// Tests that diff statistics are only computed when the query asks for them,
+/// and count lines against the first parent.
+#[test]
+fn test_get_commits_with_stats() {
+    let repo = TempGitRepository::new();
+    repo.commit_files(
+        "refs/heads/main",
+        "c1",
+        BASE_TIME,
+        &[("src/lib.rs", "a\nb\nc\n")],
+    );
+    repo.commit_files(
+        "refs/heads/main",
+        "c2",
+        BASE_TIME + 10,
+        &[("src/lib.rs", "a\nB\nc\nd\n"), ("README.md", "hi\n")],
+    );
+    let local = LocalGitRepository::new(repo.path()).unwrap();
+    let since = DateTime::from_timestamp(0, 0).unwrap();
+    let until = DateTime::from_timestamp(BASE_TIME * 2, 0).unwrap();
+    let query = CommitQuery::new(since, until);
+
+    let commits = local.get_commits(&query).unwrap();
+    assert!(commits.iter().all(|c| c.stats.is_none()));
+
+    let commits = local.get_commits(&query.with_stats(true)).unwrap();
+    assert_eq!(titles(&commits), vec!["c2", "c1"]);
+    let stats = commits[0].stats.as_ref().unwrap();
+    assert_eq!(
+        stats.files,
+        vec![
+            FileStats {
+                path: "README.md".to_string(),
+                insertions: 1,
+                deletions: 0,
+            },
+            FileStats {
+                path: "src/lib.rs".to_string(),
+                insertions: 2,
+                deletions: 1,
+            },
+        ]
+    );
+    assert_eq!((stats.insertions, stats.deletions), (3, 1));
+    let stats = commits[1].stats.as_ref().unwrap();
+    assert_eq!((stats.files_changed(), stats.insertions), (1, 3));
+}
+
+/// Tests that a date range in the filter narrows the query and combines with
+/// other filters.
+#[test]
+fn test_get_commits_filter_date_range() {
+    let repo = create_merged_repository();
+    let filter = CommitFilter::DateRange {
+        field: DateField::Committer,
+        since: DateTime::from_timestamp(BASE_TIME + 10, 0),
+        until: DateTime::from_timestamp(BASE_TIME + 20, 0),
+    };
+
+    let commits = get_filtered_commits(&repo, filter.clone());
+    assert_eq!(titles(&commits), vec!["f1", "c2"]);
+
+    let commits = get_filtered_commits(&repo, filter.and(!CommitFilter::path("src").unwrap()));
+    assert_eq!(titles(&commits), vec!["c2"]);
+}
+
+/// Tests that a bare repository can be opened and queried, and is its own
+/// common Git directory.
+#[test]
+fn test_open_bare_repository() {
+    let repo = TempGitRepository::new_bare();
+    repo.commit("refs/heads/main", "c1", BASE_TIME);
+
+    let commits = get_all_commits(&repo, RefSelection::Head);
+    assert_eq!(titles(&commits), vec!["c1"]);
+
+    let local = LocalGitRepository::new(repo.path()).unwrap();
+    assert!(!local.is_linked_worktree());
+    assert_eq!(
+        local.common_dir().unwrap().canonicalize().unwrap(),
+        repo.path().canonicalize().unwrap()
+    );
+}
+
+/// Tests that a linked worktree, whose `.git` is a file, can be opened and
+/// shares the common Git directory of its main worktree.
+#[test]
+fn test_open_linked_worktree() {
+    let repo = TempGitRepository::new();
+    repo.commit("refs/heads/main", "c1", BASE_TIME);
+    let (_dir, path) = repo.add_worktree("feature");
+    repo.commit("refs/heads/feature", "f1", BASE_TIME + 10);
+
+    let worktree = LocalGitRepository::new(&path).unwrap();
+    assert!(path.join(".git").is_file());
+    assert!(worktree.is_linked_worktree());
+    let since = DateTime::from_timestamp(0, 0).unwrap();
+    let until = DateTime::from_timestamp(BASE_TIME * 2, 0).unwrap();
+    let commits = worktree
+        .get_commits(&CommitQuery::new(since, until))
+        .unwrap();
+    assert_eq!(titles(&commits), vec!["f1", "c1"]);
+
+    let main = LocalGitRepository::new(repo.path()).unwrap();
+    assert!(!main.is_linked_worktree());
+    assert_eq!(
+        worktree.common_dir().unwrap().canonicalize().unwrap(),
+        main.common_dir().unwrap().canonicalize().unwrap()
+    );
+}
+
+/// Tests that directories which are not repositories, including directories
+/// inside a working tree, are rejected with a `PathError`.
+#[test]
+fn test_open_not_a_repository() {
+    let repo = TempGitRepository::new();
+    let src = repo.path().join("src");
+    std::fs::create_dir(&src).unwrap();
+    assert!(matches!(
+        LocalGitRepository::new(&src),
+        Err(WalrustError::PathError(path)) if path == src
+    ));
+
+    let dir = tempfile::tempdir().unwrap();
+    assert!(matches!(
+        LocalGitRepository::new(dir.path()),
+        Err(WalrustError::PathError(_))
+    ));
+}
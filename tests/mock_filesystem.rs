@@ -1,17 +1,25 @@
+#![allow(dead_code)]
+
 use std::collections::HashMap;
-use std::path::{Path, PathBuf};
-use walrust::filesystem::Filesystem;
+use std::path::{Component, Path, PathBuf};
+use walrust::filesystem::{Filesystem, Metadata};
+
+/// The number of symbolic links followed while resolving a path before giving
+/// up, as with `ELOOP`.
+const MAX_LINK_HOPS: usize = 40;
 
 /// A node in the mock filesystem.
 ///
-/// This enum represents either a directory or a file in the mock filesystem.
-/// It is used to simulate a filesystem structure for testing purposes.
+/// This enum represents a directory, a file or a symbolic link in the mock
+/// filesystem. It is used to simulate a filesystem structure for testing purposes.
 #[derive(Debug)]
 pub enum MockFsNode {
     /// A directory containing child nodes.
     Directory(HashMap<String, MockFsNode>),
     /// A file node.
     File,
+    /// A symbolic link to the node at the given path from the filesystem root.
+    Symlink(PathBuf),
 }
 
 /// Creates a mock directory tree for testing.
@@ -19,10 +27,20 @@ pub enum MockFsNode {
 /// The tree structure is as follows:
 /// ```text
 /// root
+/// ├── links
+/// │   ├── left -> root/links
+/// │   ├── right -> root/links
+/// │   └── radio -> root/depth_3/depth_3/nested_3
 /// ├── nested_1
-/// │   └── .git
+/// │   ├── .git
+/// │   └── plugins
+/// │       └── radio
+/// │           └── .git
 /// ├── not_a_repo
 /// │   └── file.txt
+/// ├── node_modules
+/// │   └── left-pad
+/// │       └── .git
 /// ├── depth_2
 /// │   └── nested_2
 /// │       └── .git
@@ -33,8 +51,12 @@ pub enum MockFsNode {
 /// └── depth_4
 ///     └── depth_4
 ///         └── depth_4
-///             └── nested_4
-///                 └── .git
+///             ├── nested_4
+///             │   └── .git
+///             └── radio.git
+///                 ├── HEAD
+///                 ├── objects
+///                 └── refs
 /// ```
 ///
 /// # Returns
@@ -53,17 +75,53 @@ fn create_mock_directory_tree() -> MockFsNode {
     MockFsNode::Directory(HashMap::from([(
         "root".to_string(),
         MockFsNode::Directory(HashMap::from([
+            (
+                "links".to_string(),
+                MockFsNode::Directory(HashMap::from([
+                    (
+                        "left".to_string(),
+                        MockFsNode::Symlink(PathBuf::from("root/links")),
+                    ),
+                    (
+                        "right".to_string(),
+                        MockFsNode::Symlink(PathBuf::from("root/links")),
+                    ),
+                    (
+                        "radio".to_string(),
+                        MockFsNode::Symlink(PathBuf::from("root/depth_3/depth_3/nested_3")),
+                    ),
+                ])),
+            ),
             (
                 "nested_1".to_string(),
-                MockFsNode::Directory(HashMap::from([(
-                    ".git".to_string(),
-                    MockFsNode::Directory(HashMap::new()),
-                )])),
+                MockFsNode::Directory(HashMap::from([
+                    (".git".to_string(), MockFsNode::Directory(HashMap::new())),
+                    (
+                        "plugins".to_string(),
+                        MockFsNode::Directory(HashMap::from([(
+                            "radio".to_string(),
+                            MockFsNode::Directory(HashMap::from([(
+                                ".git".to_string(),
+                                MockFsNode::Directory(HashMap::new()),
+                            )])),
+                        )])),
+                    ),
+                ])),
             ),
             (
                 "not_a_repo".to_string(),
                 MockFsNode::Directory(HashMap::from([("file.txt".to_string(), MockFsNode::File)])),
             ),
+            (
+                "node_modules".to_string(),
+                MockFsNode::Directory(HashMap::from([(
+                    "left-pad".to_string(),
+                    MockFsNode::Directory(HashMap::from([(
+                        ".git".to_string(),
+                        MockFsNode::Directory(HashMap::new()),
+                    )])),
+                )])),
+            ),
             (
                 "depth_2".to_string(),
                 MockFsNode::Directory(HashMap::from([(
@@ -93,13 +151,23 @@ fn create_mock_directory_tree() -> MockFsNode {
                     "depth_4".to_string(),
                     MockFsNode::Directory(HashMap::from([(
                         "depth_4".to_string(),
-                        MockFsNode::Directory(HashMap::from([(
-                            "nested_4".to_string(),
-                            MockFsNode::Directory(HashMap::from([(
-                                ".git".to_string(),
-                                MockFsNode::Directory(HashMap::new()),
-                            )])),
-                        )])),
+                        MockFsNode::Directory(HashMap::from([
+                            (
+                                "nested_4".to_string(),
+                                MockFsNode::Directory(HashMap::from([(
+                                    ".git".to_string(),
+                                    MockFsNode::Directory(HashMap::new()),
+                                )])),
+                            ),
+                            (
+                                "radio.git".to_string(),
+                                MockFsNode::Directory(HashMap::from([
+                                    ("HEAD".to_string(), MockFsNode::File),
+                                    ("objects".to_string(), MockFsNode::Directory(HashMap::new())),
+                                    ("refs".to_string(), MockFsNode::Directory(HashMap::new())),
+                                ])),
+                            ),
+                        ])),
                     )])),
                 )])),
             ),
@@ -156,18 +224,48 @@ impl MockFilesystem {
     /// assert!(node.is_some());
     /// ```
     fn find_node<'a>(&'a self, path: &Path) -> Option<&'a MockFsNode> {
+        self.resolve(path, true, MAX_LINK_HOPS)
+    }
+
+    /// Finds a node in the mock filesystem, following symbolic links.
+    ///
+    /// # Arguments
+    /// - `path`: The path to search for.
+    /// - `follow_last`: Whether to follow a symbolic link in the last component
+    ///   of the path, rather than returning the link itself.
+    /// - `hops`: The number of links that may still be followed.
+    ///
+    /// # Returns
+    /// The node, or `None` if it is not found or too many links are followed.
+    fn resolve<'a>(
+        &'a self,
+        path: &Path,
+        follow_last: bool,
+        hops: usize,
+    ) -> Option<&'a MockFsNode> {
+        let components: Vec<_> = path.iter().collect();
         let mut current = &self.root;
-        for component in path.iter() {
-            if let MockFsNode::Directory(ref children) = current {
-                current = children.get(component.to_str().unwrap())?;
-            } else {
-                return None;
+        for (i, component) in components.iter().enumerate() {
+            current = match current {
+                MockFsNode::Directory(children) => children.get(component.to_str().unwrap())?,
+                _ => return None,
+            };
+            if let MockFsNode::Symlink(target) = current {
+                if i + 1 < components.len() || follow_last {
+                    current = self.resolve(target, true, hops.checked_sub(1)?)?;
+                }
             }
         }
         Some(current)
     }
 }
 
+impl Default for MockFilesystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Filesystem for MockFilesystem {
     fn new() -> Self {
         Self::new()
@@ -198,4 +296,46 @@ impl Filesystem for MockFilesystem {
     fn exists(&self, path: &Path) -> bool {
         self.find_node(path).is_some()
     }
+
+    /// Normalizes `.` and `..` components and resolves symbolic links, since
+    /// the mock filesystem has no current directory.
+    fn canonicalize(&self, path: &Path) -> std::io::Result<PathBuf> {
+        let mut canonical = PathBuf::new();
+        for component in path.components() {
+            match component {
+                Component::CurDir => {}
+                Component::ParentDir => {
+                    canonical.pop();
+                }
+                component => {
+                    canonical.push(component);
+                    let link = self.resolve(&canonical, false, MAX_LINK_HOPS);
+                    if let Some(MockFsNode::Symlink(target)) = link {
+                        canonical = self.canonicalize(target)?;
+                    }
+                }
+            }
+        }
+        match self.find_node(&canonical) {
+            Some(_) => Ok(canonical),
+            None => Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "Path not found",
+            )),
+        }
+    }
+    /// Identifies each node by its address, since nodes are never moved while
+    /// the mock filesystem is borrowed.
+    fn metadata(&self, path: &Path) -> std::io::Result<Metadata> {
+        let not_found = || std::io::Error::new(std::io::ErrorKind::NotFound, "Path not found");
+        let link = self
+            .resolve(path, false, MAX_LINK_HOPS)
+            .ok_or_else(not_found)?;
+        let node = self.find_node(path).ok_or_else(not_found)?;
+        Ok(Metadata {
+            device: 0,
+            inode: node as *const MockFsNode as u64,
+            is_symlink: matches!(link, MockFsNode::Symlink(_)),
+        })
+    }
 }
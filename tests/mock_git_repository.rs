@@ -1,20 +1,32 @@
-use chrono::{DateTime, Utc};
-use std::path::PathBuf;
+#![allow(dead_code)]
+
+use std::path::Path;
 use walrust::commit::Commit;
+use walrust::query::CommitQuery;
 use walrust::repository::GitRepository;
 use walrust::Result;
 
 /// A mock implementation of a Git repository for testing purposes.
 ///
 /// This struct stores a list of commits and provides methods to add commits
-/// and retrieve them based on date ranges.
+/// and retrieve them based on a query's date range and filter.
 pub struct MockGitRepository {
     /// A vector of commits stored in the mock repository.
     commits: Vec<Commit>,
 }
 
+impl MockGitRepository {
+    /// Adds a commit to the mock repository.
+    ///
+    /// # Arguments
+    /// - `commit`: The commit to add.
+    pub fn add_commit(&mut self, commit: Commit) {
+        self.commits.push(commit);
+    }
+}
+
 impl GitRepository for MockGitRepository {
-    fn new(_path: &PathBuf) -> Result<Self> {
+    fn new(_path: &Path) -> Result<Self> {
         Ok(MockGitRepository {
             commits: Vec::new(),
         })
@@ -24,12 +36,11 @@ impl GitRepository for MockGitRepository {
         "mock_head".to_string()
     }
 
-    fn get_commits(&self, since: DateTime<Utc>, until: DateTime<Utc>) -> Result<Vec<Commit>> {
+    fn get_commits(&self, query: &CommitQuery) -> Result<Vec<Commit>> {
         let filtered_commits: Vec<Commit> = self
             .commits
             .iter()
-            .filter(|commit| commit.commit_date >= since)
-            .filter(|commit| commit.commit_date <= until)
+            .filter(|commit| query.matches(commit))
             .cloned()
             .collect();
         Ok(filtered_commits)
@@ -39,7 +50,7 @@ impl GitRepository for MockGitRepository {
 pub struct MockBadGitRepository;
 
 impl GitRepository for MockBadGitRepository {
-    fn new(_path: &PathBuf) -> Result<Self> {
+    fn new(_path: &Path) -> Result<Self> {
         Err(walrust::WalrustError::GitError(git2::Error::from_str(
             "Mock error",
         )))
@@ -49,7 +60,7 @@ impl GitRepository for MockBadGitRepository {
         "".to_string()
     }
 
-    fn get_commits(&self, _since: DateTime<Utc>, _until: DateTime<Utc>) -> Result<Vec<Commit>> {
+    fn get_commits(&self, _query: &CommitQuery) -> Result<Vec<Commit>> {
         Err(walrust::WalrustError::GitError(git2::Error::from_str(
             "Mock error",
         )))
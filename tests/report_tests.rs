@@ -0,0 +1,192 @@
+mod mock_git_repository;
+use chrono::{DateTime, FixedOffset, TimeZone};
+use mock_git_repository::MockGitRepository;
+use std::path::{Path, PathBuf};
+use walrust::commit::{Commit, CommitAuthor, CommitHash, DiffStats, FileStats};
+use walrust::report::{write_delimited, Column, Delimiter, ReportOptions};
+#[cfg(feature = "json")]
+use walrust::report::{write_json, write_ndjson};
+use walrust::repository::Repository;
+
+/// A type alias for a `Repository` using the mock Git repository.
+type MockRepository = Repository<MockGitRepository>;
+
+/// Creates a fixed date in the given timezone for stable golden output.
+fn date(offset_hours: i32, day: u32, hour: u32) -> DateTime<FixedOffset> {
+    FixedOffset::east_opt(offset_hours * 3600)
+        .unwrap()
+        .with_ymd_and_hms(2025, 5, day, hour, 0, 0)
+        .unwrap()
+}
+
+/// Creates a fixed set of repositories and commits covering every commit field.
+fn create_results() -> Vec<(MockRepository, Vec<Commit>)> {
+    let luthen = CommitAuthor::new("Luthen Rael".to_string(), "luthen@axis.org".to_string());
+    let kleya = CommitAuthor::new("Kleya Marki".to_string(), "kleya@axis.org".to_string());
+
+    let mut rebased = Commit::new(
+        "Rebase the antiques shop".to_string(),
+        luthen.clone(),
+        date(-7, 4, 11),
+        kleya,
+        date(2, 5, 9),
+        "Rebase the antiques shop\n\nMoved the \"relics\" to the back room.\n".to_string(),
+        CommitHash::new("0123456789abcdef0123456789abcdef01234567".to_string()),
+    );
+    rebased.branches = vec!["main".to_string(), "feature/shop".to_string()];
+    rebased.parents = vec!["fedcba9876543210fedcba9876543210fedcba98".to_string()];
+    rebased.stats = Some(DiffStats::new(vec![FileStats {
+        path: "shop/relics.md".to_string(),
+        insertions: 3,
+        deletions: 1,
+    }]));
+
+    let initial = Commit::new(
+        "Initial commit".to_string(),
+        luthen.clone(),
+        date(0, 3, 8),
+        luthen,
+        date(0, 3, 8),
+        "Initial commit\n".to_string(),
+        CommitHash::new("fedcba9876543210fedcba9876543210fedcba98".to_string()),
+    );
+
+    vec![
+        (
+            MockRepository::new(Path::new("/repos/coruscant")).unwrap(),
+            vec![rebased, initial],
+        ),
+        (
+            MockRepository::new(Path::new("/repos/ferrix")).unwrap(),
+            Vec::new(),
+        ),
+    ]
+}
+
+/// Compares rendered output against a golden file in `tests/golden`.
+///
+/// Set `UPDATE_GOLDEN=1` to rewrite the golden file instead.
+///
+/// # Panics
+/// This function will panic if the output does not match the golden file.
+fn assert_golden(name: &str, actual: &[u8]) {
+    let path: PathBuf = [env!("CARGO_MANIFEST_DIR"), "tests", "golden", name]
+        .iter()
+        .collect();
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::write(&path, actual).unwrap();
+    }
+    let expected = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(String::from_utf8_lossy(actual), expected);
+}
+
+/// Tests that the JSON report matches the golden schema.
+#[cfg(feature = "json")]
+#[test]
+fn test_write_json_golden() {
+    let mut output = Vec::new();
+    write_json(&mut output, &create_results()).unwrap();
+    assert_golden("report.json", &output);
+}
+
+/// Tests that the NDJSON report matches the golden schema.
+#[cfg(feature = "json")]
+#[test]
+fn test_write_ndjson_golden() {
+    let mut output = Vec::new();
+    write_ndjson(&mut output, &create_results()).unwrap();
+    assert_golden("report.ndjson", &output);
+}
+
+/// Tests that every NDJSON line is a standalone JSON object.
+#[cfg(feature = "json")]
+#[test]
+fn test_write_ndjson_one_object_per_line() {
+    let mut output = Vec::new();
+    write_ndjson(&mut output, &create_results()).unwrap();
+    let output = String::from_utf8(output).unwrap();
+
+    let lines: Vec<_> = output.lines().collect();
+    assert_eq!(lines.len(), 2);
+    for line in lines {
+        let value: serde_json::Value = serde_json::from_str(line).unwrap();
+        assert_eq!(value["repository"]["name"], "coruscant");
+        assert_eq!(value["repository"]["head"], "mock_head");
+    }
+}
+
+/// Tests that submodules name their parent repository, which is `null` for
+/// other repositories.
+#[cfg(feature = "json")]
+#[test]
+fn test_write_json_submodule_parent() {
+    let mut results = create_results();
+    results[1].0 = MockRepository::new(Path::new("/repos/coruscant/libs/ferrix"))
+        .unwrap()
+        .with_parent("coruscant", Path::new("libs/ferrix"));
+
+    let mut output = Vec::new();
+    write_json(&mut output, &results).unwrap();
+    let value: serde_json::Value = serde_json::from_slice(&output).unwrap();
+
+    assert!(value[0]["repository"]["parent"].is_null());
+    assert!(value[0]["repository"]
+        .as_object()
+        .unwrap()
+        .contains_key("parent"));
+    assert_eq!(value[1]["repository"]["name"], "coruscant/libs/ferrix");
+    assert_eq!(value[1]["repository"]["parent"], "coruscant");
+}
+
+/// Tests that the CSV report with every column matches the golden output.
+///
+/// The golden file covers quoting of commas, quotes and multi-line messages.
+#[test]
+fn test_write_csv_golden() {
+    let mut output = Vec::new();
+    write_delimited(
+        &mut output,
+        &create_results(),
+        Delimiter::Comma,
+        &Column::ALL,
+        &ReportOptions::default(),
+    )
+    .unwrap();
+    assert_golden("report.csv", &output);
+}
+
+/// Tests that the TSV report with every column matches the golden output.
+#[test]
+fn test_write_tsv_golden() {
+    let mut output = Vec::new();
+    write_delimited(
+        &mut output,
+        &create_results(),
+        Delimiter::Tab,
+        &Column::ALL,
+        &ReportOptions::default(),
+    )
+    .unwrap();
+    assert_golden("report.tsv", &output);
+}
+
+/// Tests that only the selected columns are written, in the selected order.
+#[test]
+fn test_write_delimited_selected_columns() {
+    let mut output = Vec::new();
+    write_delimited(
+        &mut output,
+        &create_results(),
+        Delimiter::Comma,
+        &[Column::Title, Column::Repo, Column::ShortHash],
+        &ReportOptions::default(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "title,repo,short_hash\n\
+         Rebase the antiques shop,coruscant,0123456\n\
+         Initial commit,coruscant,fedcba9\n"
+    );
+}
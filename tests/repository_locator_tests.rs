@@ -3,8 +3,12 @@ mod mock_filesystem;
 use mock_filesystem::MockFilesystem;
 mod mock_git_repository;
 use mock_git_repository::{MockBadGitRepository, MockGitRepository};
+mod temp_git_repository;
 use std::collections::HashSet;
-use walrust::repository_locator::RepositoryLocator;
+use temp_git_repository::TempGitRepository;
+use walrust::repository_locator::{
+    read_repository_list, DiscoveryFilter, GitRepositoryLocator, RepositoryLocator,
+};
 
 /// A type alias for a `RepositoryLocator` using the mock filesystem and mock Git repository.
 type MockGitRepositoryLocator = RepositoryLocator<MockFilesystem, MockGitRepository>;
@@ -102,6 +106,281 @@ fn test_discover_repositories_depth_3() {
 
     run_tests(expected_uris, search_root, search_depth);
 }
+/// Returns the URIs of the repositories a locator finds, in order.
+fn locate_uris(locator: MockGitRepositoryLocator) -> Vec<PathBuf> {
+    let repositories = locator.locate().unwrap();
+    repositories
+        .iter()
+        .map(|repo| repo.get_uri().to_path_buf())
+        .collect()
+}
+
+/// Tests that dependency directories are skipped by default.
+///
+/// This test verifies that the repository under `node_modules` is only found
+/// once the default excludes are turned off.
+#[test]
+fn test_discover_repositories_default_excludes() {
+    let locator = MockGitRepositoryLocator::new(Path::new("root"), 2);
+    assert_eq!(
+        locate_uris(locator),
+        vec![
+            PathBuf::from("root/depth_2/nested_2"),
+            PathBuf::from("root/nested_1")
+        ]
+    );
+
+    let filter = DiscoveryFilter::new(Vec::<&str>::new(), Vec::<&str>::new()).unwrap();
+    let locator = MockGitRepositoryLocator::new(Path::new("root"), 2).with_filter(filter);
+    assert_eq!(
+        locate_uris(locator),
+        vec![
+            PathBuf::from("root/depth_2/nested_2"),
+            PathBuf::from("root/nested_1"),
+            PathBuf::from("root/node_modules/left-pad")
+        ]
+    );
+}
+
+/// Tests that excluded directories are not searched.
+///
+/// This test verifies that unanchored patterns skip matching directories at
+/// any depth, that anchored patterns only apply relative to the search root,
+/// and that a search root is never excluded.
+#[test]
+fn test_discover_repositories_exclude() {
+    let filter = DiscoveryFilter::new(Vec::<&str>::new(), ["depth_3", "/nested_1", "/nested_2"])
+        .unwrap()
+        .with_default_excludes();
+    let locator = MockGitRepositoryLocator::new(Path::new("root"), 4).with_filter(filter);
+    assert_eq!(
+        locate_uris(locator),
+        vec![
+            PathBuf::from("root/depth_2/nested_2"),
+            PathBuf::from("root/depth_4/depth_4/depth_4/nested_4"),
+            PathBuf::from("root/depth_4/depth_4/depth_4/radio.git")
+        ]
+    );
+
+    let filter = DiscoveryFilter::new(Vec::<&str>::new(), ["depth_3"]).unwrap();
+    let locator =
+        MockGitRepositoryLocator::new(Path::new("root/depth_3/depth_3"), 1).with_filter(filter);
+    assert_eq!(
+        locate_uris(locator),
+        vec![PathBuf::from("root/depth_3/depth_3/nested_3")]
+    );
+}
+
+/// Tests that only repositories matching an include pattern are reported.
+///
+/// This test verifies that directories which do not match are still searched
+/// for matching repositories below them.
+#[test]
+fn test_discover_repositories_include() {
+    let filter = DiscoveryFilter::new(["depth_*/**/nested_?"], Vec::<&str>::new())
+        .unwrap()
+        .with_default_excludes();
+    let locator = MockGitRepositoryLocator::new(Path::new("root"), 5).with_filter(filter);
+    assert_eq!(
+        locate_uris(locator),
+        vec![
+            PathBuf::from("root/depth_2/nested_2"),
+            PathBuf::from("root/depth_3/depth_3/nested_3"),
+            PathBuf::from("root/depth_4/depth_4/depth_4/nested_4")
+        ]
+    );
+}
+
+/// Tests that nested repositories are only found when requested.
+///
+/// This test verifies that the search stops at a repository by default, and
+/// that nested mode searches inside it, up to the search depth, without
+/// treating its `.git` directory as a candidate.
+#[test]
+fn test_discover_repositories_nested() {
+    let locator = MockGitRepositoryLocator::new(Path::new("root"), 3);
+    assert!(!locate_uris(locator).contains(&PathBuf::from("root/nested_1/plugins/radio")));
+
+    let locator = MockGitRepositoryLocator::new(Path::new("root"), 3).with_nested(true);
+    assert_eq!(
+        locate_uris(locator),
+        vec![
+            PathBuf::from("root/depth_2/nested_2"),
+            PathBuf::from("root/depth_3/depth_3/nested_3"),
+            PathBuf::from("root/nested_1"),
+            PathBuf::from("root/nested_1/plugins/radio")
+        ]
+    );
+
+    let locator = MockGitRepositoryLocator::new(Path::new("root/nested_1"), 1).with_nested(true);
+    assert_eq!(locate_uris(locator), vec![PathBuf::from("root/nested_1")]);
+}
+
+/// Tests that bare repositories are discovered and never searched.
+///
+/// This test verifies that a directory with a `HEAD` file and `objects` and
+/// `refs` directories is reported as a repository, even in nested mode, and
+/// that it can be listed explicitly.
+#[test]
+fn test_discover_bare_repositories() {
+    let bare = PathBuf::from("root/depth_4/depth_4/depth_4/radio.git");
+    for nested in [false, true] {
+        let locator =
+            MockGitRepositoryLocator::new(Path::new("root/depth_4"), 3).with_nested(nested);
+        assert_eq!(
+            locate_uris(locator),
+            vec![
+                PathBuf::from("root/depth_4/depth_4/depth_4/nested_4"),
+                bare.clone()
+            ]
+        );
+    }
+
+    let locator = MockGitRepositoryLocator::from_list([&bare]);
+    assert_eq!(locate_uris(locator), vec![bare]);
+
+    let mirror = TempGitRepository::new_bare();
+    mirror.commit("refs/heads/main", "Mirror the radio", 1_746_000_000);
+    let repositories = GitRepositoryLocator::new(mirror.path(), 0)
+        .locate()
+        .unwrap();
+    assert_eq!(repositories.len(), 1);
+}
+
+/// Tests that worktrees of the same repository are reported once.
+///
+/// This test verifies that a linked worktree, whose `.git` is a file, is
+/// discovered on its own, and that it is reported as its main worktree when
+/// both are found, whichever is found first.
+#[test]
+fn test_discover_worktrees() {
+    let radio = TempGitRepository::new();
+    radio.commit("refs/heads/main", "Tune the radio", 1_746_000_000);
+    let (_dir, worktree) = radio.add_worktree("feature");
+
+    let repositories = GitRepositoryLocator::new(&worktree, 0).locate().unwrap();
+    assert_eq!(repositories.len(), 1);
+    assert_eq!(repositories[0].uri, worktree);
+
+    for roots in [
+        [worktree.as_path(), radio.path()],
+        [radio.path(), worktree.as_path()],
+    ] {
+        let repositories = GitRepositoryLocator::from_roots(roots, 0).locate().unwrap();
+        let uris: Vec<_> = repositories.iter().map(|repo| repo.uri.clone()).collect();
+        assert_eq!(uris, vec![radio.path().to_path_buf()]);
+    }
+}
+
+/// Tests that symbolic links are only searched when requested.
+///
+/// This test verifies that links below a search root are skipped by default,
+/// that a search root is followed even when it is a link, and that following
+/// links stops at cycles, which would otherwise repeat the search down to the
+/// search depth.
+#[test]
+fn test_discover_symlinks() {
+    let locator = MockGitRepositoryLocator::new(Path::new("root/links"), 64);
+    assert!(locate_uris(locator).is_empty());
+
+    let locator =
+        MockGitRepositoryLocator::new(Path::new("root/links"), 64).with_follow_symlinks(true);
+    assert_eq!(
+        locate_uris(locator),
+        vec![PathBuf::from("root/links/radio")]
+    );
+
+    let locator = MockGitRepositoryLocator::new(Path::new("root/links/radio"), 0);
+    assert_eq!(
+        locate_uris(locator),
+        vec![PathBuf::from("root/links/radio")]
+    );
+
+    let locator = MockGitRepositoryLocator::new(Path::new("root"), 3).with_follow_symlinks(true);
+    let uris = locate_uris(locator);
+    assert_eq!(uris.len(), 3);
+    assert!(uris.contains(&PathBuf::from("root/depth_2/nested_2")));
+    assert!(uris.contains(&PathBuf::from("root/nested_1")));
+}
+
+/// Tests that symbolic link cycles on disk are detected by device and inode.
+#[cfg(unix)]
+#[test]
+fn test_discover_symlink_cycles() {
+    let radio = TempGitRepository::new();
+    radio.commit("refs/heads/main", "Tune the radio", 1_746_000_000);
+    let dir = tempfile::tempdir().unwrap();
+    std::os::unix::fs::symlink(dir.path(), dir.path().join("left")).unwrap();
+    std::os::unix::fs::symlink(dir.path(), dir.path().join("right")).unwrap();
+    std::os::unix::fs::symlink(radio.path(), dir.path().join("radio")).unwrap();
+
+    let repositories = GitRepositoryLocator::new(dir.path(), 64).locate().unwrap();
+    assert!(repositories.is_empty());
+
+    let repositories = GitRepositoryLocator::new(dir.path(), 64)
+        .with_follow_symlinks(true)
+        .locate()
+        .unwrap();
+    let uris: Vec<_> = repositories.iter().map(|repo| repo.uri.clone()).collect();
+    assert_eq!(uris, vec![dir.path().join("radio")]);
+}
+
+/// Tests that submodules are reported with their parent repository.
+///
+/// This test verifies that submodules are enumerated through Git regardless
+/// of the search depth, named after their parent, and reported once when
+/// nested mode finds them too.
+#[test]
+fn test_discover_submodules() {
+    let radio = TempGitRepository::new();
+    radio.commit("refs/heads/main", "Tune the radio", 1_746_000_000);
+    let station = TempGitRepository::new();
+    station.commit("refs/heads/main", "Build the station", 1_746_000_000);
+    station.add_submodule(radio.path(), "libs/radio");
+    let station_name = station
+        .path()
+        .file_name()
+        .unwrap()
+        .to_string_lossy()
+        .into_owned();
+
+    let repositories = GitRepositoryLocator::new(station.path(), 0)
+        .locate()
+        .unwrap();
+    assert_eq!(repositories.len(), 1);
+
+    for nested in [false, true] {
+        let repositories = GitRepositoryLocator::new(station.path(), 2)
+            .with_nested(nested)
+            .with_submodules(true)
+            .locate()
+            .unwrap();
+        let found: Vec<_> = repositories
+            .iter()
+            .map(|repo| (repo.uri.clone(), repo.name.clone(), repo.parent.clone()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (station.path().to_path_buf(), station_name.clone(), None),
+                (
+                    station.path().join("libs/radio"),
+                    format!("{}/libs/radio", station_name),
+                    Some(station_name.clone())
+                ),
+            ]
+        );
+    }
+
+    let filter = DiscoveryFilter::new(Vec::<&str>::new(), ["libs"]).unwrap();
+    let repositories = GitRepositoryLocator::new(station.path(), 0)
+        .with_filter(filter)
+        .with_submodules(true)
+        .locate()
+        .unwrap();
+    assert_eq!(repositories.len(), 1);
+}
+
 /// Simulate handling for a corrupt or otherwise invalid Git repository.
 /// The path exists on disk, but the repository cannot be opened.
 #[test]
@@ -117,3 +396,84 @@ fn test_try_make_repo_error() {
     let repositories = repositories.unwrap();
     assert_eq!(repositories.len(), 0);
 }
+
+/// Tests that repositories found through overlapping roots are reported once,
+/// sorted by path.
+///
+/// This test verifies that the locator deduplicates repositories by canonical
+/// path, so a root nested in another root or spelled with `.` components does
+/// not report its repositories twice.
+#[test]
+fn test_discover_repositories_multiple_roots() {
+    let locator = MockGitRepositoryLocator::from_roots(
+        ["root/depth_2", "root", "root/./nested_1", "root/missing"],
+        2,
+    );
+
+    let repositories = locator.locate().unwrap();
+    let uris: Vec<_> = repositories.iter().map(|repo| repo.get_uri()).collect();
+
+    assert_eq!(
+        uris,
+        vec![
+            Path::new("root/depth_2/nested_2"),
+            Path::new("root/nested_1")
+        ]
+    );
+}
+
+/// Tests that an explicit list of repositories is opened without searching
+/// below the listed paths.
+///
+/// This test verifies that listed repositories are found regardless of depth,
+/// that paths which are not repositories are skipped, and that duplicates are
+/// reported once.
+#[test]
+fn test_locate_repository_list() {
+    let locator = MockGitRepositoryLocator::from_list([
+        "root/depth_3/depth_3/nested_3",
+        "root/nested_1",
+        "root",
+        "root/not_a_repo",
+        "root/missing",
+        "root/nested_1",
+    ]);
+
+    let repositories = locator.locate().unwrap();
+    let uris: Vec<_> = repositories.iter().map(|repo| repo.get_uri()).collect();
+
+    assert_eq!(
+        uris,
+        vec![
+            Path::new("root/depth_3/depth_3/nested_3"),
+            Path::new("root/nested_1")
+        ]
+    );
+}
+
+/// Tests reading a repository list file.
+///
+/// This test verifies that blank lines and comments are skipped, and that
+/// relative paths are resolved against the directory holding the list.
+#[test]
+fn test_read_repository_list() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("repos.txt");
+    std::fs::write(
+        &path,
+        "# Services\nservices/api\n\n  services/web  \n/srv/mirror/tools\n",
+    )
+    .unwrap();
+
+    let paths = read_repository_list(&path).unwrap();
+
+    assert_eq!(
+        paths,
+        vec![
+            dir.path().join("services/api"),
+            dir.path().join("services/web"),
+            PathBuf::from("/srv/mirror/tools"),
+        ]
+    );
+    assert!(read_repository_list(&dir.path().join("missing.txt")).is_err());
+}
@@ -1,7 +1,12 @@
 mod mock_git_repository;
-use chrono::Utc;
+use chrono::{TimeZone, Utc};
 use mock_git_repository::MockGitRepository;
 use std::path::Path;
+use walrust::author::AuthorFilter;
+use walrust::commit::{Commit, CommitAuthor, CommitHash};
+use walrust::filter::CommitFilter;
+use walrust::grep::{Grep, GrepScope};
+use walrust::query::CommitQuery;
 use walrust::repository::Repository;
 use walrust::WalrustError;
 
@@ -32,7 +37,7 @@ mod tests {
     #[test]
     fn test_local_git_repo_valid_path() {
         let path = Path::new("/path/to/repo");
-        let repo = MockRepository::new(&path.to_path_buf());
+        let repo = MockRepository::new(path);
         assert!(repo.is_ok());
 
         let repo = repo.unwrap();
@@ -52,7 +57,7 @@ mod tests {
     #[test]
     fn test_local_git_repo_invalid_path() {
         let path = Path::new("..");
-        let repo = MockRepository::new(&path.to_path_buf());
+        let repo = MockRepository::new(path);
         assert!(repo.is_err());
         if let Err(WalrustError::PathError(_)) = repo {
             // Expected error
@@ -68,16 +73,112 @@ mod tests {
     #[test]
     fn test_get_commits() {
         let path = Path::new("/path/to/repo");
-        let repo = MockRepository::new(&path.to_path_buf());
+        let repo = MockRepository::new(path);
         assert!(repo.is_ok());
 
         let repo = repo.unwrap();
         let since = Utc::now();
         let until = Utc::now();
-        let commits = repo.get_commits(since, until);
+        let commits = repo.get_commits(&CommitQuery::new(since, until));
         assert!(commits.is_ok());
 
         let commits = commits.unwrap();
         assert!(commits.is_empty());
     }
+
+    /// Tests that `get_commits` applies the query's filter to a `MockRepository`.
+    ///
+    /// This test verifies that a combined author, message, merge and hash
+    /// prefix filter selects the expected commits.
+    #[test]
+    fn test_get_commits_with_filter() {
+        let mut repo = MockRepository::new(Path::new("/path/to/repo")).unwrap();
+        let date = Utc
+            .with_ymd_and_hms(2025, 5, 4, 12, 0, 0)
+            .unwrap()
+            .fixed_offset();
+        let bix = CommitAuthor::new("Bix Caleen".to_string(), "bix@ferrix.org".to_string());
+        let brasso = CommitAuthor::new("Brasso".to_string(), "brasso@ferrix.org".to_string());
+        for (title, author, hash, parents) in [
+            ("Fix the radio", &bix, "aaaa", 1),
+            ("Merge the radio fix", &bix, "bbbb", 2),
+            ("Polish the radio", &brasso, "cccc", 1),
+            ("Sweep the yard", &bix, "dddd", 1),
+        ] {
+            let mut commit = Commit::new(
+                title.to_string(),
+                author.clone(),
+                date,
+                author.clone(),
+                date,
+                title.to_string(),
+                CommitHash::new(hash.repeat(10)),
+            );
+            commit.parents = vec!["0000".to_string(); parents];
+            repo.vcs.add_commit(commit);
+        }
+
+        let since = date.to_utc() - chrono::Duration::days(1);
+        let until = date.to_utc() + chrono::Duration::days(1);
+        let filter = CommitFilter::Author(AuthorFilter::parse(["substr:Bix"]).unwrap())
+            .and(CommitFilter::message("radio").unwrap())
+            .and(!CommitFilter::Merge)
+            .or(CommitFilter::hash_prefix("DD").unwrap());
+        let query = CommitQuery::new(since, until).with_filter(filter);
+
+        let commits = repo.get_commits(&query).unwrap();
+        let titles: Vec<_> = commits.iter().map(|c| c.title.as_str()).collect();
+        assert_eq!(titles, vec!["Fix the radio", "Sweep the yard"]);
+    }
+
+    /// Tests that message searches honour their scope, case and inversion.
+    #[test]
+    fn test_get_commits_with_grep() {
+        let mut repo = MockRepository::new(Path::new("/path/to/repo")).unwrap();
+        let date = Utc
+            .with_ymd_and_hms(2025, 5, 4, 12, 0, 0)
+            .unwrap()
+            .fixed_offset();
+        let bix = CommitAuthor::new("Bix Caleen".to_string(), "bix@ferrix.org".to_string());
+        for (title, message, hash) in [
+            ("Fix the radio", "Fix the radio\n\nRefs JIRA-12.", "aaaa"),
+            ("JIRA-34 Sweep the yard", "JIRA-34 Sweep the yard", "bbbb"),
+            ("Polish the radio", "Polish the radio", "cccc"),
+        ] {
+            repo.vcs.add_commit(Commit::new(
+                title.to_string(),
+                bix.clone(),
+                date,
+                bix.clone(),
+                date,
+                message.to_string(),
+                CommitHash::new(hash.repeat(10)),
+            ));
+        }
+
+        let since = date.to_utc() - chrono::Duration::days(1);
+        let until = date.to_utc() + chrono::Duration::days(1);
+        let titles = |grep: CommitFilter| -> Vec<String> {
+            let query = CommitQuery::new(since, until).with_filter(grep);
+            let commits = repo.get_commits(&query).unwrap();
+            commits.into_iter().map(|c| c.title).collect()
+        };
+
+        let message = Grep::new(r"jira-\d+", true, GrepScope::Message).unwrap();
+        let title = Grep::new(r"jira-\d+", true, GrepScope::Title).unwrap();
+        let exact = Grep::new(r"jira-\d+", false, GrepScope::Message).unwrap();
+        assert_eq!(
+            titles(CommitFilter::Message(message.clone())),
+            vec!["Fix the radio", "JIRA-34 Sweep the yard"]
+        );
+        assert_eq!(
+            titles(CommitFilter::Message(title)),
+            vec!["JIRA-34 Sweep the yard"]
+        );
+        assert!(titles(CommitFilter::Message(exact)).is_empty());
+        assert_eq!(
+            titles(!CommitFilter::Message(message)),
+            vec!["Polish the radio"]
+        );
+    }
 }
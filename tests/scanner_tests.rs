@@ -0,0 +1,120 @@
+mod mock_git_repository;
+use chrono::{DateTime, Duration, FixedOffset, Utc};
+use mock_git_repository::MockGitRepository;
+use std::path::{Path, PathBuf};
+use walrust::commit::{Commit, CommitHash};
+use walrust::query::CommitQuery;
+use walrust::repository::{LocalGitRepository, Repository};
+use walrust::scanner::scan_repositories;
+
+/// A type alias for a `Repository` using the mock Git repository.
+type MockRepository = Repository<MockGitRepository>;
+
+/// A fixed point in time used as the base for commit dates in these tests.
+const BASE_TIME: i64 = 1_746_000_000;
+
+/// Creates mock repositories with a different number of commits each.
+///
+/// The repositories are created out of path order so the tests can verify
+/// that results are sorted.
+///
+/// # Arguments
+/// * `count` - The number of repositories to create.
+fn create_mock_repositories(count: usize) -> Vec<MockRepository> {
+    let base = DateTime::from_timestamp(BASE_TIME, 0).unwrap();
+    let offset = FixedOffset::east_opt(0).unwrap();
+    (0..count)
+        .rev()
+        .map(|i| {
+            let mut repo =
+                MockRepository::new(Path::new(&format!("/repos/repo_{:03}", i))).unwrap();
+            for j in 0..i {
+                let date = (base + Duration::minutes(j as i64)).with_timezone(&offset);
+                repo.vcs.add_commit(Commit {
+                    title: format!("commit {} in repo {}", j, i),
+                    author_date: date,
+                    committer_date: date,
+                    hash: CommitHash::new(format!("{:07x}{:033x}", i, j)),
+                    ..Commit::default()
+                });
+            }
+            repo
+        })
+        .collect()
+}
+
+/// Returns a query that covers every commit created by `create_mock_repositories`.
+fn query_all() -> CommitQuery {
+    let since = DateTime::from_timestamp(BASE_TIME, 0).unwrap();
+    CommitQuery::new(since, Utc::now())
+}
+
+/// Flattens scan results into repository paths and commit hashes for comparison.
+fn summarize(
+    results: Vec<(MockRepository, walrust::Result<Vec<Commit>>)>,
+) -> Vec<(PathBuf, Vec<String>)> {
+    results
+        .into_iter()
+        .map(|(repo, commits)| {
+            let hashes = commits
+                .unwrap()
+                .into_iter()
+                .map(|commit| commit.hash.full)
+                .collect();
+            (repo.uri, hashes)
+        })
+        .collect()
+}
+
+/// Tests that results are sorted by repository path.
+#[test]
+fn test_scan_repositories_sorted_by_path() {
+    let results = summarize(scan_repositories(
+        create_mock_repositories(5),
+        &query_all(),
+        1,
+    ));
+
+    let paths: Vec<_> = results.iter().map(|(path, _)| path.clone()).collect();
+    let mut sorted = paths.clone();
+    sorted.sort();
+    assert_eq!(paths.len(), 5);
+    assert_eq!(paths, sorted);
+    assert_eq!(results[3].1.len(), 3);
+}
+
+/// Tests that parallel scanning returns the same results as serial scanning.
+///
+/// This test verifies that the result order and contents do not depend on the
+/// number of worker threads, including more workers than repositories.
+#[test]
+fn test_scan_repositories_parallel_matches_serial() {
+    let serial = summarize(scan_repositories(
+        create_mock_repositories(32),
+        &query_all(),
+        1,
+    ));
+
+    for jobs in [0, 2, 4, 8, 64] {
+        let parallel = summarize(scan_repositories(
+            create_mock_repositories(32),
+            &query_all(),
+            jobs,
+        ));
+        assert_eq!(parallel, serial, "jobs = {}", jobs);
+    }
+}
+
+/// Tests that scanning no repositories returns no results.
+#[test]
+fn test_scan_repositories_empty() {
+    let results = scan_repositories(Vec::<MockRepository>::new(), &query_all(), 4);
+    assert!(results.is_empty());
+}
+
+/// Tests that local repositories can be moved to worker threads.
+#[test]
+fn test_local_repository_is_send() {
+    fn assert_send<T: Send>() {}
+    assert_send::<Repository<LocalGitRepository>>();
+}
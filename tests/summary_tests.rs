@@ -0,0 +1,272 @@
+mod mock_git_repository;
+use chrono::{DateTime, FixedOffset, TimeZone};
+use mock_git_repository::MockGitRepository;
+use std::path::Path;
+use walrust::commit::{
+    Commit, CommitAuthor, CommitHash, DateField, DiffStats, DisplayTimezone, FileStats,
+};
+use walrust::report::ReportOptions;
+use walrust::repository::Repository;
+use walrust::summary::{
+    group_commits, split_non_conforming, summarize, DiffTotals, GroupBy, Summary, NON_CONFORMING,
+};
+
+/// A type alias for a `Repository` using the mock Git repository.
+type MockRepository = Repository<MockGitRepository>;
+
+/// Creates a date on the given day of May 2025 in the given timezone.
+fn date(offset_hours: i32, day: u32, hour: u32) -> DateTime<FixedOffset> {
+    FixedOffset::east_opt(offset_hours * 3600)
+        .unwrap()
+        .with_ymd_and_hms(2025, 5, day, hour, 0, 0)
+        .unwrap()
+}
+
+/// Creates a commit with the same author and committer.
+fn commit(title: &str, author: &CommitAuthor, date: DateTime<FixedOffset>) -> Commit {
+    Commit::new(
+        title.to_string(),
+        author.clone(),
+        date,
+        author.clone(),
+        date,
+        title.to_string(),
+        CommitHash::new(format!("{:0>40}", title.len())),
+    )
+}
+
+/// Creates two repositories with commits by two authors over two weeks, and
+/// one repository without commits.
+fn create_results() -> Vec<(MockRepository, Vec<Commit>)> {
+    let luthen = CommitAuthor::new("Luthen Rael".to_string(), "luthen@axis.org".to_string());
+    let kleya = CommitAuthor::new("Kleya Marki".to_string(), "kleya@axis.org".to_string());
+    vec![
+        (
+            MockRepository::new(Path::new("/repos/coruscant")).unwrap(),
+            vec![
+                commit("Sell antiques", &luthen, date(0, 12, 9)),
+                commit("Hide relics", &kleya, date(0, 5, 10)),
+            ],
+        ),
+        (
+            MockRepository::new(Path::new("/repos/ferrix")).unwrap(),
+            Vec::new(),
+        ),
+        (
+            MockRepository::new(Path::new("/repos/segra")).unwrap(),
+            vec![
+                commit("Fund the cell", &luthen, date(-7, 4, 20)),
+                commit("Meet Saw", &luthen, date(0, 5, 8)),
+            ],
+        ),
+    ]
+}
+
+/// Returns the key and commit titles of each group.
+fn group_titles(
+    results: &[(MockRepository, Vec<Commit>)],
+    group_by: GroupBy,
+    options: &ReportOptions,
+) -> Vec<(String, Vec<String>)> {
+    group_commits(results, group_by, options)
+        .into_iter()
+        .map(|group| {
+            let titles = group
+                .commits
+                .iter()
+                .map(|(_, commit)| commit.title.clone())
+                .collect();
+            (group.key, titles)
+        })
+        .collect()
+}
+
+/// Tests that repository groups keep repository order and skip empty repositories.
+#[test]
+fn test_group_by_repo() {
+    let results = create_results();
+    let groups = group_titles(&results, GroupBy::Repo, &ReportOptions::default());
+    assert_eq!(
+        groups,
+        vec![
+            (
+                "coruscant".to_string(),
+                vec!["Sell antiques".to_string(), "Hide relics".to_string()]
+            ),
+            (
+                "segra".to_string(),
+                vec!["Fund the cell".to_string(), "Meet Saw".to_string()]
+            ),
+        ]
+    );
+}
+
+/// Tests that author groups span repositories and list the most active author first.
+#[test]
+fn test_group_by_author() {
+    let results = create_results();
+    let groups = group_titles(&results, GroupBy::Author, &ReportOptions::default());
+    assert_eq!(groups.len(), 2);
+    assert_eq!(groups[0].0, "Luthen Rael <luthen@axis.org>");
+    assert_eq!(groups[0].1.len(), 3);
+    assert_eq!(groups[1].0, "Kleya Marki <kleya@axis.org>");
+}
+
+/// Tests that type groups and the type summary count conventional commits by
+/// type, most common first, and keep non-conforming commits in their own group.
+#[test]
+fn test_group_by_type() {
+    let mut results = create_results();
+    for (repo, commit, title) in [
+        (0, 0, "feat(shop): Sell antiques"),
+        (2, 0, "fix: Fund the cell"),
+        (2, 1, "Fix!: Meet Saw"),
+    ] {
+        results[repo].1[commit].title = title.to_string();
+    }
+
+    let groups = group_titles(&results, GroupBy::Type, &ReportOptions::default());
+    assert_eq!(
+        groups,
+        vec![
+            (
+                "fix".to_string(),
+                vec![
+                    "fix: Fund the cell".to_string(),
+                    "Fix!: Meet Saw".to_string()
+                ]
+            ),
+            (
+                "feat".to_string(),
+                vec!["feat(shop): Sell antiques".to_string()]
+            ),
+            (NON_CONFORMING.to_string(), vec!["Hide relics".to_string()]),
+        ]
+    );
+    assert_eq!(
+        summarize(&results).types,
+        vec![
+            ("fix".to_string(), 2),
+            ("feat".to_string(), 1),
+            (NON_CONFORMING.to_string(), 1),
+        ]
+    );
+}
+
+/// Tests that non-conforming commits are moved out of the results after being
+/// counted in the summary.
+#[test]
+fn test_split_non_conforming() {
+    let mut results = create_results();
+    results[0].1[0].title = "feat(shop): Sell antiques".to_string();
+    results[2].1[1].title = "fix: Meet Saw".to_string();
+    let summary = summarize(&results);
+
+    let removed = split_non_conforming(&mut results);
+
+    let removed: Vec<_> = removed
+        .iter()
+        .map(|(repo, commit)| (repo.as_str(), commit.title.as_str()))
+        .collect();
+    assert_eq!(
+        removed,
+        vec![("coruscant", "Hide relics"), ("segra", "Fund the cell")]
+    );
+    assert_eq!(results[0].1.len(), 1);
+    assert_eq!(results[2].1.len(), 1);
+    assert_eq!(summary.commits, 4);
+    assert!(summary.types.contains(&(NON_CONFORMING.to_string(), 2)));
+}
+
+/// Tests that day groups are ordered oldest first using the commit's own timezone.
+#[test]
+fn test_group_by_day() {
+    let results = create_results();
+    let groups = group_titles(&results, GroupBy::Day, &ReportOptions::default());
+    let keys: Vec<_> = groups.iter().map(|(key, _)| key.as_str()).collect();
+    assert_eq!(keys, vec!["2025-05-04", "2025-05-05", "2025-05-12"]);
+    assert_eq!(
+        groups[1].1,
+        vec!["Hide relics".to_string(), "Meet Saw".to_string()]
+    );
+}
+
+/// Tests that day groups follow the display timezone.
+#[test]
+fn test_group_by_day_utc() {
+    let results = create_results();
+    let options = ReportOptions {
+        date_field: DateField::Committer,
+        timezone: DisplayTimezone::Utc,
+    };
+    let groups = group_titles(&results, GroupBy::Day, &options);
+    let keys: Vec<_> = groups.iter().map(|(key, _)| key.as_str()).collect();
+    assert_eq!(keys, vec!["2025-05-05", "2025-05-12"]);
+    assert_eq!(groups[0].1.len(), 3);
+}
+
+/// Tests that week groups use ISO 8601 week numbers.
+#[test]
+fn test_group_by_week() {
+    let results = create_results();
+    let groups = group_titles(&results, GroupBy::Week, &ReportOptions::default());
+    let keys: Vec<_> = groups.iter().map(|(key, _)| key.as_str()).collect();
+    assert_eq!(keys, vec!["2025-W18", "2025-W19", "2025-W20"]);
+}
+
+/// Tests that the summary counts commits per repository and per author.
+#[test]
+fn test_summarize() {
+    let results = create_results();
+    assert_eq!(
+        summarize(&results),
+        Summary {
+            commits: 4,
+            repositories: vec![
+                ("coruscant".to_string(), 2),
+                ("ferrix".to_string(), 0),
+                ("segra".to_string(), 2),
+            ],
+            authors: vec![
+                ("Luthen Rael <luthen@axis.org>".to_string(), 3),
+                ("Kleya Marki <kleya@axis.org>".to_string(), 1),
+            ],
+            types: vec![(NON_CONFORMING.to_string(), 4)],
+            stats: None,
+        }
+    );
+}
+
+/// Tests that the summary sums the diff statistics of the commits that have them.
+#[test]
+fn test_summarize_stats() {
+    let mut results = create_results();
+    let file = |path: &str, insertions, deletions| FileStats {
+        path: path.to_string(),
+        insertions,
+        deletions,
+    };
+    results[0].1[0].stats = Some(DiffStats::new(vec![
+        file("ledger.txt", 10, 2),
+        file("relics.md", 1, 0),
+    ]));
+    results[2].1[1].stats = Some(DiffStats::new(vec![file("ledger.txt", 0, 5)]));
+
+    assert_eq!(
+        summarize(&results).stats,
+        Some(DiffTotals {
+            commits: 2,
+            files_changed: 3,
+            insertions: 11,
+            deletions: 7,
+        })
+    );
+}
+
+/// Tests that empty results produce no groups and an empty summary.
+#[test]
+fn test_empty_results() {
+    let results: Vec<(MockRepository, Vec<Commit>)> = Vec::new();
+    assert!(group_commits(&results, GroupBy::Repo, &ReportOptions::default()).is_empty());
+    assert_eq!(summarize(&results), Summary::default());
+}
@@ -0,0 +1,262 @@
+#![allow(dead_code)]
+
+use git2::build::TreeUpdateBuilder;
+use git2::{FileMode, Oid, Repository, Signature, Time};
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+/// A real Git repository in a temporary directory for testing purposes.
+///
+/// The repository is created with `main` as its HEAD branch and is deleted
+/// when this struct is dropped.
+pub struct TempGitRepository {
+    /// The temporary directory holding the repository.
+    dir: TempDir,
+    /// The underlying git2 repository.
+    pub git: Repository,
+}
+
+impl TempGitRepository {
+    /// Creates a new, empty repository whose HEAD points at `refs/heads/main`.
+    pub fn new() -> Self {
+        let dir = tempfile::tempdir().unwrap();
+        let git = Repository::init(dir.path()).unwrap();
+        git.set_head("refs/heads/main").unwrap();
+        TempGitRepository { dir, git }
+    }
+
+    /// Creates a new, empty bare repository whose HEAD points at
+    /// `refs/heads/main`.
+    pub fn new_bare() -> Self {
+        let dir = tempfile::tempdir().unwrap();
+        let git = Repository::init_bare(dir.path()).unwrap();
+        git.set_head("refs/heads/main").unwrap();
+        TempGitRepository { dir, git }
+    }
+
+    /// Returns the path of the repository working directory, or of the
+    /// repository itself when it is bare.
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+
+    /// Appends a commit to a reference.
+    ///
+    /// The author and committer are the same, both dated `seconds` since the
+    /// Unix epoch in UTC.
+    ///
+    /// # Arguments
+    /// - `refname`: The full name of the reference to update, e.g. `refs/heads/main`.
+    /// - `message`: The commit message.
+    /// - `seconds`: The commit time in seconds since the Unix epoch.
+    ///
+    /// # Returns
+    /// The id of the new commit.
+    pub fn commit(&self, refname: &str, message: &str, seconds: i64) -> Oid {
+        let signature =
+            Signature::new("Test User", "test@example.com", &Time::new(seconds, 0)).unwrap();
+        self.commit_as(refname, message, &signature, &signature)
+    }
+
+    /// Appends a commit to a reference with explicit signatures.
+    ///
+    /// # Arguments
+    /// - `refname`: The full name of the reference to update, e.g. `refs/heads/main`.
+    /// - `message`: The commit message.
+    /// - `author`: The author signature, including the author date.
+    /// - `committer`: The committer signature, including the committer date.
+    ///
+    /// # Returns
+    /// The id of the new commit.
+    pub fn commit_as(
+        &self,
+        refname: &str,
+        message: &str,
+        author: &Signature,
+        committer: &Signature,
+    ) -> Oid {
+        let tree_id = match self.tip(refname) {
+            Some(parent) => parent.tree_id(),
+            None => self.git.treebuilder(None).unwrap().write().unwrap(),
+        };
+        self.commit_tree(refname, message, author, committer, tree_id, &[])
+    }
+
+    /// Appends a commit to a reference that writes the given files on top of
+    /// the parent's tree.
+    ///
+    /// # Arguments
+    /// - `refname`: The full name of the reference to update, e.g. `refs/heads/main`.
+    /// - `message`: The commit message.
+    /// - `seconds`: The commit time in seconds since the Unix epoch.
+    /// - `files`: The paths and contents of the files to write, e.g. `("src/main.rs", "")`.
+    ///
+    /// # Returns
+    /// The id of the new commit.
+    pub fn commit_files(
+        &self,
+        refname: &str,
+        message: &str,
+        seconds: i64,
+        files: &[(&str, &str)],
+    ) -> Oid {
+        let signature =
+            Signature::new("Test User", "test@example.com", &Time::new(seconds, 0)).unwrap();
+        let mut update = TreeUpdateBuilder::new();
+        for (path, contents) in files {
+            let blob = self.git.blob(contents.as_bytes()).unwrap();
+            update.upsert(*path, blob, FileMode::Blob);
+        }
+        let base = match self.tip(refname) {
+            Some(parent) => parent.tree().unwrap(),
+            None => {
+                let empty = self.git.treebuilder(None).unwrap().write().unwrap();
+                self.git.find_tree(empty).unwrap()
+            }
+        };
+        let tree_id = update.create_updated(&self.git, &base).unwrap();
+        self.commit_tree(refname, message, &signature, &signature, tree_id, &[])
+    }
+
+    /// Appends a merge commit to a reference, with `other` as its second parent.
+    ///
+    /// The merge keeps the tree of the reference's current commit.
+    ///
+    /// # Arguments
+    /// - `refname`: The full name of the reference to update, e.g. `refs/heads/main`.
+    /// - `message`: The commit message.
+    /// - `seconds`: The commit time in seconds since the Unix epoch.
+    /// - `other`: The commit being merged.
+    ///
+    /// # Returns
+    /// The id of the new commit.
+    pub fn merge(&self, refname: &str, message: &str, seconds: i64, other: Oid) -> Oid {
+        let signature =
+            Signature::new("Test User", "test@example.com", &Time::new(seconds, 0)).unwrap();
+        let tree_id = self.tip(refname).unwrap().tree_id();
+        self.commit_tree(refname, message, &signature, &signature, tree_id, &[other])
+    }
+
+    /// Appends a merge commit to a reference, with `other` as its second parent.
+    ///
+    /// Unlike [`TempGitRepository::merge`], the merge's tree combines the
+    /// changes of both parents, as `git merge` would. The changes must not
+    /// conflict.
+    ///
+    /// # Arguments
+    /// - `refname`: The full name of the reference to update, e.g. `refs/heads/main`.
+    /// - `message`: The commit message.
+    /// - `seconds`: The commit time in seconds since the Unix epoch.
+    /// - `other`: The commit being merged.
+    ///
+    /// # Returns
+    /// The id of the new commit.
+    pub fn merge_changes(&self, refname: &str, message: &str, seconds: i64, other: Oid) -> Oid {
+        let signature =
+            Signature::new("Test User", "test@example.com", &Time::new(seconds, 0)).unwrap();
+        let ours = self.tip(refname).unwrap();
+        let theirs = self.git.find_commit(other).unwrap();
+        let mut index = self.git.merge_commits(&ours, &theirs, None).unwrap();
+        assert!(!index.has_conflicts());
+        let tree_id = index.write_tree_to(&self.git).unwrap();
+        self.commit_tree(refname, message, &signature, &signature, tree_id, &[other])
+    }
+
+    /// Returns the commit a reference points at, if the reference exists.
+    fn tip(&self, refname: &str) -> Option<git2::Commit<'_>> {
+        self.git
+            .find_reference(refname)
+            .ok()
+            .and_then(|r| r.peel_to_commit().ok())
+    }
+
+    /// Creates a commit on a reference whose first parent is the reference's
+    /// current commit, if any, followed by `extra_parents`.
+    fn commit_tree(
+        &self,
+        refname: &str,
+        message: &str,
+        author: &Signature,
+        committer: &Signature,
+        tree_id: Oid,
+        extra_parents: &[Oid],
+    ) -> Oid {
+        let tree = self.git.find_tree(tree_id).unwrap();
+        let mut parents: Vec<_> = self.tip(refname).into_iter().collect();
+        for oid in extra_parents {
+            parents.push(self.git.find_commit(*oid).unwrap());
+        }
+        let parents: Vec<_> = parents.iter().collect();
+        self.git
+            .commit(Some(refname), author, committer, message, &tree, &parents)
+            .unwrap()
+    }
+
+    /// Adds another repository as a submodule, checks it out and commits it
+    /// to `main`.
+    ///
+    /// # Arguments
+    /// - `url`: The repository to add, which must have commits.
+    /// - `path`: The path of the submodule in the working directory, e.g. `libs/radio`.
+    pub fn add_submodule(&self, url: &Path, path: &str) {
+        let mut submodule = self
+            .git
+            .submodule(url.to_str().unwrap(), Path::new(path), true)
+            .unwrap();
+        submodule.clone(None).unwrap();
+        submodule.add_finalize().unwrap();
+
+        let signature = Signature::now("Test User", "test@example.com").unwrap();
+        let tree_id = self.git.index().unwrap().write_tree().unwrap();
+        self.commit_tree(
+            "refs/heads/main",
+            "Add submodule",
+            &signature,
+            &signature,
+            tree_id,
+            &[],
+        );
+    }
+
+    /// Adds a linked worktree, checking out a new branch named after it at the
+    /// tip of `main`.
+    ///
+    /// # Arguments
+    /// - `name`: The name of the worktree and its branch.
+    ///
+    /// # Returns
+    /// The temporary directory holding the worktree, which must be kept alive
+    /// while it is used, and the path of the worktree inside it.
+    pub fn add_worktree(&self, name: &str) -> (TempDir, PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(name);
+        self.git.worktree(name, &path, None).unwrap();
+        (dir, path)
+    }
+
+    /// Creates a branch pointing at the given commit.
+    ///
+    /// # Arguments
+    /// - `name`: The short branch name, e.g. `feature/x`.
+    /// - `oid`: The commit the branch should point at.
+    pub fn branch(&self, name: &str, oid: Oid) {
+        let commit = self.git.find_commit(oid).unwrap();
+        self.git.branch(name, &commit, false).unwrap();
+    }
+
+    /// Creates a lightweight tag pointing at the given commit.
+    ///
+    /// # Arguments
+    /// - `name`: The tag name, e.g. `v1.0.0`.
+    /// - `oid`: The commit the tag should point at.
+    pub fn tag(&self, name: &str, oid: Oid) {
+        let commit = self.git.find_object(oid, None).unwrap();
+        self.git.tag_lightweight(name, &commit, false).unwrap();
+    }
+}
+
+impl Default for TempGitRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}